@@ -0,0 +1,275 @@
+//! Versioned on-disk schema for `BitcoinAnchorTracker`
+//!
+//! JSON trackers are written inside a versioned envelope:
+//!
+//! ```text
+//! { "version": 4, "tracker": { "seals": ..., "witnesses": ..., ... } }
+//! ```
+//!
+//! Files written before the envelope existed hold a bare tracker object; their
+//! layout version is inferred from the maps present. On load, every migration
+//! from the file's version up to `TRACKER_SCHEMA_VERSION` is applied in
+//! memory. The file itself is rewritten in the current layout by the next
+//! commit (the old file is kept as `<path>.bak`).
+//!
+//! ## Versions
+//!
+//! - **0**: unversioned, before the `anchors` map
+//! - **1**: unversioned, with `anchors`; later maps optional
+//! - **2**: versioned envelope, every map present
+//! - **3**: outpoint indexes (`outpoint_seals`, `outpoint_spenders`)
+//! - **4**: archival timestamps (`archived_at`) for pruning
+//!
+//! Append-only logs (`LogStore`) carry their own format version in the file
+//! header (`LOG_VERSION`); `check_file()` reports it as well.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::anchor_store::{StorageBackend, LOG_MAGIC, LOG_VERSION};
+use crate::bitcoin_anchor::BitcoinAnchorError;
+use crate::encryption;
+
+/// Current JSON tracker schema version
+pub const TRACKER_SCHEMA_VERSION: u32 = 4;
+
+/// Single upgrade step of the JSON tracker layout
+pub struct Migration {
+    /// Version this step upgrades from (to `from + 1`)
+    pub from: u32,
+
+    /// What the step changes
+    pub description: &'static str,
+
+    apply: fn(&mut Map<String, Value>),
+}
+
+/// Registered migrations, in order
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 0,
+        description: "Add empty anchors map",
+        apply: |tracker| add_missing(tracker, &[("anchors", Value::Object(Map::new()))]),
+    },
+    Migration {
+        from: 1,
+        description: "Add anchor_methods, pending_witnesses, witness_blocks and reorged_ops",
+        apply: |tracker| {
+            add_missing(
+                tracker,
+                &[
+                    ("anchor_methods", Value::Object(Map::new())),
+                    ("pending_witnesses", Value::Object(Map::new())),
+                    ("witness_blocks", Value::Object(Map::new())),
+                    ("reorged_ops", Value::Array(Vec::new())),
+                ],
+            )
+        },
+    },
+    Migration {
+        from: 2,
+        description: "Add outpoint indexes (rebuilt from seals and witnesses on load)",
+        apply: |tracker| {
+            add_missing(
+                tracker,
+                &[
+                    ("outpoint_seals", Value::Array(Vec::new())),
+                    ("outpoint_spenders", Value::Array(Vec::new())),
+                ],
+            )
+        },
+    },
+    Migration {
+        from: 3,
+        description: "Add archived_at map (existing archived witnesses start aging on first prune)",
+        apply: |tracker| add_missing(tracker, &[("archived_at", Value::Object(Map::new()))]),
+    },
+];
+
+fn add_missing(tracker: &mut Map<String, Value>, fields: &[(&str, Value)]) {
+    for (name, empty) in fields {
+        tracker
+            .entry(name.to_string())
+            .or_insert_with(|| empty.clone());
+    }
+}
+
+/// Migrations needed to bring `version` up to `TRACKER_SCHEMA_VERSION`
+pub fn pending_migrations(version: u32) -> impl Iterator<Item = &'static Migration> {
+    MIGRATIONS.iter().filter(move |m| m.from >= version)
+}
+
+/// Result of `check_file()`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaReport {
+    /// File checked
+    pub path: PathBuf,
+
+    /// Backend selected by the path
+    pub backend: StorageBackend,
+
+    /// Version found in the file
+    pub version: u32,
+
+    /// Newest version this build writes for the backend
+    pub current: u32,
+
+    /// Descriptions of the migrations a load would apply, in order
+    pub migrations: Vec<&'static str>,
+}
+
+impl SchemaReport {
+    /// Whether loading the file would migrate it
+    pub fn needs_migration(&self) -> bool {
+        !self.migrations.is_empty()
+    }
+}
+
+impl fmt::Display for SchemaReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} ({:?}): version {} of {}",
+            self.path, self.backend, self.version, self.current
+        )?;
+        if self.needs_migration() {
+            write!(f, ", migrations: {}", self.migrations.join("; "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Report the schema version of a stored tracker without changing it
+///
+/// # Errors
+/// - `PersistenceError` if the file can't be read (or for `:memory:`)
+/// - `DeserializationError` if it isn't a tracker file
+/// - `UnsupportedVersion` if it was written by a newer version
+/// - `Encryption` if the file is encrypted (its version is sealed with it)
+pub fn check_file<P: AsRef<Path>>(path: P) -> Result<SchemaReport, BitcoinAnchorError> {
+    let path = path.as_ref();
+    let backend = StorageBackend::for_path(path);
+    let io_err = |e: std::io::Error| BitcoinAnchorError::PersistenceError(e.to_string());
+
+    let (version, current, migrations) = match backend {
+        StorageBackend::Memory => {
+            return Err(BitcoinAnchorError::PersistenceError(
+                "In-memory store has no file to check".to_string(),
+            ))
+        }
+        StorageBackend::Log => {
+            let mut header = [0u8; 10];
+            std::fs::File::open(path)
+                .and_then(|mut file| file.read_exact(&mut header))
+                .map_err(io_err)?;
+            if header[..8] != LOG_MAGIC {
+                return Err(BitcoinAnchorError::DeserializationError(
+                    "Missing anchor log magic number".to_string(),
+                ));
+            }
+            let version = u16::from_le_bytes([header[8], header[9]]) as u32;
+            check_supported("Anchor log", version, LOG_VERSION as u32)?;
+            (version, LOG_VERSION as u32, Vec::new())
+        }
+        StorageBackend::Json => {
+            let json = std::fs::read(path).map_err(io_err)?;
+            if encryption::is_encrypted(&json) {
+                return Err(BitcoinAnchorError::Encryption(format!(
+                    "{:?} is encrypted; load it with its key to check the schema",
+                    path
+                )));
+            }
+            let value = serde_json::from_slice(&json)
+                .map_err(|e| BitcoinAnchorError::DeserializationError(e.to_string()))?;
+            let (version, _) = split_envelope(value)?;
+            let migrations = pending_migrations(version).map(|m| m.description).collect();
+            (version, TRACKER_SCHEMA_VERSION, migrations)
+        }
+    };
+
+    Ok(SchemaReport {
+        path: path.to_path_buf(),
+        backend,
+        version,
+        current,
+        migrations,
+    })
+}
+
+/// Upgrade a parsed JSON tracker file to the current layout
+///
+/// # Returns
+/// The bare tracker object and the version it was stored with
+pub(crate) fn upgrade(value: Value) -> Result<(Value, u32), BitcoinAnchorError> {
+    let (version, mut tracker) = split_envelope(value)?;
+    for migration in pending_migrations(version) {
+        log::debug!(
+            "Tracker schema v{} → v{}: {}",
+            migration.from,
+            migration.from + 1,
+            migration.description
+        );
+        (migration.apply)(&mut tracker);
+    }
+    Ok((Value::Object(tracker), version))
+}
+
+/// Tracker wrapped with the current schema version, for writing
+#[derive(Serialize)]
+pub(crate) struct TrackerEnvelope<'a, T> {
+    version: u32,
+    tracker: &'a T,
+}
+
+impl<'a, T: Serialize> TrackerEnvelope<'a, T> {
+    pub fn new(tracker: &'a T) -> Self {
+        Self {
+            version: TRACKER_SCHEMA_VERSION,
+            tracker,
+        }
+    }
+}
+
+/// Version and bare tracker object of a JSON tracker file
+fn split_envelope(value: Value) -> Result<(u32, Map<String, Value>), BitcoinAnchorError> {
+    let not_tracker = || {
+        BitcoinAnchorError::DeserializationError("Not a tracker file: expected object".to_string())
+    };
+    let Value::Object(mut object) = value else {
+        return Err(not_tracker());
+    };
+
+    match (object.remove("version"), object.remove("tracker")) {
+        (Some(version), Some(Value::Object(tracker))) => {
+            let version = version
+                .as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| {
+                    BitcoinAnchorError::DeserializationError(format!(
+                        "Invalid tracker schema version {}",
+                        version
+                    ))
+                })?;
+            check_supported("Tracker schema", version, TRACKER_SCHEMA_VERSION)?;
+            Ok((version, tracker))
+        }
+        (None, None) if object.contains_key("seals") => {
+            let version = if object.contains_key("anchors") { 1 } else { 0 };
+            Ok((version, object))
+        }
+        _ => Err(not_tracker()),
+    }
+}
+
+fn check_supported(what: &str, version: u32, current: u32) -> Result<(), BitcoinAnchorError> {
+    if version > current {
+        return Err(BitcoinAnchorError::UnsupportedVersion(format!(
+            "{} v{} is newer than supported v{}",
+            what, version, current
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,833 @@
+//! Storage backends for `BitcoinAnchorTracker`
+//!
+//! The tracker persists through an `AnchorStore`, chosen from the
+//! persistence path (see `StorageBackend::for_path`) or set explicitly with
+//! `BitcoinAnchorTracker::with_store()`:
+//!
+//! - **JSON** (`JsonStore`, default): the whole tracker as pretty-printed
+//!   JSON, rewritten atomically on every commit. Human-readable, right-sized
+//!   for small wallets.
+//! - **Append-only log** (`LogStore`, `*.log` paths): strict-encoded records,
+//!   one per seal set / witness / anchor / relation. Each commit appends only
+//!   the records that changed, followed by a commit marker; the log is
+//!   compacted into a snapshot once it grows past twice the live records.
+//! - **In-memory** (`MemoryStore`, `:memory:`): keeps the encoded records in
+//!   memory. For tests.
+//!
+//! Both file backends write crash-safely and hold an advisory lock on
+//! `<path>.lock` while in use. JSON stores can be encrypted at rest (see
+//! `encryption` and `open_store_with_key()`).
+//!
+//! # Log Format
+//!
+//! `LOG_MAGIC` (8 bytes) || version (u16 LE) || frames. A frame is
+//! payload length (u32 LE) || strict-encoded `LogEntry` || first 4 bytes of
+//! the payload's SHA-256. Entries take effect at the next commit marker, so
+//! a torn tail after a crash is dropped on open.
+
+use amplify::confinement::{Confined, MediumBlob, SmallBlob};
+use rgb::RgbSeal;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use strict_encoding::{
+    DecodeError, StreamWriter, StrictDecode, StrictDeserialize, StrictDumb, StrictEncode,
+    StrictReader, StrictSerialize, StrictType, StrictWriter, TypedRead,
+};
+
+use crate::bitcoin_anchor::{BitcoinAnchorError, BitcoinAnchorTracker, OutpointSeal};
+use crate::encryption::{self, EncryptionKey};
+use crate::LIB_NAME_F1R3FLY_RGB;
+
+/// Magic bytes at the start of an append-only log
+pub const LOG_MAGIC: [u8; 8] = *b"F1RGBLOG";
+
+/// Current log format version
+pub const LOG_VERSION: u16 = 1;
+
+/// Minimum number of log records before compaction is considered
+pub const COMPACT_MIN_RECORDS: usize = 10_000;
+
+/// Largest encoded record value
+pub(crate) const MAX_RECORD_SIZE: usize = 0xFF_FFFF;
+
+const LOG_HEADER_LEN: usize = 10;
+const CHECKSUM_LEN: usize = 4;
+
+/// Seal types whose trackers (including outpoint indexes) can be stored by
+/// every backend
+pub trait StoredSeal:
+    OutpointSeal
+    + RgbSeal<
+        Definition: Clone + StrictEncode + StrictDecode + Serialize + DeserializeOwned,
+        WitnessId: StrictEncode + StrictDecode + Serialize + DeserializeOwned,
+        Published: Clone + StrictEncode + StrictDecode + Serialize + DeserializeOwned,
+        Client: Clone + StrictEncode + StrictDecode + Serialize + DeserializeOwned,
+    > + 'static
+{
+}
+
+impl<Seal> StoredSeal for Seal where
+    Seal: OutpointSeal
+        + RgbSeal<
+            Definition: Clone + StrictEncode + StrictDecode + Serialize + DeserializeOwned,
+            WitnessId: StrictEncode + StrictDecode + Serialize + DeserializeOwned,
+            Published: Clone + StrictEncode + StrictDecode + Serialize + DeserializeOwned,
+            Client: Clone + StrictEncode + StrictDecode + Serialize + DeserializeOwned,
+        > + 'static
+{
+}
+
+/// Persistence backend of a `BitcoinAnchorTracker`
+pub trait AnchorStore<Seal: RgbSeal>: Send + Sync {
+    /// Location of the stored data, if on disk
+    fn path(&self) -> Option<&Path>;
+
+    /// Load the stored tracker
+    ///
+    /// # Returns
+    /// `None` if nothing has been stored yet
+    fn load(&mut self) -> Result<Option<BitcoinAnchorTracker<Seal>>, BitcoinAnchorError>;
+
+    /// Persist the current tracker state
+    fn commit(&mut self, tracker: &BitcoinAnchorTracker<Seal>) -> Result<(), BitcoinAnchorError>;
+
+    /// Path this store holds the advisory lock on, if any
+    fn locked_path(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Re-encrypt the stored data under `key` (`None` for plaintext) and use
+    /// it for later commits
+    ///
+    /// # Errors
+    /// `InvalidConfiguration` if the backend doesn't support encryption
+    fn rekey(&mut self, _key: Option<EncryptionKey>) -> Result<(), BitcoinAnchorError> {
+        Err(BitcoinAnchorError::InvalidConfiguration(
+            "Storage backend does not support encryption".to_string(),
+        ))
+    }
+}
+
+/// Built-in storage backends
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageBackend {
+    /// Whole tracker as JSON (`JsonStore`)
+    Json,
+
+    /// Append-only strict-encoded log (`LogStore`)
+    Log,
+
+    /// In-memory records (`MemoryStore`)
+    Memory,
+}
+
+impl StorageBackend {
+    /// Persistence path selecting the in-memory backend
+    pub const MEMORY_PATH: &'static str = ":memory:";
+
+    /// Pick the backend for a persistence path
+    ///
+    /// `:memory:` selects `Memory`, a `.log` extension selects `Log`, and
+    /// anything else `Json`.
+    pub fn for_path(path: &Path) -> Self {
+        if path.as_os_str() == Self::MEMORY_PATH {
+            Self::Memory
+        } else if path.extension().is_some_and(|ext| ext == "log") {
+            Self::Log
+        } else {
+            Self::Json
+        }
+    }
+
+    /// Create a store of this backend at `path`
+    pub fn open<Seal: StoredSeal>(self, path: PathBuf) -> Box<dyn AnchorStore<Seal>> {
+        match self {
+            Self::Json => Box::new(JsonStore::new(path)),
+            Self::Log => Box::new(LogStore::new(path)),
+            Self::Memory => Box::new(MemoryStore::new()),
+        }
+    }
+}
+
+/// Create the store selected by `path` (see `StorageBackend::for_path`)
+pub fn open_store<Seal: StoredSeal>(path: PathBuf) -> Box<dyn AnchorStore<Seal>> {
+    StorageBackend::for_path(&path).open(path)
+}
+
+/// Create the store selected by `path`, encrypted with `key` if set
+///
+/// # Errors
+/// `InvalidConfiguration` if `key` is set for an append-only log, which
+/// can't be encrypted. In-memory stores accept and ignore the key.
+pub fn open_store_with_key<Seal: StoredSeal>(
+    path: PathBuf,
+    key: Option<EncryptionKey>,
+) -> Result<Box<dyn AnchorStore<Seal>>, BitcoinAnchorError> {
+    let Some(key) = key else {
+        return Ok(open_store(path));
+    };
+    match StorageBackend::for_path(&path) {
+        StorageBackend::Json => Ok(Box::new(JsonStore::encrypted(path, key))),
+        StorageBackend::Memory => Ok(Box::new(MemoryStore::new())),
+        StorageBackend::Log => Err(BitcoinAnchorError::InvalidConfiguration(format!(
+            "{:?}: append-only logs can't be encrypted; use a JSON path",
+            path
+        ))),
+    }
+}
+
+// ============================================================================
+// Records
+// ============================================================================
+
+/// Kind of a tracker record
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub(crate) enum RecordKind {
+    /// Opid → seal definitions
+    Seals = 1,
+    /// WitnessId → published, client, status
+    Witness = 2,
+    /// Opid → witness IDs
+    OpWitnesses = 3,
+    /// Opid → anchor
+    Anchor = 4,
+    /// Opid → anchor method
+    AnchorMethod = 5,
+    /// Opid → witness builder (JSON)
+    PendingWitness = 6,
+    /// WitnessId → block hash
+    WitnessBlock = 7,
+    /// Opid → (empty)
+    ReorgedOp = 8,
+    /// Outpoint → seal cells
+    OutpointSeals = 9,
+    /// Outpoint → spending witness IDs
+    OutpointSpenders = 10,
+    /// WitnessId → archival time (Unix seconds)
+    ArchivedAt = 11,
+}
+
+impl TryFrom<u8> for RecordKind {
+    type Error = BitcoinAnchorError;
+
+    fn try_from(tag: u8) -> Result<Self, Self::Error> {
+        Ok(match tag {
+            1 => Self::Seals,
+            2 => Self::Witness,
+            3 => Self::OpWitnesses,
+            4 => Self::Anchor,
+            5 => Self::AnchorMethod,
+            6 => Self::PendingWitness,
+            7 => Self::WitnessBlock,
+            8 => Self::ReorgedOp,
+            9 => Self::OutpointSeals,
+            10 => Self::OutpointSpenders,
+            11 => Self::ArchivedAt,
+            tag => {
+                return Err(BitcoinAnchorError::DeserializationError(format!(
+                    "Unknown record kind {}",
+                    tag
+                )))
+            }
+        })
+    }
+}
+
+/// Single keyed piece of tracker state, with strict-encoded key and value
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct StoreRecord {
+    pub kind: RecordKind,
+    pub key: Vec<u8>,
+    pub value: Vec<u8>,
+}
+
+type RecordKey = (RecordKind, Vec<u8>);
+
+type MemWriter = StrictWriter<StreamWriter<Vec<u8>>>;
+
+/// Strict-encode values written by `f` into one buffer
+pub(crate) fn encode_with(
+    f: impl FnOnce(MemWriter) -> std::io::Result<MemWriter>,
+) -> Result<Vec<u8>, BitcoinAnchorError> {
+    let writer = f(StrictWriter::in_memory::<MAX_RECORD_SIZE>())
+        .map_err(|e| BitcoinAnchorError::SerializationError(e.to_string()))?;
+    Ok(writer.unbox().unconfine())
+}
+
+/// Strict-encode a single value
+pub(crate) fn encode<T: StrictEncode>(value: &T) -> Result<Vec<u8>, BitcoinAnchorError> {
+    encode_with(|writer| value.strict_encode(writer))
+}
+
+/// Strict reader over an encoded record
+pub(crate) fn reader(data: &[u8]) -> impl TypedRead {
+    StrictReader::in_memory::<MAX_RECORD_SIZE>(data.to_vec())
+}
+
+/// Map a strict decoding error
+pub(crate) fn decode_err(e: DecodeError) -> BitcoinAnchorError {
+    BitcoinAnchorError::DeserializationError(e.to_string())
+}
+
+/// Strict-decode a single value
+pub(crate) fn decode<T: StrictDecode>(data: &[u8]) -> Result<T, BitcoinAnchorError> {
+    T::strict_decode(&mut reader(data)).map_err(decode_err)
+}
+
+// ============================================================================
+// JSON Backend
+// ============================================================================
+
+/// Whole tracker as a JSON file
+///
+/// Writes are atomic (temp file, fsync, rename) and keep the previous file as
+/// `<path>.bak`. The advisory lock is taken on the first commit and held
+/// until the store is dropped. With a key, the file and its backup are
+/// encrypted.
+pub struct JsonStore {
+    path: PathBuf,
+    key: Option<EncryptionKey>,
+    lock: Option<PersistenceLock>,
+}
+
+impl JsonStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            key: None,
+            lock: None,
+        }
+    }
+
+    /// Store encrypted with `key`
+    pub fn encrypted(path: PathBuf, key: EncryptionKey) -> Self {
+        Self {
+            key: Some(key),
+            ..Self::new(path)
+        }
+    }
+
+    fn lock(&mut self) -> Result<(), BitcoinAnchorError> {
+        if self.lock.is_none() {
+            self.lock = Some(PersistenceLock::acquire(&self.path)?);
+        }
+        Ok(())
+    }
+}
+
+impl<Seal: StoredSeal> AnchorStore<Seal> for JsonStore {
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn load(&mut self) -> Result<Option<BitcoinAnchorTracker<Seal>>, BitcoinAnchorError> {
+        if !self.path.exists() && !sidecar_path(&self.path, "bak").exists() {
+            return Ok(None);
+        }
+        BitcoinAnchorTracker::read_json_with_backup(&self.path, self.key.as_ref()).map(Some)
+    }
+
+    fn commit(&mut self, tracker: &BitcoinAnchorTracker<Seal>) -> Result<(), BitcoinAnchorError> {
+        self.lock()?;
+        let json = tracker.to_json()?;
+        encryption::write_file(&self.path, json.as_bytes(), self.key.as_ref())?;
+        Ok(())
+    }
+
+    fn locked_path(&self) -> Option<&Path> {
+        self.lock.as_ref().map(|lock| lock.path.as_path())
+    }
+
+    fn rekey(&mut self, key: Option<EncryptionKey>) -> Result<(), BitcoinAnchorError> {
+        self.lock()?;
+        encryption::rekey_locked(&self.path, self.key.as_ref(), key.as_ref())?;
+        self.key = key;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Append-Only Log Backend
+// ============================================================================
+
+/// Log entry; an entry without key and value marks a commit
+#[derive(Clone, Debug, StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_F1R3FLY_RGB)]
+struct LogEntry {
+    /// 0 = commit marker, otherwise a `RecordKind`
+    kind: u8,
+    key: SmallBlob,
+    /// `None` deletes the record
+    value: Option<MediumBlob>,
+}
+
+impl StrictSerialize for LogEntry {}
+impl StrictDeserialize for LogEntry {}
+
+impl LogEntry {
+    fn commit() -> Self {
+        Self {
+            kind: 0,
+            key: SmallBlob::default(),
+            value: None,
+        }
+    }
+
+    fn record(
+        kind: RecordKind,
+        key: &[u8],
+        value: Option<&[u8]>,
+    ) -> Result<Self, BitcoinAnchorError> {
+        let too_large =
+            |what| BitcoinAnchorError::SerializationError(format!("Record {} too large", what));
+        Ok(Self {
+            kind: kind as u8,
+            key: Confined::try_from(key.to_vec()).map_err(|_| too_large("key"))?,
+            value: value
+                .map(|value| Confined::try_from(value.to_vec()).map_err(|_| too_large("value")))
+                .transpose()?,
+        })
+    }
+
+    fn frame(&self) -> Result<Vec<u8>, BitcoinAnchorError> {
+        let payload = self
+            .to_strict_serialized::<MAX_RECORD_SIZE>()
+            .map_err(|e| BitcoinAnchorError::SerializationError(e.to_string()))?
+            .release();
+        let mut frame = Vec::with_capacity(4 + payload.len() + CHECKSUM_LEN);
+        frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        frame.extend_from_slice(&payload);
+        frame.extend_from_slice(&checksum(&payload));
+        Ok(frame)
+    }
+}
+
+fn checksum(payload: &[u8]) -> [u8; CHECKSUM_LEN] {
+    let digest = Sha256::digest(payload);
+    [digest[0], digest[1], digest[2], digest[3]]
+}
+
+fn digest(value: &[u8]) -> [u8; 32] {
+    Sha256::digest(value).into()
+}
+
+/// Committed records of a log and the length of its valid prefix
+struct Replay {
+    records: BTreeMap<RecordKey, Vec<u8>>,
+    valid_len: usize,
+    entries: usize,
+}
+
+fn log_header() -> Vec<u8> {
+    let mut header = LOG_MAGIC.to_vec();
+    header.extend_from_slice(&LOG_VERSION.to_le_bytes());
+    header
+}
+
+/// Replay committed entries; stops at the first torn or corrupt frame
+fn replay(data: &[u8]) -> Result<Replay, BitcoinAnchorError> {
+    if data.len() < LOG_HEADER_LEN || data[..8] != LOG_MAGIC {
+        return Err(BitcoinAnchorError::DeserializationError(
+            "Missing anchor log magic number".to_string(),
+        ));
+    }
+    let version = u16::from_le_bytes([data[8], data[9]]);
+    if version != LOG_VERSION {
+        return Err(BitcoinAnchorError::UnsupportedVersion(format!(
+            "Anchor log v{}, this build reads v{}",
+            version, LOG_VERSION
+        )));
+    }
+
+    let mut records = BTreeMap::new();
+    let mut pending = Vec::new();
+    let mut pos = LOG_HEADER_LEN;
+    let mut valid_len = pos;
+    let mut entries = 0;
+    while data.len() >= pos + 4 {
+        let len =
+            u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+        let end = pos + 4 + len + CHECKSUM_LEN;
+        if data.len() < end {
+            break;
+        }
+        let payload = &data[pos + 4..pos + 4 + len];
+        if checksum(payload) != data[end - CHECKSUM_LEN..end] {
+            break;
+        }
+        let Ok(entry) = decode::<LogEntry>(payload) else {
+            break;
+        };
+        pos = end;
+
+        if entry.kind == 0 {
+            entries += pending.len() + 1;
+            for (key, value) in pending.drain(..) {
+                match value {
+                    Some(value) => records.insert(key, value),
+                    None => records.remove(&key),
+                };
+            }
+            valid_len = pos;
+        } else {
+            let kind = RecordKind::try_from(entry.kind)?;
+            pending.push((
+                (kind, entry.key.release()),
+                entry.value.map(|value| value.release()),
+            ));
+        }
+    }
+
+    if valid_len < data.len() {
+        log::warn!(
+            "Anchor log: dropping {} byte(s) of uncommitted or corrupt tail",
+            data.len() - valid_len
+        );
+    }
+
+    Ok(Replay {
+        records,
+        valid_len,
+        entries,
+    })
+}
+
+/// Open log file with its lock and the digests of committed records
+struct LogState {
+    _lock: PersistenceLock,
+    file: File,
+    digests: HashMap<RecordKey, [u8; 32]>,
+    entries: usize,
+    /// Length of the committed prefix; anything past it is a torn write
+    len: u64,
+}
+
+/// Append-only strict-encoded log
+///
+/// Each commit appends the records that changed since the previous one.
+/// When the log holds more than `max(compact_min, 2 × live records)`
+/// entries it is rewritten as a snapshot (atomically, keeping `<path>.bak`).
+pub struct LogStore {
+    path: PathBuf,
+    compact_min: usize,
+    state: Option<LogState>,
+}
+
+impl LogStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self::with_compaction(path, COMPACT_MIN_RECORDS)
+    }
+
+    /// Create a log store compacting once it holds more than `min_records`
+    /// entries (and twice the live records)
+    pub fn with_compaction(path: PathBuf, min_records: usize) -> Self {
+        Self {
+            path,
+            compact_min: min_records,
+            state: None,
+        }
+    }
+
+    /// Number of entries in the log, including deletions and commit markers
+    pub fn log_entries(&self) -> usize {
+        self.state.as_ref().map_or(0, |state| state.entries)
+    }
+
+    /// Open (creating if needed) and lock the log, truncating a torn tail
+    fn open(&mut self) -> Result<BTreeMap<RecordKey, Vec<u8>>, BitcoinAnchorError> {
+        let io_err = |e: std::io::Error| BitcoinAnchorError::PersistenceError(e.to_string());
+
+        let lock = PersistenceLock::acquire(&self.path)?;
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.path)
+            .map_err(io_err)?;
+
+        let mut data = Vec::new();
+        file.read_to_end(&mut data).map_err(io_err)?;
+        let replay = if data.is_empty() {
+            file.write_all(&log_header()).map_err(io_err)?;
+            file.sync_all().map_err(io_err)?;
+            Replay {
+                records: BTreeMap::new(),
+                valid_len: LOG_HEADER_LEN,
+                entries: 0,
+            }
+        } else {
+            let replay = replay(&data)?;
+            if replay.valid_len < data.len() {
+                file.set_len(replay.valid_len as u64).map_err(io_err)?;
+                file.sync_all().map_err(io_err)?;
+            }
+            replay
+        };
+        file.seek(SeekFrom::End(0)).map_err(io_err)?;
+
+        self.state = Some(LogState {
+            _lock: lock,
+            file,
+            digests: replay
+                .records
+                .iter()
+                .map(|(key, value)| (key.clone(), digest(value)))
+                .collect(),
+            entries: replay.entries,
+            len: replay.valid_len as u64,
+        });
+        Ok(replay.records)
+    }
+
+    /// Rewrite the log as a snapshot of `records`
+    fn compact(&mut self, records: &[StoreRecord]) -> Result<(), BitcoinAnchorError> {
+        let io_err = |e: std::io::Error| BitcoinAnchorError::PersistenceError(e.to_string());
+
+        let mut data = log_header();
+        for record in records {
+            data.extend(LogEntry::record(record.kind, &record.key, Some(&record.value))?.frame()?);
+        }
+        data.extend(LogEntry::commit().frame()?);
+        write_atomic(&self.path, &data).map_err(io_err)?;
+
+        let file = OpenOptions::new()
+            .write(true)
+            .open(&self.path)
+            .map_err(io_err)?;
+        if let Some(state) = &mut self.state {
+            state.file = file;
+            state.entries = records.len() + 1;
+            state.len = data.len() as u64;
+        }
+        log::info!(
+            "Anchor log {:?} compacted to {} record(s)",
+            self.path,
+            records.len()
+        );
+        Ok(())
+    }
+}
+
+impl<Seal: StoredSeal> AnchorStore<Seal> for LogStore {
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn load(&mut self) -> Result<Option<BitcoinAnchorTracker<Seal>>, BitcoinAnchorError> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+        let records = self.open()?;
+        let tracker = BitcoinAnchorTracker::from_records(
+            records
+                .into_iter()
+                .map(|((kind, key), value)| StoreRecord { kind, key, value }),
+        )?;
+        Ok(Some(tracker))
+    }
+
+    fn commit(&mut self, tracker: &BitcoinAnchorTracker<Seal>) -> Result<(), BitcoinAnchorError> {
+        if self.state.is_none() {
+            self.open()?;
+        }
+        let records = tracker.to_records()?;
+        let state = self.state.as_mut().expect("log opened above");
+
+        let mut current = HashMap::with_capacity(records.len());
+        let mut frames = Vec::new();
+        let mut changed = 0;
+        for record in &records {
+            let key = (record.kind, record.key.clone());
+            let value_digest = digest(&record.value);
+            if state.digests.get(&key) != Some(&value_digest) {
+                frames.extend(
+                    LogEntry::record(record.kind, &record.key, Some(&record.value))?.frame()?,
+                );
+                changed += 1;
+            }
+            current.insert(key, value_digest);
+        }
+        for (kind, key) in state.digests.keys() {
+            if !current.contains_key(&(*kind, key.clone())) {
+                frames.extend(LogEntry::record(*kind, key, None)?.frame()?);
+                changed += 1;
+            }
+        }
+        if changed == 0 {
+            return Ok(());
+        }
+        frames.extend(LogEntry::commit().frame()?);
+
+        let io_err = |e: std::io::Error| BitcoinAnchorError::PersistenceError(e.to_string());
+        if let Err(e) = append_frames(&mut state.file, state.len, &frames) {
+            // Drop the torn frame; if that fails too, reopen (and replay,
+            // which truncates it) on the next commit
+            if state.file.set_len(state.len).is_err() {
+                self.state = None;
+            }
+            return Err(io_err(e));
+        }
+        state.len += frames.len() as u64;
+        state.digests = current;
+        state.entries += changed + 1;
+
+        // The commit is durable at this point, so a failed compaction must not
+        // fail it. Reopen on the next commit, which replays whichever log
+        // ended up on disk and retries compaction.
+        if state.entries > self.compact_min.max(2 * records.len()) {
+            if let Err(e) = self.compact(&records) {
+                log::warn!("Anchor log {:?} compaction failed: {}", self.path, e);
+                self.state = None;
+            }
+        }
+        Ok(())
+    }
+
+    fn locked_path(&self) -> Option<&Path> {
+        self.state.as_ref().map(|_| self.path.as_path())
+    }
+}
+
+/// Write `frames` at the committed length `offset` and sync them
+///
+/// Anything past `offset` is the torn tail of a failed commit and is
+/// overwritten, so it can't hide the frames of later commits from replay.
+fn append_frames(file: &mut File, offset: u64, frames: &[u8]) -> std::io::Result<()> {
+    file.set_len(offset)?;
+    file.seek(SeekFrom::Start(offset))?;
+    file.write_all(frames)?;
+    file.sync_data()
+}
+
+// ============================================================================
+// In-Memory Backend
+// ============================================================================
+
+/// Encoded records kept in memory
+///
+/// Round-trips the tracker through the same encoding as `LogStore`, without
+/// touching the disk.
+#[derive(Default)]
+pub struct MemoryStore {
+    records: Option<Vec<StoreRecord>>,
+    commits: usize,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of commits received
+    pub fn commits(&self) -> usize {
+        self.commits
+    }
+}
+
+impl<Seal: StoredSeal> AnchorStore<Seal> for MemoryStore {
+    fn path(&self) -> Option<&Path> {
+        None
+    }
+
+    fn load(&mut self) -> Result<Option<BitcoinAnchorTracker<Seal>>, BitcoinAnchorError> {
+        match &self.records {
+            Some(records) => BitcoinAnchorTracker::from_records(records.iter().cloned()).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn commit(&mut self, tracker: &BitcoinAnchorTracker<Seal>) -> Result<(), BitcoinAnchorError> {
+        self.records = Some(tracker.to_records()?);
+        self.commits += 1;
+        Ok(())
+    }
+
+    /// Nothing is stored at rest, so any key is accepted
+    fn rekey(&mut self, _key: Option<EncryptionKey>) -> Result<(), BitcoinAnchorError> {
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Crash-Safe File Writes
+// ============================================================================
+
+/// Advisory lock on `<path>.lock`, released on drop
+pub(crate) struct PersistenceLock {
+    /// Persistence path the lock guards
+    pub path: PathBuf,
+    _file: File,
+}
+
+impl PersistenceLock {
+    pub fn acquire(path: &Path) -> Result<Self, BitcoinAnchorError> {
+        let lock_path = sidecar_path(path, "lock");
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|e| BitcoinAnchorError::PersistenceError(e.to_string()))?;
+
+        match file.try_lock() {
+            Ok(()) => Ok(Self {
+                path: path.to_path_buf(),
+                _file: file,
+            }),
+            Err(TryLockError::WouldBlock) => Err(BitcoinAnchorError::Locked(format!(
+                "{:?} is in use by another tracker",
+                path
+            ))),
+            Err(TryLockError::Error(e)) => Err(BitcoinAnchorError::PersistenceError(e.to_string())),
+        }
+    }
+}
+
+/// `<path>.<suffix>` next to the persistence file
+pub(crate) fn sidecar_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Write `data` to `path` so that a crash leaves either the old or the new file
+///
+/// Keeps a copy of the previous file as `<path>.bak`.
+pub(crate) fn write_atomic(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    write_atomic_with(path, data, true)
+}
+
+/// `write_atomic()` without keeping a backup, e.g. for the backup itself
+pub(crate) fn replace_atomic(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    write_atomic_with(path, data, false)
+}
+
+fn write_atomic_with(path: &Path, data: &[u8], backup: bool) -> std::io::Result<()> {
+    let tmp = sidecar_path(path, "tmp");
+    {
+        let mut file = File::create(&tmp)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+    }
+
+    if backup && path.exists() {
+        std::fs::copy(path, sidecar_path(path, "bak"))?;
+    }
+    std::fs::rename(&tmp, path)?;
+
+    // Persist the rename itself
+    #[cfg(unix)]
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        File::open(dir)?.sync_all()?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,286 @@
+//! ASCII armor for binary F1r3fly-RGB data
+//!
+//! Wraps binary payloads (e.g. strict-encoded consignments) into a text form
+//! suitable for copy-paste, email or QR-less channels, similar to RGB's
+//! armored consignments.
+//!
+//! # Format
+//!
+//! ```text
+//! -----BEGIN F1R3FLY CONSIGNMENT-----
+//! Id: contract:...
+//! Checksum-SHA256: 3f1c...
+//!
+//! RkxSRwEAAQAAAA...
+//! -----END F1R3FLY CONSIGNMENT-----
+//! ```
+//!
+//! - Payload is base64 (standard alphabet) wrapped at `ARMOR_LINE_WIDTH` characters
+//! - `Checksum-SHA256` header is always present and verified on decoding
+//! - Additional `Key: Value` headers are informational
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+/// Line width of the base64 payload
+pub const ARMOR_LINE_WIDTH: usize = 64;
+
+/// Name of the mandatory checksum header
+pub const CHECKSUM_HEADER: &str = "Checksum-SHA256";
+
+/// Result type for armoring operations
+pub type Result<T> = std::result::Result<T, ArmorError>;
+
+/// Errors decoding ASCII-armored data
+#[derive(Debug)]
+pub enum ArmorError {
+    /// No `-----BEGIN <label>-----` line for the expected label
+    MissingBegin(String),
+
+    /// No `-----END <label>-----` line after the header
+    MissingEnd(String),
+
+    /// Header line is not of the form `Name: value`
+    InvalidHeader(String),
+
+    /// Mandatory `Checksum-SHA256` header is absent
+    MissingChecksum,
+
+    /// Payload checksum differs from the header
+    ChecksumMismatch { expected: String, found: String },
+
+    /// Payload is not valid base64
+    InvalidEncoding(String),
+}
+
+impl std::fmt::Display for ArmorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingBegin(label) => write!(f, "Missing armor header for {}", label),
+            Self::MissingEnd(label) => write!(f, "Missing armor footer for {}", label),
+            Self::InvalidHeader(line) => write!(f, "Invalid armor header line: {}", line),
+            Self::MissingChecksum => write!(f, "Armor has no {} header", CHECKSUM_HEADER),
+            Self::ChecksumMismatch { expected, found } => {
+                write!(
+                    f,
+                    "Armor checksum mismatch: expected {}, found {}",
+                    expected, found
+                )
+            }
+            Self::InvalidEncoding(msg) => write!(f, "Invalid armor payload: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ArmorError {}
+
+/// Decoded armored data
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dearmored {
+    /// Informational headers, in order of appearance (checksum excluded)
+    pub headers: Vec<(String, String)>,
+
+    /// Binary payload
+    pub data: Vec<u8>,
+}
+
+impl Dearmored {
+    /// Get header value by name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Opening line for the given label
+pub fn begin_line(label: &str) -> String {
+    format!("-----BEGIN {}-----", label)
+}
+
+/// Closing line for the given label
+pub fn end_line(label: &str) -> String {
+    format!("-----END {}-----", label)
+}
+
+/// Armor binary data
+///
+/// # Arguments
+/// * `label` - Armor label (e.g. "F1R3FLY CONSIGNMENT")
+/// * `headers` - Informational headers written before the checksum
+/// * `data` - Binary payload
+///
+/// # Returns
+/// Armored text terminated by a newline
+pub fn armor(label: &str, headers: &[(&str, String)], data: &[u8]) -> String {
+    let mut out = String::new();
+    out.push_str(&begin_line(label));
+    out.push('\n');
+
+    for (key, value) in headers {
+        out.push_str(&format!("{}: {}\n", key, value));
+    }
+    out.push_str(&format!("{}: {}\n", CHECKSUM_HEADER, checksum(data)));
+    out.push('\n');
+
+    let encoded = BASE64.encode(data);
+    // Base64 output is ASCII, so byte chunks are valid line boundaries
+    for line in encoded.as_bytes().chunks(ARMOR_LINE_WIDTH) {
+        out.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+        out.push('\n');
+    }
+
+    out.push_str(&end_line(label));
+    out.push('\n');
+    out
+}
+
+/// Decode armored text and verify its checksum
+///
+/// Leading/trailing whitespace and blank lines around the payload are tolerated.
+pub fn dearmor(label: &str, text: &str) -> Result<Dearmored> {
+    let begin = begin_line(label);
+    let end = end_line(label);
+
+    let mut lines = text
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty());
+
+    if lines.next() != Some(begin.as_str()) {
+        return Err(ArmorError::MissingBegin(label.to_string()));
+    }
+
+    // Headers run until the first blank line
+    let mut headers = Vec::new();
+    let mut checksum_value = None;
+    for line in lines.by_ref() {
+        if line.is_empty() {
+            break;
+        }
+        let (key, value) = line
+            .split_once(':')
+            .ok_or_else(|| ArmorError::InvalidHeader(line.to_string()))?;
+        let (key, value) = (key.trim(), value.trim());
+        if key == CHECKSUM_HEADER {
+            checksum_value = Some(value.to_lowercase());
+        } else {
+            headers.push((key.to_string(), value.to_string()));
+        }
+    }
+
+    let mut payload = String::new();
+    let mut terminated = false;
+    for line in lines {
+        if line == end {
+            terminated = true;
+            break;
+        }
+        payload.push_str(line);
+    }
+    if !terminated {
+        return Err(ArmorError::MissingEnd(label.to_string()));
+    }
+
+    let data = BASE64
+        .decode(payload.as_bytes())
+        .map_err(|e| ArmorError::InvalidEncoding(e.to_string()))?;
+
+    let expected = checksum_value.ok_or(ArmorError::MissingChecksum)?;
+    let found = checksum(&data);
+    if expected != found {
+        return Err(ArmorError::ChecksumMismatch { expected, found });
+    }
+
+    Ok(Dearmored { headers, data })
+}
+
+/// Check whether text looks like armored data with the given label
+pub fn is_armored(label: &str, data: &[u8]) -> bool {
+    let begin = begin_line(label);
+    let trimmed = data
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .map(|start| &data[start..])
+        .unwrap_or_default();
+    trimmed.starts_with(begin.as_bytes())
+}
+
+/// Hex-encoded SHA-256 of the payload
+fn checksum(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LABEL: &str = "F1R3FLY TEST";
+
+    #[test]
+    fn test_armor_round_trip() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+        let text = armor(LABEL, &[("Id", "test-id".to_string())], &data);
+
+        assert!(is_armored(LABEL, text.as_bytes()));
+
+        let decoded = dearmor(LABEL, &text).expect("Dearmor should succeed");
+        assert_eq!(decoded.data, data);
+        assert_eq!(decoded.header("Id"), Some("test-id"));
+        assert_eq!(decoded.header(CHECKSUM_HEADER), None);
+    }
+
+    #[test]
+    fn test_armor_line_wrapping() {
+        let data = vec![0xABu8; 200];
+        let text = armor(LABEL, &[], &data);
+        let payload_lines: Vec<_> = text
+            .lines()
+            .skip_while(|line| !line.is_empty())
+            .skip(1)
+            .take_while(|line| !line.starts_with("-----"))
+            .collect();
+
+        assert!(payload_lines.len() > 1, "Payload should be wrapped");
+        assert!(payload_lines[..payload_lines.len() - 1]
+            .iter()
+            .all(|line| line.len() == ARMOR_LINE_WIDTH));
+    }
+
+    #[test]
+    fn test_dearmor_detects_corruption() {
+        let text = armor(LABEL, &[], b"f1r3fly consignment payload");
+
+        // Flip one payload character
+        let corrupted: String = text
+            .lines()
+            .map(|line| {
+                if !line.starts_with("-----") && !line.contains(':') && !line.is_empty() {
+                    let mut chars: Vec<char> = line.chars().collect();
+                    chars[0] = if chars[0] == 'A' { 'B' } else { 'A' };
+                    chars.into_iter().collect()
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        match dearmor(LABEL, &corrupted) {
+            Err(ArmorError::ChecksumMismatch { .. }) | Err(ArmorError::InvalidEncoding(_)) => {}
+            other => panic!("Expected checksum failure, got: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_dearmor_rejects_wrong_label() {
+        let text = armor(LABEL, &[], b"data");
+        assert!(matches!(
+            dearmor("OTHER LABEL", &text),
+            Err(ArmorError::MissingBegin(_))
+        ));
+        assert!(!is_armored("OTHER LABEL", text.as_bytes()));
+    }
+}
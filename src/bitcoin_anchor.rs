@@ -11,8 +11,8 @@
 //! ## Persistence
 //!
 //! **Explicit Persistence Model:**
-//! - `save(path)` - Explicitly serialize to JSON file
-//! - `load_from_disk(path)` - Explicitly deserialize from JSON file
+//! - `save(path)` - Explicitly serialize to JSON file (versioned, see `anchor_schema`)
+//! - `load_from_disk(path)` - Explicitly load from the store selected by `path`
 //!
 //! **Pile Trait Integration:**
 //! - `Pile::new(conf)` - Creates empty tracker; enables auto-persist if `conf.persistence_path` is set
@@ -23,12 +23,31 @@
 //! - With path: `BitcoinAnchorTracker::with_persistence("./data.json")` → auto-saves on `commit_transaction()`
 //! - Without path: `BitcoinAnchorTracker::new()` → call `save()` manually when needed
 //!
-//! **Future Consideration:**
-//! - For production at scale (>100K anchors, multi-process access), consider migrating to
-//!   `PileFs` from `rgb-persist-fs` crate. It provides binary storage via `aora`, ACID transactions,
-//!   and multi-database architecture. Migration is trivial: change type alias `BitcoinAnchorTracker<TxoSeal>`
-//!   → `PileFs<TxoSeal>`. Current JSON approach chosen for: faster development, human-readable debugging,
-//!   and right-sized for single-user wallet needs.
+//! **Storage Backends** (see `anchor_store`):
+//! - `*.json` and other paths: whole tracker as JSON (`JsonStore`)
+//! - `*.log`: append-only strict-encoded log, compacted periodically (`LogStore`)
+//! - `:memory:`: encoded records kept in memory, for tests (`MemoryStore`)
+//! - `with_store()` sets any `AnchorStore` explicitly
+//!
+//! **Encryption at Rest** (see `encryption`):
+//! - `with_encrypted_persistence()`, `save_encrypted()`, `load_encrypted()`
+//!   and `AnchorConfig::encryption` seal JSON files with a passphrase or
+//!   application key
+//! - `rekey()` changes the key of the persistence file and its backup
+//!
+//! **Crash Safety:**
+//! - Writes go to `<path>.tmp`, are fsynced, then renamed over `<path>`
+//! - The previous file is kept as `<path>.bak`; `load_from_disk()` falls back to it
+//!   if `<path>` is missing or unreadable
+//! - An advisory lock on `<path>.lock` guards writes; the first auto-save takes it
+//!   for the tracker's lifetime, so a second process can't clobber the file
+//! - `try_commit_transaction()` returns auto-save errors; `commit_transaction()`
+//!   logs them and keeps the last one for `take_persist_error()`
+//!
+//! **Sharing Between Devices:**
+//! - `export_ops(opids)` - Self-contained bundle of operations and their witnesses
+//! - `merge(other)` / `import_bundle(path)` - Combine trackers; witness statuses only
+//!   advance, seal and anchor conflicts are reported in `MergeReport`
 //!
 //! ## RGB Compliance
 //!
@@ -36,15 +55,31 @@
 //! relationships. Preserves RGB semantics: seal persistence, witness status progression,
 //! bidirectional mappings, batch transactions, RBF support, and genesis operations
 
-use amplify::confinement::SmallOrdMap;
-use bp::seals::Anchor;
+use amplify::confinement::{SmallBlob, SmallOrdMap};
+use bp::seals::{Anchor, TxoSeal, WOutpoint, WTxoSeal};
+use bp::{BlockHash, Outpoint, Tx, Txid};
 use rgb::{CellAddr, OpRels, Opid, Pile, RgbSeal, Witness, WitnessStatus};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::error::Error as StdError;
 use std::fmt;
 use std::marker::PhantomData;
+use std::num::NonZeroU64;
+use std::ops::RangeInclusive;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use strict_encoding::{StrictDecode, StrictEncode};
+
+use crate::anchor_schema::{self, TrackerEnvelope, TRACKER_SCHEMA_VERSION};
+use crate::anchor_store::{
+    self, decode, decode_err, encode, encode_with, open_store, open_store_with_key, sidecar_path,
+    AnchorStore, PersistenceLock, RecordKind, StoreRecord, StoredSeal,
+};
+use crate::chain::{ChainSource, SyncEvent, TxStatus};
+use crate::consignment::AnchorMethodWire;
+use crate::encryption::{self, EncryptionKey};
+use crate::witness_tx::{WitnessTx, WitnessTxBuilder, MIN_FEE_RATE};
 
 // Re-export for convenience (these are from rgb-std::pile)
 pub use rgb::{OpRels as RgbOpRels, Witness as RgbWitness};
@@ -53,7 +88,15 @@ pub use rgb::{OpRels as RgbOpRels, Witness as RgbWitness};
 #[derive(Debug, Clone, Default)]
 pub struct AnchorConfig {
     /// Optional path to load/save tracker data
+    ///
+    /// The path also selects the storage backend: `*.log` for the
+    /// append-only log, `:memory:` for in-memory, JSON otherwise.
     pub persistence_path: Option<PathBuf>,
+
+    /// Optional key encrypting the persistence file (JSON backend only)
+    ///
+    /// Loading a plaintext file with a key fails, as with `load_encrypted()`.
+    pub encryption: Option<EncryptionKey>,
 }
 
 /// Error type for BitcoinAnchorTracker operations
@@ -69,6 +112,14 @@ pub enum BitcoinAnchorError {
     SerializationError(String),
     /// Deserialization error
     DeserializationError(String),
+    /// Fee bump rejected or replacement construction failed
+    FeeBumpFailed(String),
+    /// Persistence file is locked by another tracker or process
+    Locked(String),
+    /// Stored data was written by a newer schema version
+    UnsupportedVersion(String),
+    /// Encrypted file can't be opened (missing or wrong key) or sealed
+    Encryption(String),
 }
 
 impl fmt::Display for BitcoinAnchorError {
@@ -79,12 +130,313 @@ impl fmt::Display for BitcoinAnchorError {
             Self::PersistenceError(msg) => write!(f, "Persistence error: {}", msg),
             Self::SerializationError(msg) => write!(f, "Serialization error: {}", msg),
             Self::DeserializationError(msg) => write!(f, "Deserialization error: {}", msg),
+            Self::FeeBumpFailed(msg) => write!(f, "Fee bump failed: {}", msg),
+            Self::Locked(msg) => write!(f, "Persistence file locked: {}", msg),
+            Self::UnsupportedVersion(msg) => write!(f, "Unsupported schema version: {}", msg),
+            Self::Encryption(msg) => write!(f, "Encryption error: {}", msg),
         }
     }
 }
 
 impl StdError for BitcoinAnchorError {}
 
+/// Relation problems found by `BitcoinAnchorTracker::check_consistency()`
+///
+/// Trackers saved by older versions may contain duplicate op ↔ witness
+/// relations (`add_witness()` wasn't idempotent). `repair()` fixes all of
+/// these, and rebuilds stale outpoint indexes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    /// Repeated entries in the op → witnesses or witness → ops mappings
+    pub duplicate_relations: usize,
+
+    /// Relations recorded in only one of the two mappings
+    pub one_sided_relations: usize,
+
+    /// Relations referring to a witness with no stored data
+    pub dangling_relations: usize,
+
+    /// Outpoint index entries missing or not backed by seals and witnesses
+    pub stale_index_entries: usize,
+}
+
+impl ConsistencyReport {
+    /// Check whether no problems were found
+    pub fn is_consistent(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+impl fmt::Display for ConsistencyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} duplicate, {} one-sided, {} dangling relation(s), {} stale index entries",
+            self.duplicate_relations,
+            self.one_sided_relations,
+            self.dangling_relations,
+            self.stale_index_entries
+        )
+    }
+}
+
+/// Bitcoin commitment method used by an anchor
+///
+/// Recorded explicitly alongside each anchor so verifiers don't have to infer
+/// the method from the presence of a Tapret proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AnchorMethod {
+    /// Tapret commitment in the given (taproot) output
+    Tapret { output: u32 },
+
+    /// OP_RETURN commitment in the given output
+    OpReturn { output: u32 },
+}
+
+impl AnchorMethod {
+    /// Index of the output carrying the commitment
+    pub fn output(&self) -> u32 {
+        match self {
+            Self::Tapret { output } | Self::OpReturn { output } => *output,
+        }
+    }
+
+    /// Infer the method of a legacy anchor stored without one
+    ///
+    /// Legacy anchors always committed at output 0: Tapret when a DBC proof is
+    /// present, OP_RETURN otherwise.
+    pub fn infer_legacy(anchor: &Anchor) -> Self {
+        if anchor.dbc_proof.is_some() {
+            Self::Tapret { output: 0 }
+        } else {
+            Self::OpReturn { output: 0 }
+        }
+    }
+}
+
+impl fmt::Display for AnchorMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tapret { output } => write!(f, "Tapret (output {})", output),
+            Self::OpReturn { output } => write!(f, "OP_RETURN (output {})", output),
+        }
+    }
+}
+
+/// Seals closing over Bitcoin outpoints
+///
+/// Lets the tracker index seals and witnesses by outpoint (see
+/// `BitcoinAnchorTracker::ops_by_outpoint`).
+pub trait OutpointSeal: RgbSeal {
+    /// Outpoints closed by `seal`
+    ///
+    /// Seals on an output of the operation's own witness resolve against each
+    /// of its `witnesses` (several while fee bumps are pending).
+    fn seal_outpoints(seal: &Self::Definition, witnesses: &[Self::WitnessId]) -> Vec<Outpoint>;
+
+    /// Outpoints spent by a published witness
+    fn spent_outpoints(published: &Self::Published) -> Vec<Outpoint>;
+}
+
+impl OutpointSeal for TxoSeal {
+    fn seal_outpoints(seal: &WTxoSeal, witnesses: &[Txid]) -> Vec<Outpoint> {
+        match seal.primary {
+            WOutpoint::Extern(outpoint) => vec![outpoint],
+            WOutpoint::Wout(vout) => witnesses
+                .iter()
+                .map(|txid| Outpoint::new(*txid, vout))
+                .collect(),
+        }
+    }
+
+    fn spent_outpoints(tx: &Tx) -> Vec<Outpoint> {
+        tx.inputs.iter().map(|input| input.prev_output).collect()
+    }
+}
+
+/// Coarse witness status, ignoring the block height
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StatusKind {
+    Tentative,
+    Mined,
+    Archived,
+    /// Genesis, off-chain and any other status
+    Other,
+}
+
+impl From<WitnessStatus> for StatusKind {
+    fn from(status: WitnessStatus) -> Self {
+        match status {
+            WitnessStatus::Tentative => Self::Tentative,
+            WitnessStatus::Mined(_) => Self::Mined,
+            WitnessStatus::Archived => Self::Archived,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Witness filter for `BitcoinAnchorTracker::filter_witnesses()`
+///
+/// ```ignore
+/// // Witnesses mined 1 to 5 blocks deep at tip 850_000
+/// let filter = WitnessFilter::new()
+///     .status(StatusKind::Mined)
+///     .confirmations(1..=5, 850_000);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WitnessFilter {
+    status: Option<StatusKind>,
+    confirmations: Option<(RangeInclusive<u64>, u64)>,
+}
+
+impl WitnessFilter {
+    /// Filter matching every witness
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only witnesses with this status
+    pub fn status(mut self, kind: StatusKind) -> Self {
+        self.status = Some(kind);
+        self
+    }
+
+    /// Only witnesses with a number of confirmations in `range` at
+    /// `tip_height` (unmined witnesses have none)
+    pub fn confirmations(mut self, range: RangeInclusive<u64>, tip_height: u64) -> Self {
+        self.confirmations = Some((range, tip_height));
+        self
+    }
+
+    /// Check a witness status against the filter
+    pub fn matches(&self, status: WitnessStatus) -> bool {
+        if self
+            .status
+            .is_some_and(|kind| kind != StatusKind::from(status))
+        {
+            return false;
+        }
+        match &self.confirmations {
+            Some((range, tip)) => range.contains(&confirmations(status, *tip)),
+            None => true,
+        }
+    }
+}
+
+/// Confirmations of a witness at `tip_height`
+fn confirmations(status: WitnessStatus, tip_height: u64) -> u64 {
+    match status {
+        WitnessStatus::Mined(height) if height.get() <= tip_height => tip_height - height.get() + 1,
+        _ => 0,
+    }
+}
+
+/// What `BitcoinAnchorTracker::prune()` removes
+///
+/// ```ignore
+/// let policy = PrunePolicy::archived_for(Duration::from_secs(30 * 86_400))
+///     .superseded_ops()
+///     .archive_to("./tracker_archive.log");
+/// tracker.prune(&policy)?;
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrunePolicy {
+    /// Prune witnesses `Archived` at least this long ago
+    pub archived_for: Duration,
+
+    /// Also prune operations whose seals were all spent by mined witnesses
+    /// of later operations
+    pub superseded_ops: bool,
+
+    /// Cold-storage file receiving the pruned records (backend selected by
+    /// the path, see `StorageBackend::for_path`); without one they are dropped
+    pub archive_path: Option<PathBuf>,
+
+    /// Time to measure against; the system clock if `None`
+    pub now: Option<SystemTime>,
+}
+
+impl PrunePolicy {
+    /// Prune witnesses archived for at least `age`
+    pub fn archived_for(age: Duration) -> Self {
+        Self {
+            archived_for: age,
+            superseded_ops: false,
+            archive_path: None,
+            now: None,
+        }
+    }
+
+    /// Also prune fully superseded operations
+    pub fn superseded_ops(mut self) -> Self {
+        self.superseded_ops = true;
+        self
+    }
+
+    /// Keep the pruned records in `path`
+    pub fn archive_to<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.archive_path = Some(path.into());
+        self
+    }
+
+    /// Measure ages against `now` instead of the system clock
+    pub fn at(mut self, now: SystemTime) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    fn now_secs(&self) -> u64 {
+        self.now.map_or_else(unix_now, unix_secs)
+    }
+}
+
+/// Records removed by `prune()` or brought back by `restore_pruned()`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PruneReport {
+    /// Witnesses removed or restored
+    pub witnesses: usize,
+
+    /// Operations removed or restored
+    pub operations: usize,
+
+    /// Anchors removed or restored (of pruned operations, or orphaned)
+    pub anchors: usize,
+
+    /// Cold-storage file involved, if any
+    pub archive: Option<PathBuf>,
+}
+
+impl PruneReport {
+    /// Check whether nothing was removed or restored
+    pub fn is_empty(&self) -> bool {
+        self.witnesses == 0 && self.operations == 0 && self.anchors == 0
+    }
+}
+
+impl fmt::Display for PruneReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} witness(es), {} operation(s), {} anchor(s)",
+            self.witnesses, self.operations, self.anchors
+        )?;
+        if let Some(archive) = &self.archive {
+            write!(f, " (archive {:?})", archive)?;
+        }
+        Ok(())
+    }
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+fn unix_now() -> u64 {
+    unix_secs(SystemTime::now())
+}
+
 /// Witness data for a Bitcoin transaction
 ///
 /// Stores both the published witness (Bitcoin transaction data) and the
@@ -105,6 +457,20 @@ pub struct WitnessData<Seal: RgbSeal> {
     pub status: WitnessStatus,
 }
 
+impl<Seal: RgbSeal> Clone for WitnessData<Seal>
+where
+    Seal::Published: Clone,
+    Seal::Client: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            published: self.published.clone(),
+            client: self.client.clone(),
+            status: self.status,
+        }
+    }
+}
+
 /// Tracks Bitcoin seals and witnesses for RGB operations
 ///
 /// This is a lightweight alternative to the `Pile` trait that focuses solely
@@ -152,10 +518,51 @@ pub struct BitcoinAnchorTracker<Seal: RgbSeal> {
     /// Stored when Bitcoin PSBT is finalized and Tapret proof is created
     anchors: HashMap<Opid, Anchor>,
 
-    /// Optional path for automatic persistence
-    /// If set, `commit_transaction()` will automatically save to this path
+    /// Commitment method for each anchor
+    /// Maps: Opid → AnchorMethod
+    /// Missing for anchors stored by older versions (see `AnchorMethod::infer_legacy`)
+    #[serde(default)]
+    anchor_methods: HashMap<Opid, AnchorMethod>,
+
+    /// Witness transaction builders for unconfirmed operations
+    /// Maps: Opid → WitnessTxBuilder
+    /// Kept until a witness is mined so the transaction can be fee-bumped
+    #[serde(default)]
+    pending_witnesses: HashMap<Opid, WitnessTxBuilder>,
+
+    /// Block containing each mined witness, as last seen by `sync_witnesses()`
+    /// Maps: WitnessId → BlockHash
+    #[serde(default)]
+    witness_blocks: HashMap<Seal::WitnessId, BlockHash>,
+
+    /// Operations whose witness was reorged out after being mined
+    /// F1r3fly claims made on these operations may need to be frozen or reverted
+    #[serde(default)]
+    reorged_ops: HashSet<Opid>,
+
+    /// When each witness became `Archived` (Unix seconds), for `prune()`
+    /// Maps: WitnessId → timestamp
+    #[serde(default)]
+    archived_at: HashMap<Seal::WitnessId, u64>,
+
+    /// Index: seal cells closing over each outpoint
+    /// Maps: Outpoint → [(Opid, output_index)]
+    #[serde(default, with = "outpoint_keyed")]
+    outpoint_seals: BTreeMap<Outpoint, Vec<(Opid, u16)>>,
+
+    /// Index: witnesses spending each outpoint
+    /// Maps: Outpoint → [WitnessId]
+    #[serde(default, with = "outpoint_keyed")]
+    outpoint_spenders: BTreeMap<Outpoint, Vec<Seal::WitnessId>>,
+
+    /// Optional store for automatic persistence
+    /// If set, `commit_transaction()` will automatically commit to it
+    #[serde(skip)]
+    store: Option<Box<dyn AnchorStore<Seal>>>,
+
+    /// Last error from `commit_transaction()` auto-save
     #[serde(skip)]
-    persistence_path: Option<PathBuf>,
+    persist_error: Option<BitcoinAnchorError>,
 
     /// Marker for seal type
     #[serde(skip)]
@@ -171,37 +578,30 @@ impl<Seal: RgbSeal> BitcoinAnchorTracker<Seal> {
             op_witnesses: HashMap::new(),
             witness_ops: HashMap::new(),
             anchors: HashMap::new(),
-            persistence_path: None,
+            anchor_methods: HashMap::new(),
+            pending_witnesses: HashMap::new(),
+            witness_blocks: HashMap::new(),
+            reorged_ops: HashSet::new(),
+            archived_at: HashMap::new(),
+            outpoint_seals: BTreeMap::new(),
+            outpoint_spenders: BTreeMap::new(),
+            store: None,
+            persist_error: None,
             _seal: PhantomData,
         }
     }
 
-    /// Create a new empty tracker with automatic persistence
-    ///
-    /// When `commit_transaction()` is called, data will be automatically
-    /// saved to the specified path.
-    pub fn with_persistence<P: Into<PathBuf>>(path: P) -> Self {
-        Self {
-            seals: HashMap::new(),
-            witnesses: HashMap::new(),
-            op_witnesses: HashMap::new(),
-            witness_ops: HashMap::new(),
-            anchors: HashMap::new(),
-            persistence_path: Some(path.into()),
-            _seal: PhantomData,
-        }
+    /// Get the current persistence path
+    pub fn persistence_path(&self) -> Option<&Path> {
+        self.store.as_ref().and_then(|store| store.path())
     }
 
-    /// Set or update the persistence path
+    /// Persist through `store` on `commit_transaction()`
     ///
-    /// This enables automatic persistence on `commit_transaction()` calls.
-    pub fn set_persistence_path<P: Into<PathBuf>>(&mut self, path: Option<P>) {
-        self.persistence_path = path.map(|p| p.into());
-    }
-
-    /// Get the current persistence path
-    pub fn persistence_path(&self) -> Option<&Path> {
-        self.persistence_path.as_deref()
+    /// Replaces (and releases the lock of) the previous store, if any.
+    pub fn with_store(mut self, store: Box<dyn AnchorStore<Seal>>) -> Self {
+        self.store = Some(store);
+        self
     }
 
     /// Get count of tracked operations (not part of Pile trait)
@@ -214,6 +614,75 @@ impl<Seal: RgbSeal> BitcoinAnchorTracker<Seal> {
         self.witnesses.len()
     }
 
+    // ========================================================================
+    // Fallible Witness Access
+    // ========================================================================
+
+    /// Get published witness (Bitcoin transaction) by ID
+    ///
+    /// Non-panicking variant of `Pile::pub_witness()`.
+    ///
+    /// # Errors
+    /// Returns `WitnessNotFound` if the witness is not known
+    pub fn try_pub_witness(
+        &self,
+        wid: Seal::WitnessId,
+    ) -> Result<Seal::Published, BitcoinAnchorError>
+    where
+        Seal::Published: Clone,
+    {
+        self.witness_data(wid).map(|data| data.published.clone())
+    }
+
+    /// Get client anchor by witness ID
+    ///
+    /// Non-panicking variant of `Pile::cli_witness()`.
+    ///
+    /// # Errors
+    /// Returns `WitnessNotFound` if the witness is not known
+    pub fn try_cli_witness(&self, wid: Seal::WitnessId) -> Result<Seal::Client, BitcoinAnchorError>
+    where
+        Seal::Client: Clone,
+    {
+        self.witness_data(wid).map(|data| data.client.clone())
+    }
+
+    /// Update witness confirmation status
+    ///
+    /// Non-panicking variant of `Pile::update_witness_status()`.
+    ///
+    /// # Errors
+    /// Returns `WitnessNotFound` if the witness is not known
+    pub fn try_update_witness_status(
+        &mut self,
+        wid: Seal::WitnessId,
+        status: WitnessStatus,
+    ) -> Result<(), BitcoinAnchorError> {
+        let data = self
+            .witnesses
+            .get_mut(&wid)
+            .ok_or_else(|| BitcoinAnchorError::WitnessNotFound(wid.to_string()))?;
+        data.status = status;
+        self.stamp_archived(wid, status);
+        Ok(())
+    }
+
+    /// Record when a witness became `Archived`, or forget it if it no
+    /// longer is
+    fn stamp_archived(&mut self, wid: Seal::WitnessId, status: WitnessStatus) {
+        if status == WitnessStatus::Archived {
+            self.archived_at.entry(wid).or_insert_with(unix_now);
+        } else {
+            self.archived_at.remove(&wid);
+        }
+    }
+
+    fn witness_data(&self, wid: Seal::WitnessId) -> Result<&WitnessData<Seal>, BitcoinAnchorError> {
+        self.witnesses
+            .get(&wid)
+            .ok_or_else(|| BitcoinAnchorError::WitnessNotFound(wid.to_string()))
+    }
+
     // ========================================================================
     // Persistence Methods
     // ========================================================================
@@ -221,13 +690,15 @@ impl<Seal: RgbSeal> BitcoinAnchorTracker<Seal> {
     /// Save tracker to disk (JSON format)
     ///
     /// Serializes the entire tracker state to a JSON file, including all seals,
-    /// witnesses, and their relationships.
+    /// witnesses, and their relationships. The write is atomic (temp file,
+    /// fsync, rename) and the previous file is kept as `<path>.bak`.
     ///
     /// # Arguments
     /// - `path`: File path to save to
     ///
     /// # Returns
-    /// `Ok(())` on success, error if serialization or I/O fails
+    /// `Ok(())` on success, error if serialization or I/O fails, or `Locked`
+    /// if another tracker holds the lock on `path`
     ///
     /// # Example
     /// ```ignore
@@ -240,49 +711,87 @@ impl<Seal: RgbSeal> BitcoinAnchorTracker<Seal> {
         Seal::Published: Serialize,
         Seal::Client: Serialize,
     {
-        let json = serde_json::to_string_pretty(self)
-            .map_err(|e| BitcoinAnchorError::SerializationError(e.to_string()))?;
+        self.save_with_key(path.as_ref(), None)
+    }
+
+    /// Save tracker state to an encrypted JSON file
+    ///
+    /// Same as `save()`, with the file sealed under `key` (see `encryption`).
+    /// Load it with `load_encrypted()`.
+    pub fn save_encrypted<P: AsRef<Path>>(
+        &self,
+        path: P,
+        key: &EncryptionKey,
+    ) -> Result<(), BitcoinAnchorError>
+    where
+        Seal::Definition: Serialize,
+        Seal::WitnessId: Serialize,
+        Seal::Published: Serialize,
+        Seal::Client: Serialize,
+    {
+        self.save_with_key(path.as_ref(), Some(key))
+    }
+
+    fn save_with_key(
+        &self,
+        path: &Path,
+        key: Option<&EncryptionKey>,
+    ) -> Result<(), BitcoinAnchorError>
+    where
+        Seal::Definition: Serialize,
+        Seal::WitnessId: Serialize,
+        Seal::Published: Serialize,
+        Seal::Client: Serialize,
+    {
+        let json = self.to_json()?;
 
-        std::fs::write(path, json)
-            .map_err(|e| BitcoinAnchorError::PersistenceError(e.to_string()))?;
+        // Reuse the long-lived lock if our store holds it, otherwise lock for this write
+        let locked = self.store.as_ref().and_then(|store| store.locked_path());
+        let _guard = match locked {
+            Some(locked) if locked == path => None,
+            _ => Some(PersistenceLock::acquire(path)?),
+        };
 
+        encryption::write_file(path, json.as_bytes(), key)?;
         Ok(())
     }
 
-    /// Load tracker from disk (JSON format)
-    ///
-    /// Deserializes a previously saved tracker from a JSON file, restoring all
-    /// seals, witnesses, and their relationships. The loaded tracker will have
-    /// automatic persistence enabled using the path it was loaded from.
-    ///
-    /// # Arguments
-    /// - `path`: File path to load from
-    ///
-    /// # Returns
-    /// Loaded tracker on success, error if file doesn't exist or deserialization fails
-    ///
-    /// # Example
-    /// ```ignore
-    /// let tracker = BitcoinAnchorTracker::load_from_disk("./tracker_data.json")?;
-    /// // Future commit_transaction() calls will auto-save to ./tracker_data.json
-    /// ```
-    pub fn load_from_disk<P: AsRef<Path>>(path: P) -> Result<Self, BitcoinAnchorError>
+    /// Serialize the tracker as pretty-printed JSON in the versioned envelope
+    pub(crate) fn to_json(&self) -> Result<String, BitcoinAnchorError>
     where
-        Seal::Definition: for<'de> Deserialize<'de>,
-        Seal::WitnessId: for<'de> Deserialize<'de>,
-        Seal::Published: for<'de> Deserialize<'de>,
-        Seal::Client: for<'de> Deserialize<'de>,
+        Seal::Definition: Serialize,
+        Seal::WitnessId: Serialize,
+        Seal::Published: Serialize,
+        Seal::Client: Serialize,
     {
-        let json = std::fs::read_to_string(path.as_ref())
-            .map_err(|e| BitcoinAnchorError::PersistenceError(e.to_string()))?;
-
-        let mut tracker: Self = serde_json::from_str(&json)
-            .map_err(|e| BitcoinAnchorError::DeserializationError(e.to_string()))?;
+        serde_json::to_string_pretty(&TrackerEnvelope::new(self))
+            .map_err(|e| BitcoinAnchorError::SerializationError(e.to_string()))
+    }
 
-        // Enable automatic persistence using the path we loaded from
-        tracker.persistence_path = Some(path.as_ref().to_path_buf());
+    /// Commit to the configured store
+    ///
+    /// Fallible variant of `commit_transaction()`. File stores take the
+    /// advisory lock on their path on first use and keep it until the tracker
+    /// is dropped or the store changes. Does nothing without a store.
+    ///
+    /// # Errors
+    /// Returns `Locked` if another tracker or process holds the path, or the
+    /// store's write error
+    pub fn try_commit_transaction(&mut self) -> Result<(), BitcoinAnchorError> {
+        let Some(mut store) = self.store.take() else {
+            return Ok(());
+        };
+        let result = store.commit(self);
+        self.store = Some(store);
+        result
+    }
 
-        Ok(tracker)
+    /// Take the last error from `commit_transaction()` auto-save
+    ///
+    /// `Pile::commit_transaction()` can't return errors; check this after
+    /// calling it, or use `try_commit_transaction()` instead.
+    pub fn take_persist_error(&mut self) -> Option<BitcoinAnchorError> {
+        self.persist_error.take()
     }
 
     // ============================================================================
@@ -308,7 +817,34 @@ impl<Seal: RgbSeal> BitcoinAnchorTracker<Seal> {
     /// tracker.add_anchor(opid, anchor);
     /// ```
     pub fn add_anchor(&mut self, opid: Opid, anchor: Anchor) {
+        self.anchor_methods.remove(&opid);
+        self.anchors.insert(opid, anchor);
+    }
+
+    /// Store anchor for an operation together with its commitment method
+    ///
+    /// # Arguments
+    ///
+    /// * `opid` - Operation ID
+    /// * `anchor` - Tapret or OP_RETURN anchor
+    /// * `method` - How the anchor commits in the witness transaction
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let (anchor, witness_tx, method) = create_tapret_anchor(contract_id, state_hash)?;
+    /// tracker.add_anchor_with_method(opid, anchor, method);
+    /// ```
+    pub fn add_anchor_with_method(&mut self, opid: Opid, anchor: Anchor, method: AnchorMethod) {
         self.anchors.insert(opid, anchor);
+        self.anchor_methods.insert(opid, method);
+    }
+
+    /// Retrieve the commitment method recorded for an operation's anchor
+    ///
+    /// Returns `None` if no anchor is stored or it was stored without a method.
+    pub fn get_anchor_method(&self, opid: &Opid) -> Option<AnchorMethod> {
+        self.anchor_methods.get(opid).copied()
     }
 
     /// Retrieve anchor for an operation
@@ -351,53 +887,1571 @@ impl<Seal: RgbSeal> BitcoinAnchorTracker<Seal> {
     ///
     /// The removed anchor, or `None` if it didn't exist
     pub fn remove_anchor(&mut self, opid: &Opid) -> Option<Anchor> {
+        self.anchor_methods.remove(opid);
         self.anchors.remove(opid)
     }
 }
 
-impl<Seal: RgbSeal> Default for BitcoinAnchorTracker<Seal> {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 // ============================================================================
-// Pile Trait Implementation
+// Outpoint Indexes and Wallet Queries
 // ============================================================================
 
-impl<Seal: RgbSeal> Pile for BitcoinAnchorTracker<Seal>
-where
-    Seal::WitnessId: Clone + Eq + std::hash::Hash,
-    Seal::Published: Clone,
-    Seal::Client: Clone,
-    Seal::Definition: Clone,
-    Seal::Definition: Serialize + for<'de> Deserialize<'de>,
-    Seal::WitnessId: Serialize + for<'de> Deserialize<'de>,
-    Seal::Published: Serialize + for<'de> Deserialize<'de>,
-    Seal::Client: Serialize + for<'de> Deserialize<'de>,
-{
-    type Seal = Seal;
-    type Conf = AnchorConfig;
-    type Error = BitcoinAnchorError;
-
-    /// Create a new pile with configuration
-    ///
-    /// Creates an empty in-memory tracker. If `conf.persistence_path` is provided,
-    /// automatic persistence will be enabled for `commit_transaction()` calls.
-    fn new(conf: Self::Conf) -> Result<Self, Self::Error> {
-        match conf.persistence_path {
-            Some(path) => Ok(Self::with_persistence(path)),
-            None => Ok(Self::new()),
+impl<Seal: OutpointSeal> BitcoinAnchorTracker<Seal> {
+    /// Operations defining a seal on `outpoint`
+    pub fn ops_by_outpoint(&self, outpoint: Outpoint) -> Vec<Opid> {
+        let mut opids = Vec::new();
+        for (opid, _) in self.outpoint_seals.get(&outpoint).into_iter().flatten() {
+            if !opids.contains(opid) {
+                opids.push(*opid);
+            }
         }
+        opids
     }
 
-    /// Load pile from persistence
+    /// Seals on any output of transaction `txid`, in output order
+    pub fn seals_by_txid(&self, txid: Txid) -> Vec<(CellAddr, Seal::Definition)> {
+        let from = Outpoint::new(txid, 0);
+        let to = Outpoint::new(txid, u32::MAX);
+        self.outpoint_seals
+            .range(from..=to)
+            .flat_map(|(_, cells)| cells)
+            .filter_map(|(opid, pos)| {
+                let seal = self.seals.get(opid)?.get(pos)?.clone();
+                Some((
+                    CellAddr {
+                        opid: *opid,
+                        pos: *pos,
+                    },
+                    seal,
+                ))
+            })
+            .collect()
+    }
+
+    /// Witnesses spending `outpoint`
+    ///
+    /// More than one while fee bumps are pending, or after a double-spend.
+    pub fn witnesses_spending(&self, outpoint: Outpoint) -> Vec<Seal::WitnessId> {
+        self.outpoint_spenders
+            .get(&outpoint)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Witnesses matching `filter`
+    pub fn filter_witnesses(&self, filter: &WitnessFilter) -> Vec<Seal::WitnessId> {
+        self.witnesses
+            .iter()
+            .filter(|(_, data)| filter.matches(data.status))
+            .map(|(wid, _)| *wid)
+            .collect()
+    }
+
+    /// Witnesses still `Tentative`, with the operations they anchor
+    ///
+    /// Unlike the fee-bump builders kept for `bump_witness_fee()`, this
+    /// covers every unconfirmed witness.
+    pub fn pending_witnesses(&self) -> Vec<(Seal::WitnessId, Vec<Opid>)> {
+        self.filter_witnesses(&WitnessFilter::new().status(StatusKind::Tentative))
+            .into_iter()
+            .map(|wid| {
+                let opids = self.witness_ops.get(&wid).cloned().unwrap_or_default();
+                (wid, opids)
+            })
+            .collect()
+    }
+
+    /// Operations with seals or witnesses but no anchor yet
+    pub fn unanchored_ops(&self) -> Vec<Opid> {
+        let mut opids: Vec<Opid> = self
+            .seals
+            .keys()
+            .chain(self.op_witnesses.keys())
+            .filter(|opid| !self.anchors.contains_key(opid))
+            .copied()
+            .collect();
+        opids.sort();
+        opids.dedup();
+        opids
+    }
+
+    // ========================================================================
+    // Consistency
+    // ========================================================================
+
+    /// Check op ↔ witness relations for duplicates and mismatches, and the
+    /// outpoint indexes against seals and witnesses
+    pub fn check_consistency(&self) -> ConsistencyReport {
+        let forward = relation_pairs(&self.op_witnesses, |opid, wid| (opid, wid));
+        let backward = relation_pairs(&self.witness_ops, |wid, opid| (opid, wid));
+
+        let dangling = forward
+            .unique
+            .union(&backward.unique)
+            .filter(|(_, wid)| !self.witnesses.contains_key(wid))
+            .count();
+
+        ConsistencyReport {
+            duplicate_relations: forward.duplicates + backward.duplicates,
+            one_sided_relations: forward
+                .unique
+                .symmetric_difference(&backward.unique)
+                .count(),
+            dangling_relations: dangling,
+            stale_index_entries: self.stale_index_entries(),
+        }
+    }
+
+    /// Repair op ↔ witness relations
+    ///
+    /// Removes duplicates and relations to unknown witnesses, and records
+    /// one-sided relations in both mappings. Existing order is kept. Outpoint
+    /// indexes are rebuilt. Call `save()` or `commit_transaction()` afterwards
+    /// to persist the result.
+    ///
+    /// # Returns
+    /// Problems found before the repair
+    pub fn repair(&mut self) -> ConsistencyReport {
+        let report = self.check_consistency();
+        if report.is_consistent() {
+            return report;
+        }
+
+        let forward = relation_pairs(&self.op_witnesses, |opid, wid| (opid, wid));
+        let backward = relation_pairs(&self.witness_ops, |wid, opid| (opid, wid));
+        let known = |(_, wid): &(Opid, Seal::WitnessId)| self.witnesses.contains_key(wid);
+        let pairs: Vec<(Opid, Seal::WitnessId)> = forward
+            .ordered
+            .into_iter()
+            .chain(backward.ordered)
+            .filter(known)
+            .collect();
+
+        let mut seen = HashSet::new();
+        let mut op_witnesses: HashMap<Opid, Vec<Seal::WitnessId>> = HashMap::new();
+        let mut witness_ops: HashMap<Seal::WitnessId, Vec<Opid>> = HashMap::new();
+        for (opid, wid) in pairs {
+            if seen.insert((opid, wid)) {
+                op_witnesses.entry(opid).or_default().push(wid);
+                witness_ops.entry(wid).or_default().push(opid);
+            }
+        }
+        self.op_witnesses = op_witnesses;
+        self.witness_ops = witness_ops;
+        self.rebuild_indexes();
+
+        log::info!("Tracker relations repaired: {}", report);
+        report
+    }
+
+    /// Rebuild both outpoint indexes from seals and witnesses
+    pub fn rebuild_indexes(&mut self) {
+        let (outpoint_seals, outpoint_spenders) = self.expected_indexes();
+        self.outpoint_seals = outpoint_seals;
+        self.outpoint_spenders = outpoint_spenders;
+    }
+
+    /// Rebuild the outpoint indexes if they don't match seals and witnesses
+    ///
+    /// Trackers stored before the indexes existed load with empty ones.
+    pub(crate) fn refresh_indexes(&mut self) {
+        let stale = self.stale_index_entries();
+        if stale > 0 {
+            log::info!("Rebuilding outpoint indexes ({} stale entries)", stale);
+            self.rebuild_indexes();
+        }
+    }
+
+    /// Number of index entries differing from a rebuilt index
+    fn stale_index_entries(&self) -> usize {
+        fn flatten<V: Eq + std::hash::Hash>(
+            map: &BTreeMap<Outpoint, Vec<V>>,
+        ) -> HashSet<(Outpoint, &V)> {
+            map.iter()
+                .flat_map(|(outpoint, values)| values.iter().map(move |v| (*outpoint, v)))
+                .collect()
+        }
+        fn diff<V: Eq + std::hash::Hash>(
+            actual: &BTreeMap<Outpoint, Vec<V>>,
+            expected: &BTreeMap<Outpoint, Vec<V>>,
+        ) -> usize {
+            flatten(actual)
+                .symmetric_difference(&flatten(expected))
+                .count()
+        }
+
+        let (outpoint_seals, outpoint_spenders) = self.expected_indexes();
+        diff(&self.outpoint_seals, &outpoint_seals)
+            + diff(&self.outpoint_spenders, &outpoint_spenders)
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn expected_indexes(
+        &self,
+    ) -> (
+        BTreeMap<Outpoint, Vec<(Opid, u16)>>,
+        BTreeMap<Outpoint, Vec<Seal::WitnessId>>,
+    ) {
+        let mut outpoint_seals: BTreeMap<Outpoint, Vec<(Opid, u16)>> = BTreeMap::new();
+        for opid in self.seals.keys() {
+            for (outpoint, cell) in self.op_seal_outpoints(*opid) {
+                let cells = outpoint_seals.entry(outpoint).or_default();
+                if !cells.contains(&cell) {
+                    cells.push(cell);
+                }
+            }
+        }
+
+        let mut outpoint_spenders: BTreeMap<Outpoint, Vec<Seal::WitnessId>> = BTreeMap::new();
+        for (wid, data) in &self.witnesses {
+            for outpoint in Seal::spent_outpoints(&data.published) {
+                let wids = outpoint_spenders.entry(outpoint).or_default();
+                if !wids.contains(wid) {
+                    wids.push(*wid);
+                }
+            }
+        }
+
+        (outpoint_seals, outpoint_spenders)
+    }
+
+    /// Outpoints closed by the seals of `opid`
+    fn op_seal_outpoints(&self, opid: Opid) -> Vec<(Outpoint, (Opid, u16))> {
+        let Some(seals) = self.seals.get(&opid) else {
+            return Vec::new();
+        };
+        let witnesses = self
+            .op_witnesses
+            .get(&opid)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        seals
+            .iter()
+            .flat_map(|(pos, seal)| {
+                Seal::seal_outpoints(seal, witnesses)
+                    .into_iter()
+                    .map(move |outpoint| (outpoint, (opid, *pos)))
+            })
+            .collect()
+    }
+
+    /// Add the seal cells of `opid` to the index
+    fn index_op(&mut self, opid: Opid) {
+        for (outpoint, cell) in self.op_seal_outpoints(opid) {
+            let cells = self.outpoint_seals.entry(outpoint).or_default();
+            if !cells.contains(&cell) {
+                cells.push(cell);
+            }
+        }
+    }
+
+    /// Remove the seal cells of `opid` from the index
+    fn unindex_op(&mut self, opid: Opid) {
+        for (outpoint, cell) in self.op_seal_outpoints(opid) {
+            if let Some(cells) = self.outpoint_seals.get_mut(&outpoint) {
+                cells.retain(|c| *c != cell);
+                if cells.is_empty() {
+                    self.outpoint_seals.remove(&outpoint);
+                }
+            }
+        }
+    }
+
+    /// Add or remove the inputs of witness `wid` in the spender index
+    fn index_spends(&mut self, wid: Seal::WitnessId, add: bool) {
+        let Some(data) = self.witnesses.get(&wid) else {
+            return;
+        };
+        for outpoint in Seal::spent_outpoints(&data.published) {
+            let wids = self.outpoint_spenders.entry(outpoint).or_default();
+            wids.retain(|w| *w != wid);
+            if add {
+                wids.push(wid);
+            } else if wids.is_empty() {
+                self.outpoint_spenders.remove(&outpoint);
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Witness Transactions and Fee Bumping
+// ============================================================================
+
+impl BitcoinAnchorTracker<TxoSeal> {
+    /// Register a composed witness transaction for an operation
+    ///
+    /// Stores the witness as `Tentative`, records its anchor and method, and
+    /// keeps the builder so the transaction can be replaced with
+    /// `bump_witness_fee()` until it is mined.
+    ///
+    /// # Returns
+    /// Txid of the witness transaction
+    pub fn add_witness_tx(
+        &mut self,
+        opid: Opid,
+        builder: WitnessTxBuilder,
+        witness: &WitnessTx,
+    ) -> Txid {
+        let tx: Tx = witness.psbt.to_unsigned_tx().into();
+        let txid = tx.txid();
+
+        self.add_witness(opid, txid, &tx, &witness.anchor, WitnessStatus::Tentative);
+        self.add_anchor_with_method(opid, witness.anchor.clone(), witness.anchor_method);
+        self.pending_witnesses.insert(opid, builder);
+
+        txid
+    }
+
+    /// Replace an unconfirmed witness transaction with a higher-fee one (RBF)
     ///
-    /// If `conf.persistence_path` is provided, loads from that file and enables
-    /// automatic persistence. Otherwise, creates a new empty in-memory tracker.
+    /// Rebuilds the witness PSBT from the stored builder at `new_fee_rate`,
+    /// spending the same seal outpoints, and re-embeds the same state hash
+    /// into a transaction with a new txid. As BIP125 requires, the absolute
+    /// fee must grow by at least the minimum relay fee for the replacement's
+    /// size. The replacement is registered as `Tentative` and becomes the
+    /// operation's anchor; the original stays tracked until one of them is
+    /// mined (see `confirm_witness()`).
+    ///
+    /// Sign and broadcast the returned PSBT, and pass the result to
+    /// `F1r3flyConsignment::replace_witness()` for consignments not yet
+    /// delivered.
+    ///
+    /// # Errors
+    /// Returns error if the operation has no pending witness, `new_fee_rate`
+    /// doesn't exceed the current rate, the replacement doesn't pay the BIP125
+    /// fee increment, or the replacement can't be built
+    pub fn bump_witness_fee(
+        &mut self,
+        opid: Opid,
+        new_fee_rate: u64,
+    ) -> Result<WitnessTx, BitcoinAnchorError> {
+        let builder = self.pending_witnesses.get(&opid).ok_or_else(|| {
+            BitcoinAnchorError::WitnessNotFound(format!("No pending witness for {}", opid))
+        })?;
+
+        if new_fee_rate <= builder.current_fee_rate() {
+            return Err(BitcoinAnchorError::FeeBumpFailed(format!(
+                "New fee rate {} sat/vB must exceed current {} sat/vB",
+                new_fee_rate,
+                builder.current_fee_rate()
+            )));
+        }
+
+        // The stored builder rebuilds the current witness deterministically
+        let current = builder
+            .clone()
+            .build()
+            .map_err(|e| BitcoinAnchorError::FeeBumpFailed(e.to_string()))?;
+        let builder = builder.clone().fee_rate(new_fee_rate);
+        let replacement = builder
+            .clone()
+            .build()
+            .map_err(|e| BitcoinAnchorError::FeeBumpFailed(e.to_string()))?;
+
+        // BIP125 rule 4: the replacement pays for its own relay bandwidth
+        let min_fee = current.fee.sats() + MIN_FEE_RATE * replacement.vsize;
+        if replacement.fee.sats() < min_fee {
+            return Err(BitcoinAnchorError::FeeBumpFailed(format!(
+                "Replacement fee {} sats below BIP125 minimum {} sats",
+                replacement.fee.sats(),
+                min_fee
+            )));
+        }
+
+        let txid = self.add_witness_tx(opid, builder, &replacement);
+        log::info!(
+            "Witness for {} replaced by {} at {} sat/vB (fee {} sats)",
+            opid,
+            txid,
+            new_fee_rate,
+            replacement.fee.sats()
+        );
+
+        Ok(replacement)
+    }
+
+    /// Mark a witness as mined and archive the transactions it replaced
+    ///
+    /// Every other `Tentative` witness of the same operations conflicts with
+    /// the mined one and can never confirm, so it is archived. The anchor of
+    /// the mined witness becomes the operation's anchor again (the original
+    /// may be mined instead of its replacement), and the pending builder is
+    /// dropped.
+    ///
+    /// # Returns
+    /// Txids of the archived witnesses
+    pub fn confirm_witness(&mut self, txid: Txid, height: NonZeroU64) -> Vec<Txid> {
+        self.update_witness_status(txid, WitnessStatus::Mined(height));
+
+        let mut archived = Vec::new();
+        let opids = self.witness_ops.get(&txid).cloned().unwrap_or_default();
+        let anchor = self.witnesses.get(&txid).map(|w| w.client.clone());
+        for opid in opids {
+            for other in self.op_witnesses.get(&opid).into_iter().flatten() {
+                if *other == txid {
+                    continue;
+                }
+                if let Some(data) = self.witnesses.get_mut(other) {
+                    if data.status == WitnessStatus::Tentative {
+                        data.status = WitnessStatus::Archived;
+                        self.archived_at.entry(*other).or_insert_with(unix_now);
+                        archived.push(*other);
+                        log::debug!("Witness {} archived (replaced by {})", other, txid);
+                    }
+                }
+            }
+
+            if let Some(anchor) = &anchor {
+                if self.anchors.get(&opid) != Some(anchor) {
+                    // Same builder layout, so the commitment method is unchanged
+                    let method = self.anchor_methods.get(&opid).copied();
+                    self.anchors.insert(opid, anchor.clone());
+                    if let Some(method) = method {
+                        self.anchor_methods.insert(opid, method);
+                    }
+                }
+            }
+            self.pending_witnesses.remove(&opid);
+        }
+
+        archived
+    }
+
+    /// Update every tracked witness from a chain source
+    ///
+    /// - Confirmed witnesses become `Mined(height)`, archiving the witnesses
+    ///   they replaced (see `confirm_witness()`)
+    /// - Mined witnesses no longer in the best chain go back to `Tentative`;
+    ///   when their block was reorged out (its hash no longer matches the
+    ///   best chain at that height, or wasn't recorded) their operations are
+    ///   marked in `reorged_ops()` and a `Reorged` event is raised
+    /// - Tentative witnesses whose inputs are spent by another confirmed
+    ///   transaction are `Archived` as double-spent
+    ///
+    /// Call `commit_transaction()` afterwards to persist the new statuses.
+    ///
+    /// # Returns
+    /// Status changes, in txid order
+    pub fn sync_witnesses<C: ChainSource>(
+        &mut self,
+        chain: &C,
+    ) -> Result<Vec<SyncEvent>, C::Error> {
+        let mut txids: Vec<Txid> = self.witnesses.keys().copied().collect();
+        txids.sort();
+
+        let mut events = Vec::new();
+        for txid in &txids {
+            let txid = *txid;
+            let current = self.witness_status(txid);
+            match chain.tx_status(txid)? {
+                TxStatus::Confirmed { height, block_hash } => {
+                    let Some(height) = NonZeroU64::new(height) else {
+                        continue;
+                    };
+                    let previous = self.witness_blocks.insert(txid, block_hash);
+                    if previous.is_some_and(|hash| hash != block_hash) {
+                        log::debug!("Witness {} re-included in block {}", txid, block_hash);
+                    }
+                    if current != WitnessStatus::Mined(height) {
+                        let replaced = self.confirm_witness(txid, height);
+                        events.push(SyncEvent::Mined {
+                            txid,
+                            height: height.get(),
+                        });
+                        events.extend(replaced.into_iter().map(|other| SyncEvent::Replaced {
+                            txid: other,
+                            by: txid,
+                        }));
+                    }
+                }
+                TxStatus::Mempool | TxStatus::Unknown => {
+                    if let WitnessStatus::Mined(height) = current {
+                        self.update_witness_status(txid, WitnessStatus::Tentative);
+                        let reorged = match self.witness_blocks.remove(&txid) {
+                            Some(hash) => chain.block_hash(height.get())? != Some(hash),
+                            // Mined before block hashes were recorded: assume the worst
+                            None => true,
+                        };
+                        if reorged {
+                            let opids = self.witness_ops.get(&txid).cloned().unwrap_or_default();
+                            log::warn!(
+                                "Witness {} reorged out of block {} ({} operation(s) affected)",
+                                txid,
+                                height,
+                                opids.len()
+                            );
+                            self.reorged_ops.extend(opids);
+                            events.push(SyncEvent::Reorged {
+                                txid,
+                                height: height.get(),
+                            });
+                        } else {
+                            events.push(SyncEvent::Unconfirmed {
+                                txid,
+                                height: height.get(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        // Double-spends, once replacements have been resolved
+        for txid in txids {
+            if self.witness_status(txid) != WitnessStatus::Tentative {
+                continue;
+            }
+            let inputs: Vec<_> = self.witnesses[&txid]
+                .published
+                .inputs
+                .iter()
+                .map(|input| input.prev_output)
+                .collect();
+            for outpoint in inputs {
+                let Some(spender) = chain.spender(outpoint)? else {
+                    continue;
+                };
+                if spender == txid {
+                    continue;
+                }
+                if let TxStatus::Confirmed { .. } = chain.tx_status(spender)? {
+                    self.update_witness_status(txid, WitnessStatus::Archived);
+                    log::warn!("Witness {} double-spent by {}", txid, spender);
+                    events.push(SyncEvent::DoubleSpent { txid, by: spender });
+                    break;
+                }
+            }
+        }
+
+        Ok(events)
+    }
+
+    /// Block hash of a mined witness, as last seen by `sync_witnesses()`
+    pub fn witness_block(&self, txid: Txid) -> Option<BlockHash> {
+        self.witness_blocks.get(&txid).copied()
+    }
+
+    /// Operations whose witness was reorged out after being mined
+    ///
+    /// Claims made on these operations (`witness:<id>:<vout>` migrated to the
+    /// real UTXO) are no longer backed by a confirmed transaction. Freeze or
+    /// revert them on F1r3fly (`F1r3flyRgbContract::freeze_claim()` /
+    /// `revert_claim()`), then call `clear_reorged()`.
+    pub fn reorged_ops(&self) -> impl Iterator<Item = Opid> + '_ {
+        self.reorged_ops.iter().copied()
+    }
+
+    /// Check whether an operation's witness was reorged out
+    pub fn is_reorged(&self, opid: &Opid) -> bool {
+        self.reorged_ops.contains(opid)
+    }
+
+    /// Clear the reorg mark of an operation once its claim has been handled
+    ///
+    /// # Returns
+    /// `true` if the operation was marked
+    pub fn clear_reorged(&mut self, opid: &Opid) -> bool {
+        self.reorged_ops.remove(opid)
+    }
+}
+
+// ============================================================================
+// Storage
+// ============================================================================
+
+impl<Seal: StoredSeal> BitcoinAnchorTracker<Seal> {
+    /// Create a new empty tracker with automatic persistence
+    ///
+    /// When `commit_transaction()` is called, data will be automatically
+    /// saved to the specified path, using the backend selected by the path
+    /// (see `StorageBackend::for_path`).
+    pub fn with_persistence<P: Into<PathBuf>>(path: P) -> Self {
+        Self::new().with_store(open_store(path.into()))
+    }
+
+    /// Set or update the persistence path
+    ///
+    /// This enables automatic persistence on `commit_transaction()` calls.
+    /// Releases the lock held on the previous path, if any.
+    pub fn set_persistence_path<P: Into<PathBuf>>(&mut self, path: Option<P>) {
+        self.store = path.map(|p| open_store(p.into()));
+    }
+
+    /// Create a new empty tracker persisting to an encrypted JSON file
+    ///
+    /// # Errors
+    /// `InvalidConfiguration` if `path` selects the append-only log
+    pub fn with_encrypted_persistence<P: Into<PathBuf>>(
+        path: P,
+        key: EncryptionKey,
+    ) -> Result<Self, BitcoinAnchorError> {
+        Ok(Self::new().with_store(open_store_with_key(path.into(), Some(key))?))
+    }
+
+    /// Change the key of the persistence file and its backup
+    ///
+    /// `None` removes encryption; setting a key on a plaintext store
+    /// encrypts it. The stored file is rewritten right away and later commits
+    /// use the new key. Nothing is lost if interrupted: the file is readable
+    /// with either the old key (before the rewrite) or the new one.
+    ///
+    /// # Errors
+    /// `InvalidConfiguration` without a store or for append-only logs;
+    /// `Encryption` if the stored file can't be opened with the current key
+    pub fn rekey(&mut self, key: Option<EncryptionKey>) -> Result<(), BitcoinAnchorError> {
+        let store = self.store.as_mut().ok_or_else(|| {
+            BitcoinAnchorError::InvalidConfiguration("No persistence store to rekey".to_string())
+        })?;
+        store.rekey(key)
+    }
+
+    /// Load tracker from disk
+    ///
+    /// Loads a previously stored tracker from the store selected by `path`
+    /// (JSON, append-only log or in-memory), restoring all seals, witnesses,
+    /// and their relationships. The loaded tracker will have automatic
+    /// persistence enabled using the store it was loaded from.
+    ///
+    /// If a JSON file is missing or can't be parsed but `<path>.bak` can, the
+    /// backup is loaded instead (logged as an error).
+    ///
+    /// # Arguments
+    /// - `path`: File path to load from
+    ///
+    /// # Returns
+    /// Loaded tracker on success, error if file doesn't exist or deserialization fails
+    ///
+    /// # Example
+    /// ```ignore
+    /// let tracker = BitcoinAnchorTracker::load_from_disk("./tracker_data.json")?;
+    /// // Future commit_transaction() calls will auto-save to ./tracker_data.json
+    /// ```
+    pub fn load_from_disk<P: AsRef<Path>>(path: P) -> Result<Self, BitcoinAnchorError> {
+        Self::load_with_key(path.as_ref(), None)
+    }
+
+    /// Load tracker from an encrypted JSON file
+    ///
+    /// Like `load_from_disk()`; later commits stay encrypted under `key`.
+    /// A plaintext file is rejected; encrypt it first with `rekey_file()`.
+    ///
+    /// # Errors
+    /// `Encryption` if the key is wrong, the file was modified or it is
+    /// not encrypted
+    pub fn load_encrypted<P: AsRef<Path>>(
+        path: P,
+        key: EncryptionKey,
+    ) -> Result<Self, BitcoinAnchorError> {
+        Self::load_with_key(path.as_ref(), Some(key))
+    }
+
+    fn load_with_key(path: &Path, key: Option<EncryptionKey>) -> Result<Self, BitcoinAnchorError> {
+        let mut store = open_store_with_key::<Seal>(path.to_path_buf(), key)?;
+        let mut tracker = match store.load()? {
+            Some(tracker) => tracker,
+            // A fresh in-memory store has nothing to load yet
+            None if store.path().is_none() => Self::new(),
+            None => {
+                return Err(BitcoinAnchorError::PersistenceError(format!(
+                    "{:?} not found",
+                    path
+                )))
+            }
+        };
+
+        // Enable automatic persistence using the store we loaded from
+        tracker.store = Some(store);
+
+        let report = tracker.check_consistency();
+        if !report.is_consistent() {
+            log::warn!(
+                "Tracker {:?} has inconsistent relations ({}); call repair()",
+                path,
+                report
+            );
+        }
+
+        Ok(tracker)
+    }
+
+    /// Read a JSON tracker, decrypting with `key`, migrating older schemas
+    /// and falling back to `<path>.bak` if the file is unreadable
+    pub(crate) fn read_json_with_backup(
+        path: &Path,
+        key: Option<&EncryptionKey>,
+    ) -> Result<Self, BitcoinAnchorError> {
+        match Self::read_json(path, key) {
+            Ok(tracker) => Ok(tracker),
+            // A newer file is intact, and a wrong key must not fall back to an
+            // older plaintext backup; either would silently roll it back
+            Err(e @ BitcoinAnchorError::UnsupportedVersion(_))
+            | Err(e @ BitcoinAnchorError::Encryption(_)) => Err(e),
+            Err(e) => {
+                let backup = sidecar_path(path, "bak");
+                let Ok(tracker) = Self::read_json(&backup, key) else {
+                    return Err(e);
+                };
+                log::error!(
+                    "Tracker {:?} unreadable ({}); loaded backup {:?}",
+                    path,
+                    e,
+                    backup
+                );
+                Ok(tracker)
+            }
+        }
+    }
+
+    fn read_json(path: &Path, key: Option<&EncryptionKey>) -> Result<Self, BitcoinAnchorError> {
+        let json = encryption::read_file(path, key)?;
+        let value = serde_json::from_slice(&json)
+            .map_err(|e| BitcoinAnchorError::DeserializationError(e.to_string()))?;
+
+        let (tracker, version) = anchor_schema::upgrade(value)?;
+        if version < TRACKER_SCHEMA_VERSION {
+            log::info!(
+                "Tracker {:?} migrated from schema v{} to v{}; rewritten on next commit",
+                path,
+                version,
+                TRACKER_SCHEMA_VERSION
+            );
+        }
+
+        let mut tracker: Self = serde_json::from_value(tracker).map_err(|e| {
+            BitcoinAnchorError::DeserializationError(format!("schema v{}: {}", version, e))
+        })?;
+        tracker.refresh_indexes();
+        Ok(tracker)
+    }
+
+    /// Split the tracker into keyed records for record-based stores
+    ///
+    /// The witness → operations mapping is not stored; `from_records()`
+    /// rebuilds it from the operation → witnesses records.
+    pub(crate) fn to_records(&self) -> Result<Vec<StoreRecord>, BitcoinAnchorError> {
+        let mut records = Vec::new();
+        let mut push =
+            |kind, key: Vec<u8>, value: Vec<u8>| records.push(StoreRecord { kind, key, value });
+
+        for (opid, seals) in &self.seals {
+            let value = encode_with(|writer| {
+                let mut writer = (seals.len() as u16).strict_encode(writer)?;
+                for (pos, seal) in seals.iter() {
+                    writer = pos.strict_encode(writer)?;
+                    writer = seal.strict_encode(writer)?;
+                }
+                Ok(writer)
+            })?;
+            push(RecordKind::Seals, encode(opid)?, value);
+        }
+        for (wid, data) in &self.witnesses {
+            // WitnessStatus has no strict encoding
+            let status = serde_json::to_vec(&data.status)
+                .map_err(|e| BitcoinAnchorError::SerializationError(e.to_string()))?;
+            let value = encode_with(|writer| {
+                let writer = data.published.strict_encode(writer)?;
+                let writer = data.client.strict_encode(writer)?;
+                let status = SmallBlob::try_from(status).map_err(std::io::Error::other)?;
+                status.strict_encode(writer)
+            })?;
+            push(RecordKind::Witness, encode(wid)?, value);
+        }
+        for (opid, wids) in &self.op_witnesses {
+            let value = encode_with(|writer| {
+                let mut writer = (wids.len() as u16).strict_encode(writer)?;
+                for wid in wids {
+                    writer = wid.strict_encode(writer)?;
+                }
+                Ok(writer)
+            })?;
+            push(RecordKind::OpWitnesses, encode(opid)?, value);
+        }
+        for (opid, anchor) in &self.anchors {
+            push(RecordKind::Anchor, encode(opid)?, encode(anchor)?);
+        }
+        for (opid, method) in &self.anchor_methods {
+            let wire = AnchorMethodWire::from(*method);
+            push(RecordKind::AnchorMethod, encode(opid)?, encode(&wire)?);
+        }
+        for (opid, builder) in &self.pending_witnesses {
+            let value = serde_json::to_vec(builder)
+                .map_err(|e| BitcoinAnchorError::SerializationError(e.to_string()))?;
+            push(RecordKind::PendingWitness, encode(opid)?, value);
+        }
+        for (wid, block_hash) in &self.witness_blocks {
+            push(RecordKind::WitnessBlock, encode(wid)?, encode(block_hash)?);
+        }
+        for opid in &self.reorged_ops {
+            push(RecordKind::ReorgedOp, encode(opid)?, Vec::new());
+        }
+        for (wid, since) in &self.archived_at {
+            push(RecordKind::ArchivedAt, encode(wid)?, encode(since)?);
+        }
+        for (outpoint, cells) in &self.outpoint_seals {
+            let value = encode_with(|writer| {
+                let mut writer = (cells.len() as u16).strict_encode(writer)?;
+                for (opid, pos) in cells {
+                    writer = opid.strict_encode(writer)?;
+                    writer = pos.strict_encode(writer)?;
+                }
+                Ok(writer)
+            })?;
+            push(RecordKind::OutpointSeals, encode(outpoint)?, value);
+        }
+        for (outpoint, wids) in &self.outpoint_spenders {
+            let value = encode_with(|writer| {
+                let mut writer = (wids.len() as u16).strict_encode(writer)?;
+                for wid in wids {
+                    writer = wid.strict_encode(writer)?;
+                }
+                Ok(writer)
+            })?;
+            push(RecordKind::OutpointSpenders, encode(outpoint)?, value);
+        }
+
+        Ok(records)
+    }
+
+    /// Rebuild a tracker from records produced by `to_records()`
+    ///
+    /// Outpoint indexes missing from older logs are rebuilt.
+    pub(crate) fn from_records(
+        records: impl IntoIterator<Item = StoreRecord>,
+    ) -> Result<Self, BitcoinAnchorError> {
+        let mut tracker = Self::new();
+        for record in records {
+            let value = &record.value;
+            match record.kind {
+                RecordKind::Seals => {
+                    let mut reader = anchor_store::reader(value);
+                    let count = u16::strict_decode(&mut reader).map_err(decode_err)?;
+                    let mut seals = SmallOrdMap::new();
+                    for _ in 0..count {
+                        let pos = u16::strict_decode(&mut reader).map_err(decode_err)?;
+                        let seal =
+                            Seal::Definition::strict_decode(&mut reader).map_err(decode_err)?;
+                        seals
+                            .insert(pos, seal)
+                            .map_err(|e| BitcoinAnchorError::DeserializationError(e.to_string()))?;
+                    }
+                    tracker.seals.insert(decode(&record.key)?, seals);
+                }
+                RecordKind::Witness => {
+                    let mut reader = anchor_store::reader(value);
+                    let published =
+                        Seal::Published::strict_decode(&mut reader).map_err(decode_err)?;
+                    let client = Seal::Client::strict_decode(&mut reader).map_err(decode_err)?;
+                    let status = SmallBlob::strict_decode(&mut reader).map_err(decode_err)?;
+                    let status = serde_json::from_slice(status.as_slice())
+                        .map_err(|e| BitcoinAnchorError::DeserializationError(e.to_string()))?;
+                    tracker.witnesses.insert(
+                        decode(&record.key)?,
+                        WitnessData {
+                            published,
+                            client,
+                            status,
+                        },
+                    );
+                }
+                RecordKind::OpWitnesses => {
+                    let mut reader = anchor_store::reader(value);
+                    let count = u16::strict_decode(&mut reader).map_err(decode_err)?;
+                    let wids = (0..count)
+                        .map(|_| Seal::WitnessId::strict_decode(&mut reader).map_err(decode_err))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    tracker.op_witnesses.insert(decode(&record.key)?, wids);
+                }
+                RecordKind::Anchor => {
+                    tracker.anchors.insert(decode(&record.key)?, decode(value)?);
+                }
+                RecordKind::AnchorMethod => {
+                    let method = AnchorMethod::try_from(decode::<AnchorMethodWire>(value)?)
+                        .map_err(|e| BitcoinAnchorError::DeserializationError(e.to_string()))?;
+                    tracker.anchor_methods.insert(decode(&record.key)?, method);
+                }
+                RecordKind::PendingWitness => {
+                    let builder = serde_json::from_slice(value)
+                        .map_err(|e| BitcoinAnchorError::DeserializationError(e.to_string()))?;
+                    tracker
+                        .pending_witnesses
+                        .insert(decode(&record.key)?, builder);
+                }
+                RecordKind::WitnessBlock => {
+                    tracker
+                        .witness_blocks
+                        .insert(decode(&record.key)?, decode(value)?);
+                }
+                RecordKind::ReorgedOp => {
+                    tracker.reorged_ops.insert(decode(&record.key)?);
+                }
+                RecordKind::ArchivedAt => {
+                    tracker
+                        .archived_at
+                        .insert(decode(&record.key)?, decode(value)?);
+                }
+                RecordKind::OutpointSeals => {
+                    let mut reader = anchor_store::reader(value);
+                    let count = u16::strict_decode(&mut reader).map_err(decode_err)?;
+                    let cells = (0..count)
+                        .map(|_| {
+                            let opid = Opid::strict_decode(&mut reader)?;
+                            let pos = u16::strict_decode(&mut reader)?;
+                            Ok((opid, pos))
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(decode_err)?;
+                    tracker.outpoint_seals.insert(decode(&record.key)?, cells);
+                }
+                RecordKind::OutpointSpenders => {
+                    let mut reader = anchor_store::reader(value);
+                    let count = u16::strict_decode(&mut reader).map_err(decode_err)?;
+                    let wids = (0..count)
+                        .map(|_| Seal::WitnessId::strict_decode(&mut reader).map_err(decode_err))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    tracker.outpoint_spenders.insert(decode(&record.key)?, wids);
+                }
+            }
+        }
+
+        for (opid, wids) in &tracker.op_witnesses {
+            for wid in wids {
+                let opids = tracker.witness_ops.entry(*wid).or_insert_with(Vec::new);
+                if !opids.contains(opid) {
+                    opids.push(*opid);
+                }
+            }
+        }
+        tracker.refresh_indexes();
+
+        Ok(tracker)
+    }
+}
+
+// ============================================================================
+// Pruning and Cold Storage
+// ============================================================================
+
+impl<Seal: StoredSeal> BitcoinAnchorTracker<Seal> {
+    /// Remove old records according to `policy`
+    ///
+    /// Removes:
+    /// - witnesses `Archived` for at least `policy.archived_for` (e.g. replaced
+    ///   by RBF or double-spent); witnesses archived before timestamps were
+    ///   recorded start aging now
+    /// - with `policy.superseded_ops`, operations whose own witnesses are all
+    ///   settled and whose seals were all spent by mined witnesses of other
+    ///   operations, together with witnesses used only by them
+    /// - anchors, methods and fee-bump builders of operations left without
+    ///   any witness
+    ///
+    /// With `policy.archive_path`, the pruned records are first appended to
+    /// that file; `restore_pruned()` brings them back, e.g. when a dispute
+    /// needs old proofs. Call `commit_transaction()` afterwards to persist
+    /// the smaller tracker.
+    ///
+    /// # Errors
+    /// Returns the archive's load or write error; the tracker is unchanged
+    /// in that case
+    pub fn prune(&mut self, policy: &PrunePolicy) -> Result<PruneReport, BitcoinAnchorError> {
+        let now = policy.now_secs();
+        for (wid, data) in &self.witnesses {
+            if data.status == WitnessStatus::Archived {
+                self.archived_at.entry(*wid).or_insert(now);
+            }
+        }
+
+        let min_age = policy.archived_for.as_secs();
+        let mut wids: HashSet<Seal::WitnessId> = self
+            .archived_at
+            .iter()
+            .filter(|(wid, since)| {
+                self.witness_status(**wid) == WitnessStatus::Archived
+                    && now.saturating_sub(**since) >= min_age
+            })
+            .map(|(wid, _)| *wid)
+            .collect();
+
+        let opids: HashSet<Opid> = match policy.superseded_ops {
+            true => self
+                .seals
+                .keys()
+                .filter(|opid| self.is_superseded(**opid))
+                .copied()
+                .collect(),
+            false => HashSet::new(),
+        };
+        for opid in &opids {
+            for wid in self.op_witnesses.get(opid).into_iter().flatten() {
+                let ops = self.witness_ops.get(wid).into_iter().flatten();
+                if ops.clone().all(|op| opids.contains(op)) {
+                    wids.insert(*wid);
+                }
+            }
+        }
+
+        // Operations whose every witness is pruned keep no usable anchor
+        let orphaned: HashSet<Opid> = self
+            .op_witnesses
+            .iter()
+            .filter(|(opid, own)| {
+                !opids.contains(*opid)
+                    && !own.is_empty()
+                    && own.iter().all(|wid| wids.contains(wid))
+            })
+            .map(|(opid, _)| *opid)
+            .collect();
+
+        let mut report = PruneReport {
+            witnesses: wids.len(),
+            operations: opids.len(),
+            anchors: opids
+                .iter()
+                .chain(&orphaned)
+                .filter(|opid| self.anchors.contains_key(opid))
+                .count(),
+            archive: policy.archive_path.clone(),
+        };
+        if report.is_empty() {
+            report.archive = None;
+            return Ok(report);
+        }
+
+        // Archive first, so a failed write leaves the tracker untouched
+        if let Some(path) = &policy.archive_path {
+            let pruned = self.extract(&opids, &wids, &orphaned);
+            let mut store = open_store::<Seal>(path.clone());
+            let mut archive = store.load()?.unwrap_or_default();
+            archive.merge(pruned);
+            store.commit(&archive)?;
+        }
+
+        self.remove_records(&opids, &wids, &orphaned);
+
+        log::info!("Tracker pruned: {}", report);
+        Ok(report)
+    }
+
+    /// Remove every record of an operation
+    ///
+    /// Drops its seals, anchor, method and fee-bump builder, and the
+    /// witnesses no other operation uses. Used to roll back an operation
+    /// that failed half-way (see `F1r3flyRgbContract::send()`); call
+    /// `commit_transaction()` afterwards if it was already persisted.
+    ///
+    /// # Returns
+    /// `true` if the tracker held any record of `opid`
+    pub fn discard_op(&mut self, opid: Opid) -> bool {
+        let known = self.seals.contains_key(&opid)
+            || self.op_witnesses.contains_key(&opid)
+            || self.anchors.contains_key(&opid)
+            || self.pending_witnesses.contains_key(&opid);
+        if !known {
+            return false;
+        }
+
+        let wids: HashSet<Seal::WitnessId> = self
+            .op_witnesses
+            .get(&opid)
+            .into_iter()
+            .flatten()
+            .filter(|wid| {
+                let mut ops = self.witness_ops.get(wid).into_iter().flatten();
+                ops.all(|op| *op == opid)
+            })
+            .copied()
+            .collect();
+        self.remove_records(&HashSet::from([opid]), &wids, &HashSet::new());
+
+        log::debug!("Operation {} discarded ({} witness(es))", opid, wids.len());
+        true
+    }
+
+    /// Remove records of `opids` and `wids`, plus anchors of `orphaned`
+    fn remove_records(
+        &mut self,
+        opids: &HashSet<Opid>,
+        wids: &HashSet<Seal::WitnessId>,
+        orphaned: &HashSet<Opid>,
+    ) {
+        for wid in wids {
+            self.witnesses.remove(wid);
+            self.witness_blocks.remove(wid);
+            self.archived_at.remove(wid);
+            for opid in self.witness_ops.remove(wid).unwrap_or_default() {
+                if let Some(own) = self.op_witnesses.get_mut(&opid) {
+                    own.retain(|w| w != wid);
+                    if own.is_empty() {
+                        self.op_witnesses.remove(&opid);
+                    }
+                }
+            }
+        }
+        for opid in opids {
+            self.seals.remove(opid);
+            self.reorged_ops.remove(opid);
+            for wid in self.op_witnesses.remove(opid).unwrap_or_default() {
+                if let Some(ops) = self.witness_ops.get_mut(&wid) {
+                    ops.retain(|op| op != opid);
+                }
+            }
+        }
+        for opid in opids.iter().chain(orphaned) {
+            self.anchors.remove(opid);
+            self.anchor_methods.remove(opid);
+            self.pending_witnesses.remove(opid);
+        }
+        self.rebuild_indexes();
+    }
+
+    /// Bring records pruned to a cold-storage file back into the tracker
+    ///
+    /// Combined with `merge()`: records still present in the tracker keep
+    /// their seals and anchors, witness statuses only advance.
+    ///
+    /// # Errors
+    /// Returns error if the archive can't be loaded
+    pub fn restore_pruned<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<PruneReport, BitcoinAnchorError> {
+        let path = path.as_ref();
+        let Some(archive) = open_store::<Seal>(path.to_path_buf()).load()? else {
+            return Err(BitcoinAnchorError::PersistenceError(format!(
+                "{:?} not found",
+                path
+            )));
+        };
+        let merged = self.merge(archive);
+        let report = PruneReport {
+            witnesses: merged.witnesses_added,
+            operations: merged.operations_added,
+            anchors: merged.anchors_added,
+            archive: Some(path.to_path_buf()),
+        };
+        log::info!("Restored from cold storage: {}", report);
+        Ok(report)
+    }
+
+    /// Whether every seal of `opid` was spent by a mined witness of another
+    /// operation, while its own witnesses are settled
+    fn is_superseded(&self, opid: Opid) -> bool {
+        let own = self.op_witnesses.get(&opid).cloned().unwrap_or_default();
+        if own.is_empty() || self.reorged_ops.contains(&opid) {
+            return false;
+        }
+        if own
+            .iter()
+            .any(|wid| self.witness_status(*wid) == WitnessStatus::Tentative)
+        {
+            return false;
+        }
+        let mined: Vec<_> = own
+            .iter()
+            .copied()
+            .filter(|wid| matches!(self.witness_status(*wid), WitnessStatus::Mined(_)))
+            .collect();
+
+        let Some(seals) = self.seals.get(&opid).filter(|seals| !seals.is_empty()) else {
+            return false;
+        };
+        seals.values().all(|seal| {
+            let outpoints = Seal::seal_outpoints(seal, &mined);
+            !outpoints.is_empty()
+                && outpoints.iter().all(|outpoint| {
+                    self.outpoint_spenders
+                        .get(outpoint)
+                        .into_iter()
+                        .flatten()
+                        .any(|wid| {
+                            !own.contains(wid)
+                                && matches!(self.witness_status(*wid), WitnessStatus::Mined(_))
+                        })
+                })
+        })
+    }
+
+    /// Copy the records `prune()` is about to remove into a new tracker
+    fn extract(
+        &self,
+        opids: &HashSet<Opid>,
+        wids: &HashSet<Seal::WitnessId>,
+        orphaned: &HashSet<Opid>,
+    ) -> Self {
+        let mut pruned = Self::new();
+        for opid in opids {
+            if let Some(seals) = self.seals.get(opid) {
+                pruned.seals.insert(*opid, seals.clone());
+            }
+            if self.reorged_ops.contains(opid) {
+                pruned.reorged_ops.insert(*opid);
+            }
+            // Keep relations to shared witnesses too, with a copy of their data
+            for wid in self.op_witnesses.get(opid).into_iter().flatten() {
+                pruned.copy_witness(self, *wid);
+                pruned.link(*opid, *wid);
+            }
+        }
+        for wid in wids {
+            pruned.copy_witness(self, *wid);
+            for opid in self.witness_ops.get(wid).into_iter().flatten() {
+                pruned.link(*opid, *wid);
+            }
+        }
+        for opid in opids.iter().chain(orphaned) {
+            if let Some(anchor) = self.anchors.get(opid) {
+                pruned.anchors.insert(*opid, anchor.clone());
+            }
+            if let Some(method) = self.anchor_methods.get(opid) {
+                pruned.anchor_methods.insert(*opid, *method);
+            }
+            if let Some(builder) = self.pending_witnesses.get(opid) {
+                pruned.pending_witnesses.insert(*opid, builder.clone());
+            }
+        }
+        pruned.rebuild_indexes();
+        pruned
+    }
+
+    fn copy_witness(&mut self, from: &Self, wid: Seal::WitnessId) {
+        if let Some(data) = from.witnesses.get(&wid) {
+            self.witnesses.insert(wid, data.clone());
+        }
+        if let Some(block_hash) = from.witness_blocks.get(&wid) {
+            self.witness_blocks.insert(wid, *block_hash);
+        }
+        if let Some(since) = from.archived_at.get(&wid) {
+            self.archived_at.insert(wid, *since);
+        }
+    }
+
+    /// Record an op ↔ witness relation in both mappings, once
+    fn link(&mut self, opid: Opid, wid: Seal::WitnessId) {
+        let wids = self.op_witnesses.entry(opid).or_default();
+        if !wids.contains(&wid) {
+            wids.push(wid);
+        }
+        let opids = self.witness_ops.entry(wid).or_default();
+        if !opids.contains(&opid) {
+            opids.push(opid);
+        }
+    }
+}
+
+// ============================================================================
+// Merge, Export and Import
+// ============================================================================
+
+impl<Seal: StoredSeal> BitcoinAnchorTracker<Seal> {
+    /// Self-contained copy of the given operations
+    ///
+    /// Includes their seals, anchors and methods, fee-bump builders, reorg
+    /// flags, and every witness they reference with its data. Relations to
+    /// operations not exported are left out, so the bundle is consistent on
+    /// its own. Save it with `save()` (or any `AnchorStore`) and combine it on
+    /// another device with `import_bundle()` or `merge()`.
+    ///
+    /// Unknown operations are skipped with a warning.
+    pub fn export_ops(&self, opids: impl IntoIterator<Item = Opid>) -> Self {
+        let mut bundle = Self::new();
+        for opid in opids {
+            let known = self.seals.contains_key(&opid)
+                || self.op_witnesses.contains_key(&opid)
+                || self.anchors.contains_key(&opid);
+            if !known {
+                log::warn!("Export skipped unknown operation {}", opid);
+                continue;
+            }
+
+            if let Some(seals) = self.seals.get(&opid) {
+                bundle.seals.insert(opid, seals.clone());
+            }
+            for wid in self.op_witnesses.get(&opid).into_iter().flatten() {
+                bundle.copy_witness(self, *wid);
+                bundle.link(opid, *wid);
+            }
+            if let Some(anchor) = self.anchors.get(&opid) {
+                bundle.anchors.insert(opid, anchor.clone());
+            }
+            if let Some(method) = self.anchor_methods.get(&opid) {
+                bundle.anchor_methods.insert(opid, *method);
+            }
+            if let Some(builder) = self.pending_witnesses.get(&opid) {
+                bundle.pending_witnesses.insert(opid, builder.clone());
+            }
+            if self.reorged_ops.contains(&opid) {
+                bundle.reorged_ops.insert(opid);
+            }
+        }
+        bundle.rebuild_indexes();
+        bundle
+    }
+
+    /// Merge a bundle saved by `export_ops()` (or a whole tracker) from disk
+    ///
+    /// # Errors
+    /// Returns error if `path` can't be loaded
+    pub fn import_bundle<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<MergeReport, BitcoinAnchorError> {
+        let path = path.as_ref();
+        let Some(bundle) = open_store::<Seal>(path.to_path_buf()).load()? else {
+            return Err(BitcoinAnchorError::PersistenceError(format!(
+                "{:?} not found",
+                path
+            )));
+        };
+        Ok(self.merge(bundle))
+    }
+
+    /// Combine another tracker into this one
+    ///
+    /// - Operations, witnesses, anchors and relations only `other` knows
+    ///   are added
+    /// - For witnesses both know, the most advanced status wins
+    ///   (`Tentative` < `Archived` < `Mined`; for two different mined
+    ///   heights ours is kept and the witness reported)
+    /// - Different seal definitions for the same operation are reported and
+    ///   ours are kept; nothing is overwritten silently
+    /// - Different anchors for the same operation are resolved in favour of
+    ///   the one of a mined witness, or reported and ours kept
+    ///
+    /// Call `commit_transaction()` afterwards to persist the result.
+    pub fn merge(&mut self, other: Self) -> MergeReport {
+        let mut report = MergeReport::default();
+
+        for (opid, seals) in other.seals {
+            match self.seals.entry(opid) {
+                Entry::Vacant(entry) => {
+                    entry.insert(seals);
+                    report.operations_added += 1;
+                }
+                Entry::Occupied(entry) => {
+                    if !same_encoding(entry.get(), &seals) {
+                        report.seal_conflicts.push(opid);
+                    }
+                }
+            }
+        }
+
+        for (wid, data) in other.witnesses {
+            let theirs = data.status;
+            match self.witnesses.entry(wid) {
+                Entry::Vacant(entry) => {
+                    entry.insert(data);
+                    report.witnesses_added += 1;
+                }
+                Entry::Occupied(mut entry) => {
+                    let ours = entry.get().status;
+                    if ours == theirs {
+                        continue;
+                    }
+                    if status_rank(theirs) > status_rank(ours) {
+                        entry.get_mut().status = theirs;
+                        report.witnesses_advanced += 1;
+                    } else if status_rank(theirs) == status_rank(ours) {
+                        report.status_conflicts.push(wid.to_string());
+                        continue;
+                    } else {
+                        continue;
+                    }
+                }
+            }
+            self.stamp_archived(wid, theirs);
+            if let Some(since) = other.archived_at.get(&wid) {
+                self.archived_at.insert(wid, *since);
+            }
+            if let Some(block_hash) = other.witness_blocks.get(&wid) {
+                self.witness_blocks.insert(wid, *block_hash);
+            }
+        }
+
+        for (opid, wids) in other.op_witnesses {
+            for wid in wids {
+                self.link(opid, wid);
+            }
+        }
+        for (wid, opids) in other.witness_ops {
+            for opid in opids {
+                self.link(opid, wid);
+            }
+        }
+
+        for (opid, anchor) in other.anchors {
+            let method = other.anchor_methods.get(&opid).copied();
+            match self.anchors.get(&opid) {
+                None => report.anchors_added += 1,
+                Some(ours) if same_encoding(ours, &anchor) => continue,
+                Some(ours) => {
+                    if self.mined_anchor(opid, ours) || !self.mined_anchor(opid, &anchor) {
+                        report.anchor_conflicts.push(opid);
+                        continue;
+                    }
+                    report.anchors_replaced += 1;
+                }
+            }
+            self.anchors.insert(opid, anchor);
+            match method {
+                Some(method) => self.anchor_methods.insert(opid, method),
+                None => self.anchor_methods.remove(&opid),
+            };
+        }
+
+        for (opid, builder) in other.pending_witnesses {
+            self.pending_witnesses.entry(opid).or_insert(builder);
+        }
+        // Builders are only kept until a witness of the operation is mined
+        let mined: Vec<Opid> = self
+            .pending_witnesses
+            .keys()
+            .filter(|opid| {
+                self.op_witnesses
+                    .get(opid)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|wid| self.witnesses.get(wid))
+                    .any(|data| matches!(data.status, WitnessStatus::Mined(_)))
+            })
+            .copied()
+            .collect();
+        for opid in mined {
+            self.pending_witnesses.remove(&opid);
+        }
+
+        self.reorged_ops.extend(other.reorged_ops);
+        self.rebuild_indexes();
+
+        if !report.is_clean() {
+            log::warn!("Tracker merge found conflicts: {}", report);
+        }
+        report
+    }
+
+    /// Whether `anchor` is the client anchor of a mined witness of `opid`
+    fn mined_anchor(&self, opid: Opid, anchor: &Anchor) -> bool {
+        self.op_witnesses
+            .get(&opid)
+            .into_iter()
+            .flatten()
+            .filter_map(|wid| self.witnesses.get(wid))
+            .any(|data| {
+                matches!(data.status, WitnessStatus::Mined(_))
+                    && same_encoding(&data.client, anchor)
+            })
+    }
+}
+
+/// Outcome of `BitcoinAnchorTracker::merge()`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MergeReport {
+    /// Operations only the other tracker knew
+    pub operations_added: usize,
+
+    /// Witnesses only the other tracker knew
+    pub witnesses_added: usize,
+
+    /// Shared witnesses whose status advanced
+    pub witnesses_advanced: usize,
+
+    /// Anchors only the other tracker knew
+    pub anchors_added: usize,
+
+    /// Anchors replaced by the other tracker's mined one
+    pub anchors_replaced: usize,
+
+    /// Operations with different seal definitions on each side (ours kept)
+    pub seal_conflicts: Vec<Opid>,
+
+    /// Operations with different anchors, neither from a mined witness
+    /// (ours kept)
+    pub anchor_conflicts: Vec<Opid>,
+
+    /// Witnesses mined at different heights on each side (ours kept)
+    pub status_conflicts: Vec<String>,
+}
+
+impl MergeReport {
+    /// Check whether the merge found no conflicts
+    pub fn is_clean(&self) -> bool {
+        self.seal_conflicts.is_empty()
+            && self.anchor_conflicts.is_empty()
+            && self.status_conflicts.is_empty()
+    }
+}
+
+impl fmt::Display for MergeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "added {} operation(s), {} witness(es), {} anchor(s); {} status update(s), \
+             {} anchor replacement(s); {} seal, {} anchor, {} status conflict(s)",
+            self.operations_added,
+            self.witnesses_added,
+            self.anchors_added,
+            self.witnesses_advanced,
+            self.anchors_replaced,
+            self.seal_conflicts.len(),
+            self.anchor_conflicts.len(),
+            self.status_conflicts.len()
+        )
+    }
+}
+
+/// Order of witness statuses when merging: higher wins
+fn status_rank(status: WitnessStatus) -> u8 {
+    match StatusKind::from(status) {
+        StatusKind::Other => 0,
+        StatusKind::Tentative => 1,
+        StatusKind::Archived => 2,
+        StatusKind::Mined => 3,
+    }
+}
+
+/// Compare values of possibly different types by their strict encoding
+fn same_encoding<A: StrictEncode, B: StrictEncode>(a: &A, b: &B) -> bool {
+    match (encode(a), encode(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+impl<Seal: RgbSeal> Default for BitcoinAnchorTracker<Seal> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ============================================================================
+// Pile Trait Implementation
+// ============================================================================
+
+impl<Seal: StoredSeal> Pile for BitcoinAnchorTracker<Seal> {
+    type Seal = Seal;
+    type Conf = AnchorConfig;
+    type Error = BitcoinAnchorError;
+
+    /// Create a new pile with configuration
+    ///
+    /// Creates an empty in-memory tracker. If `conf.persistence_path` is provided,
+    /// automatic persistence will be enabled for `commit_transaction()` calls,
+    /// using the backend selected by the path and encrypted with
+    /// `conf.encryption` if set.
+    fn new(conf: Self::Conf) -> Result<Self, Self::Error> {
+        match conf.persistence_path {
+            Some(path) => Ok(Self::new().with_store(open_store_with_key(path, conf.encryption)?)),
+            None => Ok(Self::new()),
+        }
+    }
+
+    /// Load pile from persistence
+    ///
+    /// If `conf.persistence_path` is provided, loads from the store it selects and
+    /// enables automatic persistence, decrypting with `conf.encryption` if set.
+    /// Otherwise, creates a new empty in-memory tracker.
     fn load(conf: Self::Conf) -> Result<Self, Self::Error> {
         match conf.persistence_path {
-            Some(path) => Self::load_from_disk(path),
+            Some(path) => Self::load_with_key(&path, conf.encryption),
             None => Ok(Self::new()),
         }
     }
@@ -405,13 +2459,10 @@ where
     /// Get published witness (Bitcoin transaction) by ID
     ///
     /// # Panics
-    /// Panics if the witness is not known
+    /// Panics if the witness is not known; use `try_pub_witness()` to get an
+    /// error instead
     fn pub_witness(&self, wid: Seal::WitnessId) -> Seal::Published {
-        self.witnesses
-            .get(&wid)
-            .expect("Witness must exist")
-            .published
-            .clone()
+        self.try_pub_witness(wid).expect("Witness must exist")
     }
 
     /// Check if witness exists
@@ -422,13 +2473,10 @@ where
     /// Get client anchor (Tapret proof) by witness ID
     ///
     /// # Panics
-    /// Panics if the witness is not known
+    /// Panics if the witness is not known; use `try_cli_witness()` to get an
+    /// error instead
     fn cli_witness(&self, wid: Seal::WitnessId) -> Seal::Client {
-        self.witnesses
-            .get(&wid)
-            .expect("Witness must exist")
-            .client
-            .clone()
+        self.try_cli_witness(wid).expect("Witness must exist")
     }
 
     /// Get witness confirmation status
@@ -540,7 +2588,8 @@ where
     /// Add witness data for an operation
     ///
     /// Links an RGB operation to a Bitcoin transaction (witness).
-    /// If the witness already exists, it will be updated.
+    /// If the witness already exists, it will be updated. Idempotent: the
+    /// op ↔ witness relation is recorded only once.
     fn add_witness(
         &mut self,
         opid: Opid,
@@ -549,7 +2598,8 @@ where
         anchor: &Seal::Client,
         status: WitnessStatus,
     ) {
-        // Store witness data
+        // Store witness data, replacing its spends in the index
+        self.index_spends(wid, false);
         self.witnesses.insert(
             wid,
             WitnessData {
@@ -559,51 +2609,100 @@ where
             },
         );
 
+        self.stamp_archived(wid, status);
+
         // Add to op → witnesses mapping
-        self.op_witnesses
-            .entry(opid)
-            .or_insert_with(Vec::new)
-            .push(wid);
+        let wids = self.op_witnesses.entry(opid).or_insert_with(Vec::new);
+        if !wids.contains(&wid) {
+            wids.push(wid);
+        }
 
         // Add to witness → ops mapping
-        self.witness_ops
-            .entry(wid)
-            .or_insert_with(Vec::new)
-            .push(opid);
+        let opids = self.witness_ops.entry(wid).or_insert_with(Vec::new);
+        if !opids.contains(&opid) {
+            opids.push(opid);
+        }
+
+        // Seals on witness outputs now also resolve against this witness
+        self.index_spends(wid, true);
+        self.index_op(opid);
     }
 
     /// Add seal definitions for an operation
     ///
     /// Seals define which Bitcoin UTXOs control the assets created by this operation.
     fn add_seals(&mut self, opid: Opid, seals: SmallOrdMap<u16, Seal::Definition>) {
+        self.unindex_op(opid);
         self.seals.insert(opid, seals);
+        self.index_op(opid);
     }
 
     /// Update witness confirmation status
     ///
-    /// # Panics
-    /// Panics if the witness is not known
+    /// Unknown witnesses are ignored with a warning; use
+    /// `try_update_witness_status()` to get an error instead.
     fn update_witness_status(&mut self, wid: Seal::WitnessId, status: WitnessStatus) {
-        self.witnesses
-            .get_mut(&wid)
-            .expect("Witness must exist")
-            .status = status;
+        if let Err(e) = self.try_update_witness_status(wid, status) {
+            log::warn!("Status update ignored: {}", e);
+        }
     }
 
     /// Commit changes to persistence
     ///
-    /// If a persistence path is configured (via `with_persistence()`, `set_persistence_path()`,
-    /// or `load_from_disk()`), this automatically saves the tracker to disk.
+    /// If a store is configured (via `with_persistence()`, `set_persistence_path()`,
+    /// `with_store()` or `load_from_disk()`), this automatically commits the tracker to it.
     ///
-    /// Errors during save are silently ignored to maintain compatibility with the
-    /// Pile trait. Check logs for persistence errors.
+    /// The Pile trait can't return errors: failures are logged and kept for
+    /// `take_persist_error()`. Use `try_commit_transaction()` to get them
+    /// directly.
     fn commit_transaction(&mut self) {
-        if let Some(path) = &self.persistence_path {
-            if let Err(e) = self.save(path) {
-                log::warn!("Failed to auto-save tracker to {:?}: {}", path, e);
+        if let Err(e) = self.try_commit_transaction() {
+            log::warn!(
+                "Failed to auto-save tracker to {:?}: {}",
+                self.persistence_path(),
+                e
+            );
+            self.persist_error = Some(e);
+        }
+    }
+}
+
+/// Relations flattened from one direction of the op ↔ witness mappings
+struct RelationPairs<W> {
+    /// Pairs in mapping order, including duplicates
+    ordered: Vec<(Opid, W)>,
+    /// Distinct pairs
+    unique: HashSet<(Opid, W)>,
+    /// Number of repeated entries
+    duplicates: usize,
+}
+
+fn relation_pairs<K, V, W>(
+    map: &HashMap<K, Vec<V>>,
+    pair: impl Fn(K, V) -> (Opid, W),
+) -> RelationPairs<W>
+where
+    K: Copy,
+    V: Copy,
+    W: Copy + Eq + std::hash::Hash,
+{
+    let mut ordered = Vec::new();
+    let mut unique = HashSet::new();
+    let mut duplicates = 0;
+    for (key, values) in map {
+        for value in values {
+            let entry = pair(*key, *value);
+            if !unique.insert(entry) {
+                duplicates += 1;
             }
+            ordered.push(entry);
         }
     }
+    RelationPairs {
+        ordered,
+        unique,
+        duplicates,
+    }
 }
 
 /// Wrapper to make Vec iterator into ExactSizeIterator
@@ -628,3 +2727,24 @@ impl<T> ExactSizeIterator for ExactSizeIter<T> {
         self.inner.len()
     }
 }
+
+/// Serde for outpoint-keyed maps as sequences of pairs
+///
+/// Outpoints aren't strings, so they can't be JSON object keys.
+mod outpoint_keyed {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer, V: Serialize>(
+        map: &BTreeMap<Outpoint, V>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(map.iter())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, V: Deserialize<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<Outpoint, V>, D::Error> {
+        Vec::<(Outpoint, V)>::deserialize(deserializer).map(|pairs| pairs.into_iter().collect())
+    }
+}
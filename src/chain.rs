@@ -0,0 +1,393 @@
+//! Blockchain sources for witness status tracking
+//!
+//! `ChainSource` abstracts the few chain queries needed to drive
+//! `WitnessStatus` automatically (see `BitcoinAnchorTracker::sync_witnesses`):
+//! transaction status, tip height, block hash at height and the spender of
+//! an outpoint (for double-spend detection).
+//!
+//! `MockChain` is an in-memory implementation that can broadcast, mine and
+//! reorg blocks. Esplora or Electrum adapters implement the same trait.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use f1r3fly_rgb::{BitcoinAnchorTracker, MockChain, TxoSeal};
+//! # fn example(mut tracker: BitcoinAnchorTracker<TxoSeal>, witness_tx: f1r3fly_rgb::Tx) -> Result<(), Box<dyn std::error::Error>> {
+//! let mut chain = MockChain::new();
+//! chain.broadcast(witness_tx)?;
+//! chain.mine_block();
+//!
+//! for event in tracker.sync_witnesses(&chain)? {
+//!     println!("{}", event);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use amplify::ByteArray;
+use bp::{BlockHash, Outpoint, Tx, Txid};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// Status of a transaction as seen by a chain source
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TxStatus {
+    /// Transaction is not known
+    Unknown,
+
+    /// Transaction is in the mempool
+    Mempool,
+
+    /// Transaction is confirmed in the block at `height`
+    Confirmed { height: u64, block_hash: BlockHash },
+}
+
+/// Source of blockchain data
+///
+/// Heights start at 1 for the first block; a tip height of 0 means no
+/// blocks are known.
+pub trait ChainSource {
+    type Error: std::error::Error;
+
+    /// Get status of a transaction
+    fn tx_status(&self, txid: Txid) -> Result<TxStatus, Self::Error>;
+
+    /// Get current tip height
+    fn tip_height(&self) -> Result<u64, Self::Error>;
+
+    /// Get hash of the block at `height` on the best chain
+    fn block_hash(&self, height: u64) -> Result<Option<BlockHash>, Self::Error>;
+
+    /// Get the transaction (confirmed or in mempool) spending `outpoint`
+    fn spender(&self, outpoint: Outpoint) -> Result<Option<Txid>, Self::Error>;
+}
+
+/// Witness status change produced by `BitcoinAnchorTracker::sync_witnesses`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncEvent {
+    /// Witness confirmed (or re-confirmed after a reorg) at `height`
+    Mined { txid: Txid, height: u64 },
+
+    /// Witness mined at `height` is no longer in the best chain, although
+    /// that block still is
+    Unconfirmed { txid: Txid, height: u64 },
+
+    /// Block at `height` holding the witness was reorged out; the witness's
+    /// operations are marked in `BitcoinAnchorTracker::reorged_ops()`
+    Reorged { txid: Txid, height: u64 },
+
+    /// Witness was archived because a replacement of it got mined
+    Replaced { txid: Txid, by: Txid },
+
+    /// Witness inputs were spent by an unrelated confirmed transaction
+    DoubleSpent { txid: Txid, by: Txid },
+}
+
+impl fmt::Display for SyncEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Mined { txid, height } => write!(f, "{} mined at height {}", txid, height),
+            Self::Unconfirmed { txid, height } => {
+                write!(f, "{} no longer confirmed (was at height {})", txid, height)
+            }
+            Self::Reorged { txid, height } => {
+                write!(f, "{} reorged out of block at height {}", txid, height)
+            }
+            Self::Replaced { txid, by } => write!(f, "{} replaced by {}", txid, by),
+            Self::DoubleSpent { txid, by } => write!(f, "{} double-spent by {}", txid, by),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum MockChainError {
+    /// Transaction spends an outpoint already spent in a confirmed block
+    DoubleSpend { outpoint: Outpoint, by: Txid },
+    /// Reorg deeper than the chain
+    ReorgTooDeep { depth: usize, height: u64 },
+}
+
+impl fmt::Display for MockChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DoubleSpend { outpoint, by } => {
+                write!(f, "Outpoint {} already spent by confirmed {}", outpoint, by)
+            }
+            Self::ReorgTooDeep { depth, height } => {
+                write!(f, "Cannot reorg {} blocks at height {}", depth, height)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MockChainError {}
+
+#[derive(Clone, Debug)]
+struct MockBlock {
+    hash: BlockHash,
+    txs: Vec<Tx>,
+}
+
+/// In-memory blockchain for tests and offline development
+#[derive(Clone, Debug, Default)]
+pub struct MockChain {
+    blocks: Vec<MockBlock>,
+    mempool: Vec<Tx>,
+    /// Number of reorgs so far, mixed into block hashes so replaced blocks differ
+    reorgs: u64,
+}
+
+impl MockChain {
+    /// Create an empty chain
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a transaction to the mempool
+    ///
+    /// Mempool transactions spending the same outpoints are replaced (RBF).
+    ///
+    /// # Errors
+    /// Returns error if an input is already spent in a confirmed block
+    pub fn broadcast(&mut self, tx: Tx) -> Result<Txid, MockChainError> {
+        let txid = tx.txid();
+        if self.find_tx(txid).is_some() {
+            return Ok(txid);
+        }
+
+        for input in &tx.inputs {
+            if let Some(by) = self.confirmed_spender(input.prev_output) {
+                return Err(MockChainError::DoubleSpend {
+                    outpoint: input.prev_output,
+                    by,
+                });
+            }
+        }
+
+        self.evict_conflicts(&tx);
+        self.mempool.push(tx);
+        Ok(txid)
+    }
+
+    /// Mine a block with every mempool transaction
+    ///
+    /// # Returns
+    /// Height of the new block
+    pub fn mine_block(&mut self) -> u64 {
+        let txs = std::mem::take(&mut self.mempool);
+        self.push_block(txs)
+    }
+
+    /// Mine a block with exactly `txs`, bypassing the mempool
+    ///
+    /// Mempool transactions conflicting with `txs` are evicted. Used to
+    /// simulate double-spends mined by someone else.
+    pub fn mine_block_with(&mut self, txs: Vec<Tx>) -> u64 {
+        for tx in &txs {
+            self.mempool.retain(|pending| pending.txid() != tx.txid());
+            self.evict_conflicts(tx);
+        }
+        self.push_block(txs)
+    }
+
+    /// Mine `count` empty blocks
+    pub fn mine_empty(&mut self, count: usize) -> u64 {
+        for _ in 0..count {
+            self.push_block(Vec::new());
+        }
+        self.height()
+    }
+
+    /// Disconnect the last `depth` blocks
+    ///
+    /// Their transactions return to the mempool, as in Bitcoin Core. Blocks
+    /// mined afterwards get new hashes.
+    ///
+    /// # Returns
+    /// Transactions of the disconnected blocks
+    pub fn reorg(&mut self, depth: usize) -> Result<Vec<Tx>, MockChainError> {
+        if depth > self.blocks.len() {
+            return Err(MockChainError::ReorgTooDeep {
+                depth,
+                height: self.height(),
+            });
+        }
+
+        let disconnected = self.blocks.split_off(self.blocks.len() - depth);
+        let txs: Vec<Tx> = disconnected.into_iter().flat_map(|b| b.txs).collect();
+        self.mempool.extend(txs.iter().cloned());
+        self.reorgs += 1;
+
+        Ok(txs)
+    }
+
+    /// Drop a transaction from the mempool
+    pub fn evict(&mut self, txid: Txid) -> bool {
+        let before = self.mempool.len();
+        self.mempool.retain(|tx| tx.txid() != txid);
+        self.mempool.len() != before
+    }
+
+    /// Current tip height
+    pub fn height(&self) -> u64 {
+        self.blocks.len() as u64
+    }
+
+    fn push_block(&mut self, txs: Vec<Tx>) -> u64 {
+        let height = self.height() + 1;
+        let prev = self
+            .blocks
+            .last()
+            .map(|b| b.hash.to_byte_array())
+            .unwrap_or_default();
+
+        let mut hasher = Sha256::new();
+        hasher.update(prev);
+        hasher.update(height.to_le_bytes());
+        hasher.update(self.reorgs.to_le_bytes());
+        for tx in &txs {
+            hasher.update(tx.txid().to_byte_array());
+        }
+        let hash = BlockHash::from_byte_array(<[u8; 32]>::from(hasher.finalize()));
+
+        self.blocks.push(MockBlock { hash, txs });
+        height
+    }
+
+    fn evict_conflicts(&mut self, tx: &Tx) {
+        let txid = tx.txid();
+        self.mempool.retain(|pending| {
+            pending.txid() == txid
+                || !pending
+                    .inputs
+                    .iter()
+                    .any(|a| tx.inputs.iter().any(|b| a.prev_output == b.prev_output))
+        });
+    }
+
+    fn find_tx(&self, txid: Txid) -> Option<TxStatus> {
+        for (index, block) in self.blocks.iter().enumerate() {
+            if block.txs.iter().any(|tx| tx.txid() == txid) {
+                return Some(TxStatus::Confirmed {
+                    height: index as u64 + 1,
+                    block_hash: block.hash,
+                });
+            }
+        }
+        self.mempool
+            .iter()
+            .any(|tx| tx.txid() == txid)
+            .then_some(TxStatus::Mempool)
+    }
+
+    fn confirmed_spender(&self, outpoint: Outpoint) -> Option<Txid> {
+        self.blocks
+            .iter()
+            .flat_map(|block| &block.txs)
+            .find(|tx| tx.inputs.iter().any(|input| input.prev_output == outpoint))
+            .map(|tx| tx.txid())
+    }
+}
+
+impl ChainSource for MockChain {
+    type Error = MockChainError;
+
+    fn tx_status(&self, txid: Txid) -> Result<TxStatus, Self::Error> {
+        Ok(self.find_tx(txid).unwrap_or(TxStatus::Unknown))
+    }
+
+    fn tip_height(&self) -> Result<u64, Self::Error> {
+        Ok(self.height())
+    }
+
+    fn block_hash(&self, height: u64) -> Result<Option<BlockHash>, Self::Error> {
+        Ok(height
+            .checked_sub(1)
+            .and_then(|index| self.blocks.get(index as usize))
+            .map(|block| block.hash))
+    }
+
+    fn spender(&self, outpoint: Outpoint) -> Result<Option<Txid>, Self::Error> {
+        if let Some(txid) = self.confirmed_spender(outpoint) {
+            return Ok(Some(txid));
+        }
+        Ok(self
+            .mempool
+            .iter()
+            .find(|tx| tx.inputs.iter().any(|input| input.prev_output == outpoint))
+            .map(|tx| tx.txid()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amplify::confinement::Confined;
+    use bp::{LockTime, Sats, SeqNo, SigScript, TxIn, TxOut, TxVer, Witness};
+    use bpstd::ScriptPubkey;
+
+    fn tx_spending(seed: u8, value: u64) -> Tx {
+        Tx {
+            version: TxVer::V2,
+            inputs: Confined::try_from(vec![TxIn {
+                prev_output: Outpoint::new(Txid::from_byte_array([seed; 32]), 0),
+                sig_script: SigScript::empty(),
+                sequence: SeqNo::ZERO,
+                witness: Witness::default(),
+            }])
+            .unwrap(),
+            outputs: Confined::try_from(vec![TxOut {
+                value: Sats::from(value),
+                script_pubkey: ScriptPubkey::op_return(&[]),
+            }])
+            .unwrap(),
+            lock_time: LockTime::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_mock_chain_mine_and_reorg() {
+        let mut chain = MockChain::new();
+        let tx = tx_spending(1, 1000);
+        let txid = chain.broadcast(tx.clone()).unwrap();
+        assert_eq!(chain.tx_status(txid).unwrap(), TxStatus::Mempool);
+
+        let height = chain.mine_block();
+        let hash = chain.block_hash(height).unwrap().unwrap();
+        assert_eq!(
+            chain.tx_status(txid).unwrap(),
+            TxStatus::Confirmed {
+                height: 1,
+                block_hash: hash
+            }
+        );
+
+        // Reorg returns the transaction to the mempool; new block differs
+        assert_eq!(chain.reorg(1).unwrap(), vec![tx]);
+        assert_eq!(chain.tx_status(txid).unwrap(), TxStatus::Mempool);
+        chain.mine_block();
+        assert_ne!(chain.block_hash(1).unwrap(), Some(hash));
+        assert!(chain.reorg(5).is_err());
+    }
+
+    #[test]
+    fn test_mock_chain_conflicts() {
+        let mut chain = MockChain::new();
+        let original = tx_spending(1, 1000);
+        let replacement = tx_spending(1, 900);
+        let outpoint = original.inputs[0].prev_output;
+
+        // Mempool replacement (RBF)
+        chain.broadcast(original.clone()).unwrap();
+        chain.broadcast(replacement.clone()).unwrap();
+        assert_eq!(chain.tx_status(original.txid()).unwrap(), TxStatus::Unknown);
+        assert_eq!(chain.spender(outpoint).unwrap(), Some(replacement.txid()));
+
+        // Confirmed spend can't be double-spent
+        chain.mine_block();
+        assert!(matches!(
+            chain.broadcast(original),
+            Err(MockChainError::DoubleSpend { .. })
+        ));
+    }
+}
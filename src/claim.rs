@@ -0,0 +1,507 @@
+//! Automatic claims of received balances
+//!
+//! A transfer credits the recipient at a provisional witness key
+//! (`SealKey::Witness`) because the witness transaction's txid isn't known
+//! when F1r3fly executes it. Once the witness confirms, the recipient calls
+//! `claim` to move the balance to the real UTXO (`SealKey::Outpoint`).
+//!
+//! `ClaimManager` records a pending claim for every accepted transfer, watches
+//! the witness through a `ChainSource` and submits the signed claim once it
+//! has enough confirmations (`DEFAULT_CLAIM_CONFIRMATIONS` unless configured).
+//!
+//! # Idempotency
+//!
+//! Before and after submitting, `process()` checks the state on F1r3fly: a
+//! witness key without balance and a claim record of it on the real UTXO
+//! mean the claim was already applied (e.g. submitted before a crash, or
+//! from another device), so claims can be retried safely. Progress is
+//! persisted after every change.
+//!
+//! Witnesses the contract's tracker marks as reorged out (see
+//! `BitcoinAnchorTracker::reorged_ops`) are not claimed until the mark is
+//! cleared; claims made before the reorg are handled through the tracker.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use f1r3fly_rgb::{ClaimManager, F1r3flyRgbContracts, MockChain};
+//! # async fn example(mut contracts: F1r3flyRgbContracts, bytes: Vec<u8>, chain: MockChain, key: secp256k1::SecretKey) -> Result<(), Box<dyn std::error::Error>> {
+//! let mut claims = ClaimManager::with_persistence("./claims.json", None)?;
+//!
+//! let accepted = contracts.accept_consignment(&bytes).await?;
+//! claims.record(&accepted)?;
+//!
+//! // Later, e.g. on every new block
+//! for event in claims.process(&chain, &mut contracts, &key).await? {
+//!     println!("{}", event);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use bp::{Outpoint, Txid};
+use hypersonic::{ContractId, Opid};
+use secp256k1::SecretKey;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use strict_types::StrictVal;
+
+use crate::chain::{ChainSource, TxStatus};
+use crate::contract::{query_balance, query_claim};
+use crate::{
+    encryption, generate_claim_signature, AcceptedConsignment, EncryptionKey, F1r3flyExecutor,
+    F1r3flyRgbContracts, F1r3flyRgbError, SealKey,
+};
+
+/// Claim file format version written by `ClaimManager`
+pub const CLAIMS_VERSION: u16 = 1;
+
+/// Confirmations a witness needs before `ClaimManager` claims it by default
+///
+/// A claim moves the balance to the real UTXO on F1r3fly, which a Bitcoin
+/// reorg doesn't undo; deeper witnesses are less likely to be reorged out.
+pub const DEFAULT_CLAIM_CONFIRMATIONS: u64 = 6;
+
+/// Progress of a claim
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClaimStatus {
+    /// Waiting for the witness to confirm, or for a retry
+    Pending,
+
+    /// Balance moved to the real UTXO
+    Claimed { real_utxo: SealKey, amount: u64 },
+}
+
+/// Balance received at a witness identifier, to be claimed
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingClaim {
+    /// Contract the balance is in
+    #[serde(with = "contract_id_hex")]
+    pub contract_id: ContractId,
+
+    /// Operation that credited the witness identifier
+    pub opid: Opid,
+
+    /// Provisional witness key holding the balance
+    pub witness_id: SealKey,
+
+    /// Witness transaction paying the recipient output
+    pub witness_txid: Txid,
+
+    /// Recipient output in the witness transaction
+    pub vout: u32,
+
+    /// Amount received
+    pub amount: u64,
+
+    /// Claim progress
+    pub status: ClaimStatus,
+
+    /// Failed claim attempts so far
+    pub attempts: u32,
+
+    /// Error of the last failed attempt
+    pub last_error: Option<String>,
+}
+
+impl PendingClaim {
+    /// Real UTXO the balance is claimed to
+    pub fn real_utxo(&self) -> SealKey {
+        SealKey::from(Outpoint::new(self.witness_txid, self.vout))
+    }
+
+    /// Whether the balance still sits at the witness key
+    pub fn is_pending(&self) -> bool {
+        self.status == ClaimStatus::Pending
+    }
+}
+
+/// Claim progress reported by `ClaimManager::process()`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClaimEvent {
+    /// Claim submitted and applied
+    Claimed {
+        witness_id: SealKey,
+        real_utxo: SealKey,
+        amount: u64,
+    },
+
+    /// Claim was found recorded for the real UTXO already
+    AlreadyClaimed {
+        witness_id: SealKey,
+        real_utxo: SealKey,
+        amount: u64,
+    },
+
+    /// Claim attempt failed; retried on the next `process()`
+    Failed {
+        witness_id: SealKey,
+        attempts: u32,
+        reason: String,
+    },
+}
+
+impl fmt::Display for ClaimEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Claimed {
+                witness_id,
+                real_utxo,
+                amount,
+            } => write!(f, "{} claimed {} to {}", witness_id, amount, real_utxo),
+            Self::AlreadyClaimed {
+                witness_id,
+                real_utxo,
+                amount,
+            } => write!(
+                f,
+                "{} already claimed ({} at {})",
+                witness_id, amount, real_utxo
+            ),
+            Self::Failed {
+                witness_id,
+                attempts,
+                reason,
+            } => write!(
+                f,
+                "{} claim attempt {} failed: {}",
+                witness_id, attempts, reason
+            ),
+        }
+    }
+}
+
+/// Persisted claim file
+#[derive(Serialize, Deserialize)]
+struct ClaimFile {
+    version: u16,
+    claims: Vec<PendingClaim>,
+}
+
+/// Watches received transfers and claims their balances once confirmed
+///
+/// Witnesses are claimed after `DEFAULT_CLAIM_CONFIRMATIONS` confirmations
+/// unless set with `min_confirmations()`, and never while the contract's
+/// tracker marks them as reorged out.
+#[derive(Debug)]
+pub struct ClaimManager {
+    claims: Vec<PendingClaim>,
+    min_confirmations: u64,
+    path: Option<PathBuf>,
+    key: Option<EncryptionKey>,
+}
+
+impl Default for ClaimManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClaimManager {
+    /// Create an in-memory manager claiming after
+    /// `DEFAULT_CLAIM_CONFIRMATIONS` confirmations
+    pub fn new() -> Self {
+        Self {
+            claims: Vec::new(),
+            min_confirmations: DEFAULT_CLAIM_CONFIRMATIONS,
+            path: None,
+            key: None,
+        }
+    }
+
+    /// Create a manager persisting to `path`, loading the claims already there
+    ///
+    /// The file is encrypted if `key` is set (see `encryption`); with a key,
+    /// a plaintext file is rejected until migrated with `rekey_file()`.
+    ///
+    /// # Errors
+    /// Returns error if the existing file can't be read or has an
+    /// unsupported version
+    pub fn with_persistence<P: Into<PathBuf>>(
+        path: P,
+        key: Option<EncryptionKey>,
+    ) -> Result<Self, F1r3flyRgbError> {
+        let path = path.into();
+        let claims = match path.exists() {
+            true => Self::read(&path, key.as_ref())?,
+            false => Vec::new(),
+        };
+
+        Ok(Self {
+            claims,
+            path: Some(path),
+            key,
+            ..Self::new()
+        })
+    }
+
+    /// Confirmations the witness needs before claiming (at least 1)
+    pub fn min_confirmations(mut self, confirmations: u64) -> Self {
+        self.min_confirmations = confirmations.max(1);
+        self
+    }
+
+    /// File the claims are persisted to
+    pub fn persistence_path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// All recorded claims, including completed ones
+    pub fn claims(&self) -> &[PendingClaim] {
+        &self.claims
+    }
+
+    /// Claims still waiting for their balance to move
+    pub fn pending(&self) -> impl Iterator<Item = &PendingClaim> {
+        self.claims.iter().filter(|claim| claim.is_pending())
+    }
+
+    /// Record the claim of a transfer accepted with
+    /// `F1r3flyRgbContracts::accept_consignment()`
+    ///
+    /// Genesis consignments, transfers without a witness mapping and
+    /// transfers already claimed are skipped, as are claims recorded before.
+    ///
+    /// # Returns
+    /// `true` if a new claim was recorded
+    ///
+    /// # Errors
+    /// Returns error if the claims can't be persisted
+    pub fn record(&mut self, accepted: &AcceptedConsignment) -> Result<bool, F1r3flyRgbError> {
+        let (Some(witness_txid), Some(mapping)) = (accepted.witness_id, &accepted.witness_mapping)
+        else {
+            return Ok(false);
+        };
+        let witness_key = mapping.witness_id;
+        if accepted.seal_id != Some(witness_key) {
+            // Balance is no longer at the witness key
+            return Ok(false);
+        }
+        let known = self.claims.iter().any(|claim| {
+            claim.contract_id == accepted.contract_id && claim.witness_id == witness_key
+        });
+        if known {
+            return Ok(false);
+        }
+
+        self.claims.push(PendingClaim {
+            contract_id: accepted.contract_id,
+            opid: accepted.opid,
+            witness_id: witness_key,
+            witness_txid,
+            vout: mapping.expected_vout,
+            amount: accepted.amount,
+            status: ClaimStatus::Pending,
+            attempts: 0,
+            last_error: None,
+        });
+        log::info!(
+            "Recorded claim of {} at {} (witness {})",
+            accepted.amount,
+            witness_key,
+            witness_txid
+        );
+
+        self.persist()?;
+        Ok(true)
+    }
+
+    /// Claim every pending balance whose witness has enough confirmations
+    ///
+    /// Claims signed by `signer` (the key registered as owner of the
+    /// witness identifiers) are submitted through the executor of
+    /// `contracts`, which must know the contracts. Operations whose tracker
+    /// marks them as reorged out (`BitcoinAnchorTracker::is_reorged()`) are
+    /// skipped until the mark is cleared. Failed attempts stay pending with
+    /// their error and are retried on the next call.
+    ///
+    /// # Errors
+    /// Returns error if the chain source fails or progress can't be persisted
+    pub async fn process<C: ChainSource>(
+        &mut self,
+        chain: &C,
+        contracts: &mut F1r3flyRgbContracts,
+        signer: &SecretKey,
+    ) -> Result<Vec<ClaimEvent>, F1r3flyRgbError> {
+        let chain_error =
+            |e: C::Error| F1r3flyRgbError::QueryFailed(format!("Chain source: {}", e));
+        let tip = chain.tip_height().map_err(chain_error)?;
+
+        let mut events = Vec::new();
+        for index in 0..self.claims.len() {
+            let claim = &self.claims[index];
+            if !claim.is_pending() {
+                continue;
+            }
+            let height = match chain.tx_status(claim.witness_txid).map_err(chain_error)? {
+                TxStatus::Confirmed { height, .. } => height,
+                TxStatus::Mempool | TxStatus::Unknown => continue,
+            };
+            if tip.saturating_sub(height) + 1 < self.min_confirmations {
+                continue;
+            }
+            let reorged = contracts
+                .get(&claim.contract_id)
+                .is_some_and(|contract| contract.tracker().is_reorged(&claim.opid));
+            if reorged {
+                log::warn!(
+                    "Not claiming {}: witness {} was reorged out",
+                    claim.witness_id,
+                    claim.witness_txid
+                );
+                continue;
+            }
+
+            let claim = claim.clone();
+            let real_utxo = claim.real_utxo();
+            let executor = contracts.executor_mut();
+            let event = match submit_claim(executor, signer, &claim, &real_utxo).await {
+                Ok((amount, submitted)) => {
+                    self.claims[index].status = ClaimStatus::Claimed { real_utxo, amount };
+                    self.claims[index].last_error = None;
+                    match submitted {
+                        true => ClaimEvent::Claimed {
+                            witness_id: claim.witness_id,
+                            real_utxo,
+                            amount,
+                        },
+                        false => ClaimEvent::AlreadyClaimed {
+                            witness_id: claim.witness_id,
+                            real_utxo,
+                            amount,
+                        },
+                    }
+                }
+                Err(e) => {
+                    let entry = &mut self.claims[index];
+                    entry.attempts += 1;
+                    entry.last_error = Some(e.to_string());
+                    ClaimEvent::Failed {
+                        witness_id: claim.witness_id,
+                        attempts: entry.attempts,
+                        reason: e.to_string(),
+                    }
+                }
+            };
+
+            log::info!("Claim: {}", event);
+            events.push(event);
+            self.persist()?;
+        }
+
+        Ok(events)
+    }
+
+    /// Write the claims to `path`, encrypted if `key` is set
+    ///
+    /// Writes crash-safely, keeping the previous file as `<path>.bak`.
+    pub fn save<P: AsRef<Path>>(
+        &self,
+        path: P,
+        key: Option<&EncryptionKey>,
+    ) -> Result<(), F1r3flyRgbError> {
+        let file = ClaimFile {
+            version: CLAIMS_VERSION,
+            claims: self.claims.clone(),
+        };
+        let json = serde_json::to_vec_pretty(&file)
+            .map_err(|e| F1r3flyRgbError::SerializationError(e.to_string()))?;
+        encryption::write_file(path.as_ref(), &json, key)
+            .map_err(|e| F1r3flyRgbError::PersistenceFailed(e.to_string()))
+    }
+
+    fn persist(&self) -> Result<(), F1r3flyRgbError> {
+        match &self.path {
+            Some(path) => self.save(path, self.key.as_ref()),
+            None => Ok(()),
+        }
+    }
+
+    fn read(
+        path: &Path,
+        key: Option<&EncryptionKey>,
+    ) -> Result<Vec<PendingClaim>, F1r3flyRgbError> {
+        let json = encryption::read_file(path, key)
+            .map_err(|e| F1r3flyRgbError::PersistenceFailed(e.to_string()))?;
+        let file: ClaimFile = serde_json::from_slice(&json)
+            .map_err(|e| F1r3flyRgbError::SerializationError(e.to_string()))?;
+        if file.version > CLAIMS_VERSION {
+            return Err(F1r3flyRgbError::PersistenceFailed(format!(
+                "Claim file version {} is newer than supported {}",
+                file.version, CLAIMS_VERSION
+            )));
+        }
+        Ok(file.claims)
+    }
+}
+
+/// Move a claim's balance to `real_utxo` unless that already happened
+///
+/// # Returns
+/// Claimed amount, and whether a claim was submitted
+async fn submit_claim(
+    executor: &mut F1r3flyExecutor,
+    signer: &SecretKey,
+    claim: &PendingClaim,
+    real_utxo: &SealKey,
+) -> Result<(u64, bool), F1r3flyRgbError> {
+    let pending = query_balance(executor, claim.contract_id, &claim.witness_id).await?;
+    if pending == 0 {
+        // The real UTXO may be funded otherwise; only a claim record from
+        // this witness key shows the claim went through
+        return match query_claim(executor, claim.contract_id, real_utxo).await? {
+            Some(record) if record.witness_id == claim.witness_id => Ok((record.amount, false)),
+            _ => Err(F1r3flyRgbError::ClaimFailed(format!(
+                "No balance at {} and no claim of it recorded for {}",
+                claim.witness_id, real_utxo
+            ))),
+        };
+    }
+
+    let signature = generate_claim_signature(&claim.witness_id, real_utxo, signer)
+        .map_err(|e| F1r3flyRgbError::ClaimFailed(format!("Signing failed: {}", e)))?;
+    let (witness_id, real_utxo) = (claim.witness_id.to_string(), real_utxo.to_string());
+    executor
+        .call_method(
+            claim.contract_id,
+            "claim",
+            &[
+                ("witness_id", StrictVal::from(witness_id.as_str())),
+                ("real_utxo", StrictVal::from(real_utxo.as_str())),
+                ("claimantSignatureHex", StrictVal::from(signature.as_str())),
+            ],
+        )
+        .await?;
+
+    // The contract reports rejections in its return value only
+    let remaining = query_balance(executor, claim.contract_id, &claim.witness_id).await?;
+    if remaining != 0 {
+        return Err(F1r3flyRgbError::ClaimFailed(format!(
+            "{} still holds {} (signer is not the registered owner?)",
+            claim.witness_id, remaining
+        )));
+    }
+
+    Ok((pending, true))
+}
+
+/// Serde adapter storing contract IDs as hex, like `ExecutorSnapshot`
+mod contract_id_hex {
+    use amplify::ByteArray;
+    use hypersonic::ContractId;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(id: &ContractId, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&hex::encode(id.to_byte_array()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ContractId, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        let bytes: [u8; 32] = hex::decode(&id)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| serde::de::Error::custom(format!("Invalid contract ID {}", id)))?;
+        Ok(ContractId::from(bytes))
+    }
+}
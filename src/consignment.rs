@@ -3,22 +3,212 @@
 //! Provides lightweight consignment packages for transferring RGB assets
 //! with F1r3fly state proofs and Bitcoin anchors.
 
+use crate::armor;
+use crate::bitcoin_anchor::AnchorMethod;
+use crate::contract::{query_balance, query_claim};
+use crate::error::InvoiceMismatch;
+use crate::executor::{compute_state_hash, derive_contract_id_from_uri};
+use crate::invoice::GeneratedInvoice;
+use crate::multi_protocol::anchor_commitment;
+use crate::opreturn::verify_opret_proof_in_tx;
+use crate::tapret::{check_tapret_host, verify_tapret_proof_in_tx};
+use crate::validation::{CheckKind, CommitmentType, ValidationReport};
+use crate::witness_tx::WitnessTx;
 use crate::{
     ContractMetadata, F1r3flyExecutionResult, F1r3flyExecutor, F1r3flyRgbContract, F1r3flyRgbError,
-    Tx,
+    SealKey, Tx, LIB_NAME_F1R3FLY_RGB,
 };
-use amplify::confinement::SmallOrdMap;
-use bp::seals::{Anchor, WTxoSeal};
-use hypersonic::ContractId;
+use amplify::confinement::{Confined, MediumBlob, SmallBlob, SmallOrdMap, SmallVec, TinyVec};
+use bp::seals::{Anchor, WOutpoint, WTxoSeal};
+use bp::Outpoint;
+use hypersonic::{ContractId, Opid};
+use rgb_invoice::RgbBeneficiary;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use strict_encoding::{
+    StrictDecode, StrictDeserialize, StrictDumb, StrictEncode, StrictReader, StrictSerialize,
+    StrictType,
+};
+
+/// Maximum number of history entries accepted in a single consignment
+///
+/// Bounds the work a recipient performs when walking the provenance chain
+/// (one finality query and one commitment verification per entry).
+pub const MAX_HISTORY_DEPTH: usize = 256;
+
+/// Magic number opening every binary consignment
+pub const CONSIGNMENT_MAGIC: [u8; 4] = *b"FRGC";
+
+/// Armor label used by `to_armored()`/`from_armored()`
+pub const CONSIGNMENT_ARMOR_LABEL: &str = "F1R3FLY CONSIGNMENT";
+
+/// Maximum size of a strict-encoded consignment body (16 MiB)
+pub const MAX_CONSIGNMENT_SIZE: usize = 1 << 24;
+
+/// Magic (4 bytes) + major version (2 bytes) + minor version (2 bytes)
+const BINARY_HEADER_LEN: usize = 8;
+
+/// Consignment format version
+///
+/// - Major versions are incompatible: readers reject majors they don't support.
+/// - Minor versions only add optional data: readers accept newer minors of a
+///   supported major, ignoring what they don't understand, and migrate older
+///   minors to the current layout. A migrated consignment keeps the version
+///   it was written with (see `format_version()`).
+///
+/// | Version | Changes                                                      |
+/// |---------|--------------------------------------------------------------|
+/// | 1.0     | Initial JSON consignment                                     |
+/// | 1.1     | Provenance history, binary and armored formats, explicit     |
+/// |         | anchor methods, operation ID                                 |
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ConsignmentVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl ConsignmentVersion {
+    /// Initial format
+    pub const V1_0: Self = Self::new(1, 0);
+
+    /// Provenance history, binary and armored formats, explicit anchor
+    /// methods, operation ID
+    pub const V1_1: Self = Self::new(1, 1);
+
+    /// Version written by this library
+    pub const CURRENT: Self = Self::V1_1;
+
+    /// Major versions this library can read
+    pub const SUPPORTED_MAJORS: &'static [u16] = &[1];
+
+    pub const fn new(major: u16, minor: u16) -> Self {
+        Self { major, minor }
+    }
+
+    /// Known versions this library can read (for wallet capability reporting)
+    ///
+    /// Newer minor versions of a supported major are accepted as well.
+    pub fn supported() -> &'static [ConsignmentVersion] {
+        &[Self::V1_0, Self::V1_1]
+    }
+
+    /// Whether consignments of this version can be read
+    pub fn is_supported(&self) -> bool {
+        Self::SUPPORTED_MAJORS.contains(&self.major)
+    }
+
+    /// Whether this version is newer than `CURRENT` within the same major
+    pub fn is_newer_minor(&self) -> bool {
+        self.major == Self::CURRENT.major && self.minor > Self::CURRENT.minor
+    }
+
+    /// Fail with `UnsupportedVersion` if this version can't be read
+    pub fn check_supported(&self) -> Result<(), F1r3flyRgbError> {
+        if self.is_supported() {
+            return Ok(());
+        }
+        Err(F1r3flyRgbError::UnsupportedVersion {
+            found: self.to_string(),
+            supported: Self::SUPPORTED_MAJORS
+                .iter()
+                .map(|major| format!("{}.x", major))
+                .collect::<Vec<_>>()
+                .join(", "),
+        })
+    }
+}
+
+impl std::fmt::Display for ConsignmentVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl std::str::FromStr for ConsignmentVersion {
+    type Err = F1r3flyRgbError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid =
+            || F1r3flyRgbError::SerializationError(format!("Invalid consignment version: {}", s));
+        let (major, minor) = s.split_once('.').ok_or_else(invalid)?;
+        Ok(Self::new(
+            major.parse().map_err(|_| invalid())?,
+            minor.parse().map_err(|_| invalid())?,
+        ))
+    }
+}
+
+/// Upgrade step applied to a JSON consignment of the given minor version
+type JsonMigration = fn(&mut serde_json::Map<String, serde_json::Value>);
+
+/// JSON migrations within major version 1, indexed by source minor version
+const JSON_MIGRATIONS_V1: &[JsonMigration] = &[migrate_json_v1_0_to_v1_1];
+
+/// Read an optional u16 field from a JSON consignment
+fn json_u16_field(
+    object: &serde_json::Map<String, serde_json::Value>,
+    key: &str,
+) -> Result<Option<u16>, F1r3flyRgbError> {
+    match object.get(key) {
+        None => Ok(None),
+        Some(field) => field
+            .as_u64()
+            .and_then(|n| u16::try_from(n).ok())
+            .map(Some)
+            .ok_or_else(|| F1r3flyRgbError::SerializationError(format!("Invalid {} field", key))),
+    }
+}
+
+/// 1.0 -> 1.1: introduce (empty) provenance history; anchor method and
+/// operation ID are unknown (see `F1r3flyConsignment::migrate()`)
+fn migrate_json_v1_0_to_v1_1(object: &mut serde_json::Map<String, serde_json::Value>) {
+    object
+        .entry("history")
+        .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+    object
+        .entry("anchor_method")
+        .or_insert(serde_json::Value::Null);
+    object.entry("opid").or_insert(serde_json::Value::Null);
+}
+
+/// Serialization format of a consignment
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConsignmentFormat {
+    /// Canonical strict-encoded binary with magic number and version header
+    Binary,
+
+    /// ASCII-armored binary (base64, checksummed, line-wrapped)
+    Armored,
+
+    /// Human-readable JSON (debugging only, not canonical)
+    Json,
+}
+
+impl ConsignmentFormat {
+    /// Detect the format of serialized consignment data
+    ///
+    /// Returns `None` if the data matches none of the known formats.
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        if data.starts_with(&CONSIGNMENT_MAGIC) {
+            return Some(Self::Binary);
+        }
+        if armor::is_armored(CONSIGNMENT_ARMOR_LABEL, data) {
+            return Some(Self::Armored);
+        }
+        match data.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'{') => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
 
 /// Witness identifier mapping for claim process
 /// Links witness_id (temporary) → real UTXO (after Bitcoin TX)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct WitnessMapping {
-    /// Temporary witness identifier used during transfer
-    /// Format: "witness:{address_hash}:{vout}"
-    pub witness_id: String,
+    /// Temporary witness identifier used during transfer (`SealKey::witness`
+    /// of the witness transaction's first input, recipient address and output)
+    pub witness_id: SealKey,
 
     /// Recipient's Bitcoin address (for UTXO matching)
     pub recipient_address: String,
@@ -34,9 +224,10 @@ pub struct WitnessMapping {
 /// - F1r3fly state proof (block hash, state hash)
 /// - Bitcoin anchor (Tapret proof)
 /// - Seals and witnesses
+/// - Optional provenance history back to issuance
 ///
 /// Unlike traditional RGB consignments, this does NOT contain:
-/// - Full operation history (state is on F1r3fly shard)
+/// - Full operation scripts or state (state is on F1r3fly shard)
 /// - AluVM schemas (uses Rholang)
 /// - Client-side state (queries F1r3fly)
 ///
@@ -65,9 +256,16 @@ pub struct WitnessMapping {
 /// ```
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct F1r3flyConsignment {
-    /// Consignment format version
+    /// Consignment format major version (see `ConsignmentVersion`)
+    ///
+    /// Decoded consignments keep the version they were written with, also
+    /// once migrated.
     pub version: u16,
 
+    /// Consignment format minor version (absent in 1.0 consignments)
+    #[serde(default)]
+    pub minor_version: u16,
+
     /// Contract ID (serialized as raw bytes)
     #[serde(
         serialize_with = "serialize_contract_id",
@@ -84,6 +282,19 @@ pub struct F1r3flyConsignment {
     /// Bitcoin anchor (Tapret proof)
     pub bitcoin_anchor: Anchor,
 
+    /// How the anchor commits in the witness transaction
+    ///
+    /// `None` for genesis. Inferred with `AnchorMethod::infer_legacy` when
+    /// consignments written before 1.1 are migrated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor_method: Option<AnchorMethod>,
+
+    /// RGB operation ID of the consigned operation
+    ///
+    /// `None` for consignments written before 1.1; see `operation_id()`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opid: Option<Opid>,
+
     /// Seals (UTXO bindings)
     pub seals: SmallOrdMap<u16, WTxoSeal>,
 
@@ -100,6 +311,104 @@ pub struct F1r3flyConsignment {
     /// Used by recipient to claim balance from witness_id to real UTXO
     #[serde(skip_serializing_if = "Option::is_none")]
     pub witness_mapping: Option<WitnessMapping>,
+
+    /// Provenance chain from issuance up to (but excluding) this consignment's operation
+    ///
+    /// Optional: empty for genesis consignments and for senders that don't ship history.
+    /// When present, `validate()` walks the chain and checks every step.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<HistoryEntry>,
+}
+
+/// Kind of operation recorded in a consignment history
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum HistoryOperation {
+    /// Token issuance (genesis allocation)
+    Issue,
+
+    /// Transfer anchored in a Bitcoin witness transaction
+    Transfer,
+
+    /// Claim migrating a balance from `witness:<id>:<vout>` to the real UTXO
+    Claim,
+}
+
+/// Single step in the provenance chain of a consignment
+///
+/// Each entry carries the F1r3fly state proof of the operation and, for
+/// Bitcoin-anchored operations, the anchor and witness transaction committing
+/// to that state.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Kind of operation
+    pub operation: HistoryOperation,
+
+    /// RGB operation ID of this step
+    pub opid: Opid,
+
+    /// F1r3fly state proof for this step
+    pub f1r3fly_proof: F1r3flyStateProof,
+
+    /// Bitcoin anchor (required for transfers)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bitcoin_anchor: Option<Anchor>,
+
+    /// How the anchor commits in the witness transaction
+    ///
+    /// `None` for entries without an anchor; a missing one is inferred with
+    /// `AnchorMethod::infer_legacy` on migration.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anchor_method: Option<AnchorMethod>,
+
+    /// Witness transaction (required for transfers, optional for issue)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub witness_tx: Option<Tx>,
+
+    /// Seals defined by this step
+    pub seals: SmallOrdMap<u16, WTxoSeal>,
+}
+
+impl HistoryEntry {
+    /// Create history entry from an execution result
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - Kind of operation
+    /// * `result` - F1r3fly execution result of the operation
+    /// * `seals` - Seals defined by the operation
+    /// * `bitcoin_anchor` - Anchor committing to the state hash (transfers only)
+    /// * `witness_tx` - Bitcoin transaction carrying the commitment
+    pub fn new(
+        operation: HistoryOperation,
+        result: &F1r3flyExecutionResult,
+        seals: SmallOrdMap<u16, WTxoSeal>,
+        bitcoin_anchor: Option<Anchor>,
+        witness_tx: Option<Tx>,
+    ) -> Result<Self, F1r3flyRgbError> {
+        Ok(Self {
+            operation,
+            opid: result.opid,
+            f1r3fly_proof: F1r3flyStateProof::from_result(result)?,
+            bitcoin_anchor,
+            anchor_method: None,
+            witness_tx,
+            seals,
+        })
+    }
+
+    /// Set the anchor method of the entry's Bitcoin anchor
+    pub fn with_anchor_method(mut self, method: AnchorMethod) -> Self {
+        self.anchor_method = Some(method);
+        self
+    }
+
+    /// Outpoints of the seals defined by this step
+    ///
+    /// Witness-relative seals are resolved against the entry's witness transaction;
+    /// they are skipped when no witness transaction is available.
+    fn defined_outpoints(&self) -> Vec<Outpoint> {
+        resolve_seal_outpoints(&self.seals, self.witness_tx.as_ref())
+    }
 }
 
 /// F1r3fly state proof for consignment validation
@@ -117,6 +426,25 @@ pub struct F1r3flyStateProof {
     pub deploy_id: String,
 }
 
+impl F1r3flyStateProof {
+    /// Build state proof from a F1r3fly execution result
+    pub fn from_result(result: &F1r3flyExecutionResult) -> Result<Self, F1r3flyRgbError> {
+        // Convert SmallVec to String for serialization
+        let block_hash = result
+            .block_hash_string()
+            .map_err(|e| F1r3flyRgbError::InvalidResponse(format!("Invalid block hash: {}", e)))?;
+        let deploy_id = result
+            .deploy_id_string()
+            .map_err(|e| F1r3flyRgbError::InvalidResponse(format!("Invalid deploy ID: {}", e)))?;
+
+        Ok(Self {
+            block_hash,
+            state_hash: result.state_hash,
+            deploy_id,
+        })
+    }
+}
+
 impl F1r3flyConsignment {
     /// Create new consignment from contract execution result
     ///
@@ -157,13 +485,7 @@ impl F1r3flyConsignment {
         witness_txs: Vec<Tx>,
         is_genesis: bool,
     ) -> Result<Self, F1r3flyRgbError> {
-        // Convert SmallVec to String for serialization
-        let block_hash = result
-            .block_hash_string()
-            .map_err(|e| F1r3flyRgbError::InvalidResponse(format!("Invalid block hash: {}", e)))?;
-        let deploy_id = result
-            .deploy_id_string()
-            .map_err(|e| F1r3flyRgbError::InvalidResponse(format!("Invalid deploy ID: {}", e)))?;
+        let f1r3fly_proof = F1r3flyStateProof::from_result(&result)?;
 
         // Get anchor from contract tracker (or create placeholder for genesis)
         // Use the opid from the execution result (not derived from state_hash)
@@ -191,29 +513,119 @@ impl F1r3flyConsignment {
                 })?
         };
 
+        let anchor_method = if is_genesis {
+            None
+        } else {
+            contract.tracker().get_anchor_method(&opid)
+        };
+
         Ok(Self {
-            version: 1,
+            version: ConsignmentVersion::CURRENT.major,
+            minor_version: ConsignmentVersion::CURRENT.minor,
             contract_id: contract.contract_id(),
             contract_metadata: contract.metadata().clone(),
-            f1r3fly_proof: F1r3flyStateProof {
-                block_hash,
-                state_hash: result.state_hash,
-                deploy_id,
-            },
+            f1r3fly_proof,
             bitcoin_anchor,
+            anchor_method,
+            opid: Some(opid),
             seals,
             witness_txs,
             is_genesis,
             witness_mapping: None,
+            history: Vec::new(),
+        })
+    }
+
+    /// Set the anchor method explicitly
+    ///
+    /// `new()` takes it from the contract tracker; use this when the anchor was
+    /// stored without one.
+    pub fn with_anchor_method(mut self, method: AnchorMethod) -> Self {
+        self.anchor_method = Some(method);
+        self
+    }
+
+    /// Attach provenance history to the consignment
+    ///
+    /// `history` must be ordered from issuance to the operation preceding this
+    /// consignment's own operation.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use f1r3fly_rgb::{F1r3flyConsignment, HistoryEntry};
+    /// # fn example(consignment: F1r3flyConsignment, history: Vec<HistoryEntry>) {
+    /// let consignment = consignment.with_history(history);
+    /// # }
+    /// ```
+    pub fn with_history(mut self, history: Vec<HistoryEntry>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Point an undelivered consignment at a replacement witness transaction
+    ///
+    /// Used after `BitcoinAnchorTracker::bump_witness_fee()`: the replacement
+    /// commits to the same state hash with a new proof, so the anchor, anchor
+    /// method and witness transaction are swapped while the F1r3fly proof and
+    /// seals stay unchanged.
+    ///
+    /// # Errors
+    /// Returns error for genesis consignments, which have no witness transaction
+    pub fn replace_witness(&mut self, witness: &WitnessTx) -> Result<(), F1r3flyRgbError> {
+        if self.is_genesis {
+            return Err(F1r3flyRgbError::InvalidConsignment(
+                "Genesis consignment has no witness transaction to replace".to_string(),
+            ));
+        }
+
+        self.bitcoin_anchor = witness.anchor.clone();
+        self.anchor_method = Some(witness.anchor_method);
+        self.witness_txs = vec![witness.psbt.to_unsigned_tx().into()];
+        self.witness_mapping = Some(witness.witness_mapping.clone());
+
+        Ok(())
+    }
+
+    /// Get consignment format version
+    pub fn format_version(&self) -> ConsignmentVersion {
+        ConsignmentVersion::new(self.version, self.minor_version)
+    }
+
+    /// RGB operation ID the recipient files the consignment under
+    ///
+    /// Consignments before 1.1 don't carry the sender's operation ID; for
+    /// them it is derived from the F1r3fly state proof, so every recipient
+    /// of the same consignment uses the same ID.
+    pub fn operation_id(&self) -> Opid {
+        self.opid.unwrap_or_else(|| {
+            use sha2::{Digest, Sha256};
+            let mut hasher = Sha256::new();
+            hasher.update(self.contract_id.as_slice());
+            hasher.update(self.f1r3fly_proof.deploy_id.as_bytes());
+            hasher.update(self.f1r3fly_proof.state_hash);
+            let opid_bytes: [u8; 32] = hasher.finalize().into();
+            Opid::from(opid_bytes)
         })
     }
 
+    /// Outpoints of the seals this consignment defines
+    ///
+    /// Witness-relative seals resolve against the first witness transaction.
+    pub fn seal_outpoints(&self) -> Vec<Outpoint> {
+        resolve_seal_outpoints(&self.seals, self.witness_txs.first())
+    }
+
     /// Validate consignment
     ///
     /// Verifies:
+    /// 0. Format version is supported and the contract ID matches its registry URI
     /// 1. F1r3fly state proof is valid (query shard)
     /// 2. Bitcoin anchor matches state hash
-    /// 3. Seals are valid UTXOs
+    /// 3. Seals are valid UTXOs, witness mapping matches the witness transaction
+    /// 4. Provenance history, if present (see `MAX_HISTORY_DEPTH`)
+    ///
+    /// Use `validate_report()` to see the outcome of every check.
     ///
     /// # Arguments
     ///
@@ -221,7 +633,7 @@ impl F1r3flyConsignment {
     ///
     /// # Returns
     ///
-    /// Ok(()) if valid, error otherwise
+    /// Ok(()) if valid, the first failure otherwise
     ///
     /// # Example
     ///
@@ -234,117 +646,566 @@ impl F1r3flyConsignment {
     /// # }
     /// ```
     pub async fn validate(&self, executor: &F1r3flyExecutor) -> Result<(), F1r3flyRgbError> {
+        self.validate_report(executor).await.into_result()
+    }
+
+    /// Validate consignment and report the outcome of every check
+    ///
+    /// Unlike `validate()`, does not stop at the first failure. Checks that
+    /// can't run because an earlier check failed are reported as skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `executor` - F1r3fly executor for state verification
+    ///
+    /// # Returns
+    ///
+    /// Report listing each check with its status and reason
+    pub async fn validate_report(&self, executor: &F1r3flyExecutor) -> ValidationReport {
         log::info!("Validating consignment for contract: {}", self.contract_id);
 
-        // 1. Verify F1r3fly state proof - check block is finalized
-        log::debug!(
-            "Checking F1r3fly block finalization: {}",
-            self.f1r3fly_proof.block_hash
-        );
-        let is_finalized = executor
-            .is_block_finalized(&self.f1r3fly_proof.block_hash)
-            .await?;
+        let mut report = ValidationReport::new(self.contract_id, self.is_genesis);
 
-        if !is_finalized {
-            return Err(F1r3flyRgbError::InvalidConsignment(format!(
-                "F1r3fly block {} is not finalized. Consignment requires immutable state.",
-                self.f1r3fly_proof.block_hash
-            )));
+        // 0. Format version and contract identity
+        let version = self.format_version();
+        if let Err(e) = version.check_supported() {
+            report.fail(CheckKind::FormatVersion, e);
+            log::info!("❌ Consignment rejected: unsupported format version");
+            return report;
+        }
+        report.pass(CheckKind::FormatVersion, format!("Version {}", version));
+
+        let expected_id = derive_contract_id_from_uri(&self.contract_metadata.registry_uri);
+        if expected_id == self.contract_id {
+            report.pass(
+                CheckKind::ContractIdentity,
+                format!(
+                    "Contract ID matches registry URI {}",
+                    self.contract_metadata.registry_uri
+                ),
+            );
+        } else {
+            report.fail(
+                CheckKind::ContractIdentity,
+                F1r3flyRgbError::InvalidConsignment(format!(
+                    "Contract ID {} does not match registry URI {} (expected {})",
+                    self.contract_id, self.contract_metadata.registry_uri, expected_id
+                )),
+            );
+        }
+
+        // 1. Verify F1r3fly state proof - state hash binds block and deploy,
+        //    block must be finalized
+        let proof = &self.f1r3fly_proof;
+        if compute_state_hash(&proof.block_hash, &proof.deploy_id) == proof.state_hash {
+            report.pass(
+                CheckKind::StateProof,
+                format!("State hash {}", hex::encode(proof.state_hash)),
+            );
+        } else {
+            report.fail(
+                CheckKind::StateProof,
+                F1r3flyRgbError::InvalidConsignment(format!(
+                    "State hash {} does not match block {} and deploy {}",
+                    hex::encode(proof.state_hash),
+                    proof.block_hash,
+                    proof.deploy_id
+                )),
+            );
         }
-        log::debug!("✓ F1r3fly block is finalized");
 
-        // State hash is implicitly verified through:
-        // - Block finalization (proves F1r3fly state is immutable)
-        // - Step 2 below (Bitcoin anchor verifies Tapret commitment) - ONLY FOR TRANSFERS
+        log::debug!("Checking F1r3fly block finalization: {}", proof.block_hash);
+        match executor.is_block_finalized(&proof.block_hash).await {
+            Ok(true) => {
+                log::debug!("✓ F1r3fly block is finalized");
+                report.pass(
+                    CheckKind::Finality,
+                    format!("F1r3fly block {} is finalized", proof.block_hash),
+                );
+            }
+            Ok(false) => report.fail(
+                CheckKind::Finality,
+                F1r3flyRgbError::InvalidConsignment(format!(
+                    "F1r3fly block {} is not finalized. Consignment requires immutable state.",
+                    proof.block_hash
+                )),
+            ),
+            Err(e) => report.fail(CheckKind::Finality, e),
+        }
 
         // 2. Verify Bitcoin anchor
         // For GENESIS: Skip Tapret verification (genesis UTXO itself is the Bitcoin anchor)
-        // For TRANSFER: Full Tapret cryptographic verification required
+        // For TRANSFER: Full cryptographic verification required
         if self.is_genesis {
-            log::debug!("✓ Genesis consignment - Tapret verification skipped (not required)");
-            log::debug!("  Genesis UTXO serves as Bitcoin anchor");
+            // Verify we have the genesis transaction in witnesses
+            if self.witness_txs.is_empty() {
+                report.fail(
+                    CheckKind::Commitment,
+                    F1r3flyRgbError::InvalidConsignment(
+                        "Genesis consignment must include genesis UTXO transaction".to_string(),
+                    ),
+                );
+            } else {
+                log::debug!("✓ Genesis consignment - Tapret verification skipped (not required)");
+                report.skip(
+                    CheckKind::Commitment,
+                    "Genesis consignment - Tapret verification skipped (genesis UTXO serves as anchor)",
+                );
+            }
+        } else {
+            let inferred =
+                self.anchor_method.is_none() || self.format_version() < ConsignmentVersion::V1_1;
+            let method = self
+                .anchor_method
+                .unwrap_or_else(|| AnchorMethod::infer_legacy(&self.bitcoin_anchor));
+            let commitment_type = match method {
+                AnchorMethod::Tapret { .. } => CommitmentType::Tapret,
+                AnchorMethod::OpReturn { .. } => CommitmentType::OpReturn,
+            };
+            report.commitment_type = Some(commitment_type);
 
-            // Verify we have at least one seal (the genesis seal)
-            if self.seals.is_empty() {
-                return Err(F1r3flyRgbError::InvalidConsignment(
-                    "Genesis consignment must have at least one seal".to_string(),
-                ));
+            match self.witness_txs.first() {
+                Some(witness_tx) => {
+                    let result = verify_witness_commitment(
+                        witness_tx,
+                        self.contract_id,
+                        self.f1r3fly_proof.state_hash,
+                        &self.bitcoin_anchor,
+                        method,
+                    );
+                    match result {
+                        Ok(()) if inferred => report.warn(
+                            CheckKind::Commitment,
+                            format!(
+                                "{} commitment verified in witness {} \
+                                 (method inferred, consignment predates explicit anchor methods)",
+                                method,
+                                witness_tx.txid()
+                            ),
+                        ),
+                        Ok(()) => report.pass(
+                            CheckKind::Commitment,
+                            format!(
+                                "{} commitment verified in witness {}",
+                                method,
+                                witness_tx.txid()
+                            ),
+                        ),
+                        Err(e) => report.fail(CheckKind::Commitment, e),
+                    }
+                }
+                None => report.fail(
+                    CheckKind::Commitment,
+                    F1r3flyRgbError::InvalidConsignment(
+                        "No witness transaction for commitment verification. \
+                         Transfer consignment must include Bitcoin transaction."
+                            .to_string(),
+                    ),
+                ),
             }
+            log::debug!("   Witness TX count: {}", self.witness_txs.len());
+        }
 
-            // Verify we have the genesis transaction in witnesses
-            if self.witness_txs.is_empty() {
-                return Err(F1r3flyRgbError::InvalidConsignment(
-                    "Genesis consignment must include genesis UTXO transaction".to_string(),
-                ));
+        // 3. Verify seals and witness mapping
+        if self.seals.is_empty() {
+            report.fail(
+                CheckKind::Seals,
+                F1r3flyRgbError::InvalidConsignment("No seals in consignment".to_string()),
+            );
+        } else {
+            log::debug!("✓ Seals present: {} seal(s)", self.seals.len());
+            report.pass(
+                CheckKind::Seals,
+                format!("{} seal(s) present", self.seals.len()),
+            );
+        }
+        self.check_witness_mapping(&mut report);
+
+        // 4. Verify provenance history (if shipped)
+        if self.history.is_empty() {
+            report.skip(CheckKind::History, "No provenance history shipped");
+        } else {
+            let result = self
+                .validate_history(executor)
+                .await
+                .map(|_| format!("{} entries verified from issuance", self.history.len()));
+            report.record(CheckKind::History, result);
+        }
+
+        if report.is_valid() {
+            log::info!("✅ Consignment validated");
+        } else {
+            log::info!("❌ Consignment rejected");
+        }
+        report
+    }
+
+    /// Check that the witness mapping points at a matching witness output
+    ///
+    /// Address-only witness keys of consignments before 1.1 are reported as
+    /// warnings.
+    fn check_witness_mapping(&self, report: &mut ValidationReport) {
+        if self.is_genesis {
+            report.skip(
+                CheckKind::WitnessMapping,
+                "Genesis consignment - no witness mapping",
+            );
+            return;
+        }
+        let Some(mapping) = self.witness_mapping.as_ref() else {
+            report.skip(
+                CheckKind::WitnessMapping,
+                "No witness mapping - recipient seal is not a witness output",
+            );
+            return;
+        };
+        let Some(witness_tx) = self.witness_txs.first() else {
+            report.skip(
+                CheckKind::WitnessMapping,
+                "No witness transaction to check against",
+            );
+            return;
+        };
+
+        let seal_key = mapping.witness_id;
+        if !seal_key.is_witness() || seal_key.vout() != mapping.expected_vout {
+            report.fail(
+                CheckKind::WitnessMapping,
+                F1r3flyRgbError::InvalidConsignment(format!(
+                    "Witness mapping {} is not a witness key for output {}",
+                    seal_key, mapping.expected_vout
+                )),
+            );
+            return;
+        }
+
+        let Some(output) = witness_tx.outputs.get(mapping.expected_vout as usize) else {
+            report.fail(
+                CheckKind::WitnessMapping,
+                F1r3flyRgbError::InvalidConsignment(format!(
+                    "Witness mapping {} expects output {}, witness transaction has {}",
+                    mapping.witness_id,
+                    mapping.expected_vout,
+                    witness_tx.outputs.len()
+                )),
+            );
+            return;
+        };
+
+        let address = match bitcoin::Address::from_str(&mapping.recipient_address) {
+            Ok(address) => address.assume_checked(),
+            Err(e) => {
+                report.fail(
+                    CheckKind::WitnessMapping,
+                    F1r3flyRgbError::InvalidConsignment(format!(
+                        "Recipient address {} could not be parsed: {}",
+                        mapping.recipient_address, e
+                    )),
+                );
+                return;
+            }
+        };
+
+        let derived = witness_tx.inputs.first().map(|input| {
+            SealKey::witness(
+                input.prev_output,
+                &mapping.recipient_address,
+                mapping.expected_vout,
+            )
+        });
+        // 1.0 consignments derived the key from the recipient address alone
+        let legacy = derived != Some(seal_key)
+            && self.format_version() < ConsignmentVersion::V1_1
+            && seal_key
+                == SealKey::legacy_witness(&mapping.recipient_address, mapping.expected_vout);
+        if derived != Some(seal_key) && !legacy {
+            report.fail(
+                CheckKind::WitnessMapping,
+                F1r3flyRgbError::InvalidConsignment(format!(
+                    "Witness mapping {} was not derived from recipient {} and the seal spent by the witness transaction",
+                    seal_key, mapping.recipient_address
+                )),
+            );
+        } else if address.script_pubkey().as_bytes() == output.script_pubkey.as_slice() {
+            if legacy {
+                report.warn(
+                    CheckKind::WitnessMapping,
+                    format!(
+                        "Output {} pays recipient {} (legacy witness key {} is derived from the address only, not bound to the spent seal)",
+                        mapping.expected_vout, mapping.recipient_address, seal_key
+                    ),
+                );
+            } else {
+                report.pass(
+                    CheckKind::WitnessMapping,
+                    format!(
+                        "Output {} pays recipient {}",
+                        mapping.expected_vout, mapping.recipient_address
+                    ),
+                );
             }
         } else {
-            // TRANSFER: Verify Bitcoin commitment (Tapret or OP_RETURN)
-            // Get Bitcoin transaction from witness
-            let witness_tx = self.witness_txs.first().ok_or_else(|| {
-                F1r3flyRgbError::InvalidConsignment(
-                    "No witness transaction for commitment verification. \
-                     Transfer consignment must include Bitcoin transaction."
-                        .to_string(),
-                )
-            })?;
+            report.fail(
+                CheckKind::WitnessMapping,
+                F1r3flyRgbError::InvalidConsignment(format!(
+                    "Witness output {} does not pay recipient {}",
+                    mapping.expected_vout, mapping.recipient_address
+                )),
+            );
+        }
+    }
+
+    /// Validate consignment against the invoice it is paying
+    ///
+    /// Verifies:
+    /// 1. Contract ID matches the invoice
+    /// 2. Witness transaction pays the invoice's `WitnessOut` address at
+    ///    `WitnessMapping.expected_vout`
+    /// 3. Amount received at the resulting seal equals the invoice amount
+    ///    (the witness identifier's balance before claim, the claim record of
+    ///    the real UTXO after)
+    ///
+    /// Does not replace `validate()`; recipients should run both.
+    ///
+    /// # Arguments
+    ///
+    /// * `invoice` - Invoice issued by the recipient
+    /// * `executor` - F1r3fly executor for balance queries
+    ///
+    /// # Returns
+    ///
+    /// Ok(()) if the consignment pays the invoice, `InvoiceMismatch` otherwise
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use f1r3fly_rgb::{F1r3flyConsignment, F1r3flyExecutor, GeneratedInvoice};
+    /// # async fn example(consignment: F1r3flyConsignment, invoice: GeneratedInvoice, executor: &F1r3flyExecutor) -> Result<(), Box<dyn std::error::Error>> {
+    /// consignment.validate(executor).await?;
+    /// consignment.validate_against_invoice(&invoice, executor).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn validate_against_invoice(
+        &self,
+        invoice: &GeneratedInvoice,
+        executor: &F1r3flyExecutor,
+    ) -> Result<(), F1r3flyRgbError> {
+        let mismatch = |reason| Err(F1r3flyRgbError::InvoiceMismatch(reason));
+
+        // 1. Contract identity
+        let expected_id = invoice.invoice.scope;
+        if self.contract_id != expected_id {
+            return mismatch(InvoiceMismatch::ContractId {
+                expected: expected_id.to_string(),
+                found: self.contract_id.to_string(),
+            });
+        }
+
+        // 2. Witness output pays the invoice beneficiary
+        let RgbBeneficiary::WitnessOut(wout) = &invoice.invoice.auth else {
+            return mismatch(InvoiceMismatch::UnsupportedBeneficiary);
+        };
+        let witness_tx = match self.witness_txs.first() {
+            Some(tx) if !self.is_genesis => tx,
+            _ => return mismatch(InvoiceMismatch::MissingWitnessTransaction),
+        };
+        let Some(mapping) = self.witness_mapping.as_ref() else {
+            return mismatch(InvoiceMismatch::MissingWitnessMapping);
+        };
+
+        let vout = mapping.expected_vout;
+        let Some(output) = witness_tx.outputs.get(vout as usize) else {
+            return mismatch(InvoiceMismatch::MissingOutput {
+                vout,
+                outputs: witness_tx.outputs.len(),
+            });
+        };
+        if output.script_pubkey.as_slice() != wout.script_pubkey().as_slice() {
+            return mismatch(InvoiceMismatch::Beneficiary {
+                vout,
+                expected: invoice.address.clone(),
+            });
+        }
+
+        // 3. Amount received at the resulting seal
+        let (_, balance) = mapped_balance(executor, self.contract_id, witness_tx, mapping).await?;
+        if balance != invoice.amount {
+            return mismatch(InvoiceMismatch::Amount {
+                expected: invoice.amount,
+                found: balance,
+            });
+        }
+
+        log::info!(
+            "✅ Consignment pays invoice: {} at output {}",
+            invoice.amount,
+            vout
+        );
+        Ok(())
+    }
+
+    /// Walk the provenance chain and verify it end to end
+    ///
+    /// Verifies:
+    /// 1. History depth is within `MAX_HISTORY_DEPTH`
+    /// 2. Chain starts with exactly one issue operation
+    /// 3. Every step's state hash binds its F1r3fly block and deploy, and
+    ///    the block is finalized
+    /// 4. Every transfer's Bitcoin commitment to this contract matches its
+    ///    state hash
+    /// 5. Every transfer's witness transaction spends a seal defined by the
+    ///    previous Bitcoin-anchored step, and this consignment's witness
+    ///    transaction spends a seal defined by the last one
+    /// 6. Every claim is unanchored and only moves balance onto seals the
+    ///    previous Bitcoin-anchored step defined
+    async fn validate_history(&self, executor: &F1r3flyExecutor) -> Result<(), F1r3flyRgbError> {
+        log::debug!("Validating history: {} entries", self.history.len());
+
+        if self.is_genesis {
+            return Err(F1r3flyRgbError::InvalidConsignment(
+                "Genesis consignment must not carry history".to_string(),
+            ));
+        }
+
+        if self.history.len() > MAX_HISTORY_DEPTH {
+            return Err(F1r3flyRgbError::InvalidConsignment(format!(
+                "History depth {} exceeds maximum of {}",
+                self.history.len(),
+                MAX_HISTORY_DEPTH
+            )));
+        }
+
+        // Structural checks first, so malformed chains are rejected without network calls
+        for (index, entry) in self.history.iter().enumerate() {
+            let is_issue = entry.operation == HistoryOperation::Issue;
+            if (index == 0) != is_issue {
+                return Err(F1r3flyRgbError::InvalidConsignment(format!(
+                    "History entry {} ({:?}): chain must start with a single issue operation",
+                    index, entry.operation
+                )));
+            }
+            let proof = &entry.f1r3fly_proof;
+            if compute_state_hash(&proof.block_hash, &proof.deploy_id) != proof.state_hash {
+                return Err(F1r3flyRgbError::InvalidConsignment(format!(
+                    "History entry {} ({}): state hash {} does not match block {} and deploy {}",
+                    index,
+                    entry.opid,
+                    hex::encode(proof.state_hash),
+                    proof.block_hash,
+                    proof.deploy_id
+                )));
+            }
+        }
+
+        let mut live_outpoints: Vec<Outpoint> = Vec::new();
+
+        for (index, entry) in self.history.iter().enumerate() {
+            let is_finalized = executor
+                .is_block_finalized(&entry.f1r3fly_proof.block_hash)
+                .await?;
+            if !is_finalized {
+                return Err(F1r3flyRgbError::InvalidConsignment(format!(
+                    "History entry {} ({}): F1r3fly block {} is not finalized",
+                    index, entry.opid, entry.f1r3fly_proof.block_hash
+                )));
+            }
 
-            // Check which anchoring method was used
-            if let Some(tapret_proof) = self.bitcoin_anchor.dbc_proof.as_ref() {
-                // Tapret proof present - verify Tapret commitment
-                log::debug!("Verifying Bitcoin anchor with Tapret proof (transfer consignment)");
+            match entry.operation {
+                HistoryOperation::Issue => {
+                    live_outpoints = entry.defined_outpoints();
+                }
+                HistoryOperation::Transfer => {
+                    let (Some(anchor), Some(witness_tx)) =
+                        (entry.bitcoin_anchor.as_ref(), entry.witness_tx.as_ref())
+                    else {
+                        return Err(F1r3flyRgbError::InvalidConsignment(format!(
+                            "History entry {} ({}): transfer requires Bitcoin anchor and witness transaction",
+                            index, entry.opid
+                        )));
+                    };
 
-                // Perform full cryptographic verification
-                use crate::verify_tapret_proof_in_tx;
-                verify_tapret_proof_in_tx(witness_tx, self.f1r3fly_proof.state_hash, tapret_proof)
+                    let method = entry
+                        .anchor_method
+                        .unwrap_or_else(|| AnchorMethod::infer_legacy(anchor));
+                    verify_witness_commitment(
+                        witness_tx,
+                        self.contract_id,
+                        entry.f1r3fly_proof.state_hash,
+                        anchor,
+                        method,
+                    )
                     .map_err(|e| {
                         F1r3flyRgbError::InvalidConsignment(format!(
-                            "Tapret proof verification failed: {}",
-                            e
+                            "History entry {} ({}): {}",
+                            index, entry.opid, e
                         ))
                     })?;
 
-                log::debug!("✓ Tapret proof cryptographically verified");
-            } else {
-                // No Tapret proof - verify OP_RETURN commitment
-                log::debug!("Verifying Bitcoin anchor with OP_RETURN (transfer consignment)");
-
-                // Extract OP_RETURN commitment from transaction (should be at output 0)
-                use crate::extract_opreturn_commitment;
-                let extracted_hash = extract_opreturn_commitment(witness_tx, 0).map_err(|e| {
-                    F1r3flyRgbError::InvalidConsignment(format!(
-                        "OP_RETURN extraction failed: {}",
-                        e
-                    ))
-                })?;
+                    if !spends_any(witness_tx, &live_outpoints) {
+                        return Err(F1r3flyRgbError::InvalidConsignment(format!(
+                            "History entry {} ({}): witness transaction {} does not spend \
+                             a seal defined by the previous operation",
+                            index,
+                            entry.opid,
+                            witness_tx.txid()
+                        )));
+                    }
 
-                // Verify the extracted hash matches the state hash
-                if extracted_hash != self.f1r3fly_proof.state_hash {
-                    return Err(F1r3flyRgbError::InvalidConsignment(format!(
-                        "OP_RETURN hash mismatch. Expected: {}, Found: {}",
-                        hex::encode(self.f1r3fly_proof.state_hash),
-                        hex::encode(extracted_hash)
-                    )));
+                    live_outpoints = entry.defined_outpoints();
                 }
+                HistoryOperation::Claim => {
+                    // Claims migrate F1r3fly state only; Bitcoin seals are unchanged
+                    if entry.bitcoin_anchor.is_some() {
+                        return Err(F1r3flyRgbError::InvalidConsignment(format!(
+                            "History entry {} ({}): claim must not carry a Bitcoin anchor",
+                            index, entry.opid
+                        )));
+                    }
+                    let claimed = entry.defined_outpoints();
+                    if claimed.is_empty()
+                        || claimed.len() != entry.seals.len()
+                        || !claimed
+                            .iter()
+                            .all(|outpoint| live_outpoints.contains(outpoint))
+                    {
+                        return Err(F1r3flyRgbError::InvalidConsignment(format!(
+                            "History entry {} ({}): claim seals are not outputs defined \
+                             by the previous operation",
+                            index, entry.opid
+                        )));
+                    }
+                }
+            }
 
-                log::debug!("✓ OP_RETURN commitment cryptographically verified");
+            if live_outpoints.is_empty() {
+                return Err(F1r3flyRgbError::InvalidConsignment(format!(
+                    "History entry {} ({}): no resolvable seal outpoints",
+                    index, entry.opid
+                )));
             }
 
-            log::debug!("   Witness TX count: {}", self.witness_txs.len());
+            log::debug!("✓ History entry {} ({:?}) verified", index, entry.operation);
         }
 
-        // 3. Verify seals are valid
-        if self.seals.is_empty() {
-            return Err(F1r3flyRgbError::InvalidConsignment(
-                "No seals in consignment".to_string(),
-            ));
+        // Close the chain: this consignment's witness must spend the last defined seal
+        if let Some(witness_tx) = self.witness_txs.first() {
+            if !spends_any(witness_tx, &live_outpoints) {
+                return Err(F1r3flyRgbError::InvalidConsignment(format!(
+                    "Witness transaction {} does not spend a seal defined by the last history entry",
+                    witness_tx.txid()
+                )));
+            }
         }
-        log::debug!("✓ Seals present: {} seal(s)", self.seals.len());
 
-        log::info!("✅ Consignment validated");
+        log::debug!("✓ History chain verified from issuance");
         Ok(())
     }
 
-    /// Serialize consignment to bytes
+    /// Serialize consignment to bytes (canonical binary format)
+    ///
+    /// Equivalent to `to_binary()`. Use `to_armored()` for a text form or
+    /// `to_json()` for debugging.
     ///
     /// # Returns
     ///
@@ -361,11 +1222,13 @@ impl F1r3flyConsignment {
     /// # }
     /// ```
     pub fn to_bytes(&self) -> Result<Vec<u8>, F1r3flyRgbError> {
-        serde_json::to_vec(self).map_err(|e| F1r3flyRgbError::SerializationError(e.to_string()))
+        self.to_binary()
     }
 
     /// Deserialize consignment from bytes
     ///
+    /// Auto-detects the format (binary, armored or JSON).
+    ///
     /// # Arguments
     ///
     /// * `data` - Serialized consignment bytes
@@ -384,7 +1247,196 @@ impl F1r3flyConsignment {
     /// # }
     /// ```
     pub fn from_bytes(data: &[u8]) -> Result<Self, F1r3flyRgbError> {
-        serde_json::from_slice(data).map_err(|e| F1r3flyRgbError::SerializationError(e.to_string()))
+        match ConsignmentFormat::detect(data) {
+            Some(ConsignmentFormat::Binary) => Self::from_binary(data),
+            Some(ConsignmentFormat::Armored) => {
+                let text = std::str::from_utf8(data).map_err(|e| {
+                    F1r3flyRgbError::SerializationError(format!("Armored data is not UTF-8: {}", e))
+                })?;
+                Self::from_armored(text)
+            }
+            Some(ConsignmentFormat::Json) => Self::from_json(data),
+            None => Err(F1r3flyRgbError::SerializationError(
+                "Unrecognized consignment format".to_string(),
+            )),
+        }
+    }
+
+    /// Serialize consignment in the given format
+    pub fn encode(&self, format: ConsignmentFormat) -> Result<Vec<u8>, F1r3flyRgbError> {
+        match format {
+            ConsignmentFormat::Binary => self.to_binary(),
+            ConsignmentFormat::Armored => self.to_armored().map(String::into_bytes),
+            ConsignmentFormat::Json => self.to_json(),
+        }
+    }
+
+    /// Serialize consignment to canonical strict-encoded binary
+    ///
+    /// Layout: `CONSIGNMENT_MAGIC` (4 bytes) || major version (u16 LE) ||
+    /// minor version (u16 LE) || strict-encoded body.
+    pub fn to_binary(&self) -> Result<Vec<u8>, F1r3flyRgbError> {
+        let body = ConsignmentWire::try_from(self)?
+            .to_strict_serialized::<MAX_CONSIGNMENT_SIZE>()
+            .map_err(|e| F1r3flyRgbError::SerializationError(e.to_string()))?
+            .release();
+
+        let mut data = Vec::with_capacity(BINARY_HEADER_LEN + body.len());
+        data.extend_from_slice(&CONSIGNMENT_MAGIC);
+        data.extend_from_slice(&self.version.to_le_bytes());
+        data.extend_from_slice(&self.minor_version.to_le_bytes());
+        data.extend_from_slice(&body);
+        Ok(data)
+    }
+
+    /// Deserialize consignment from canonical strict-encoded binary
+    ///
+    /// All supported versions share the 1.1 body layout; 1.0 consignments
+    /// written back as binary are migrated like JSON ones. Newer minor
+    /// versions may append fields to the body; trailing data is ignored for
+    /// them.
+    pub fn from_binary(data: &[u8]) -> Result<Self, F1r3flyRgbError> {
+        if data.len() < BINARY_HEADER_LEN || data[..4] != CONSIGNMENT_MAGIC {
+            return Err(F1r3flyRgbError::SerializationError(
+                "Missing binary consignment magic number".to_string(),
+            ));
+        }
+        let version = ConsignmentVersion::new(
+            u16::from_le_bytes([data[4], data[5]]),
+            u16::from_le_bytes([data[6], data[7]]),
+        );
+        version.check_supported()?;
+
+        let body = data[BINARY_HEADER_LEN..].to_vec();
+        let wire = if version.is_newer_minor() {
+            log::warn!(
+                "Consignment version {} is newer than {}; ignoring unknown fields",
+                version,
+                ConsignmentVersion::CURRENT
+            );
+            let mut reader = StrictReader::in_memory::<MAX_CONSIGNMENT_SIZE>(body);
+            ConsignmentWire::strict_decode(&mut reader)
+                .map_err(|e| F1r3flyRgbError::SerializationError(e.to_string()))?
+        } else {
+            let body = Confined::<Vec<u8>, 0, MAX_CONSIGNMENT_SIZE>::try_from(body)
+                .map_err(|e| F1r3flyRgbError::SerializationError(e.to_string()))?;
+            ConsignmentWire::from_strict_serialized::<MAX_CONSIGNMENT_SIZE>(body)
+                .map_err(|e| F1r3flyRgbError::SerializationError(e.to_string()))?
+        };
+
+        let version = if version.is_newer_minor() {
+            ConsignmentVersion::CURRENT
+        } else {
+            version
+        };
+        let mut consignment = wire.into_consignment(version)?;
+        consignment.migrate();
+        Ok(consignment)
+    }
+
+    /// Serialize consignment to ASCII-armored text
+    ///
+    /// Wraps the binary format in base64 with a SHA-256 checksum header.
+    pub fn to_armored(&self) -> Result<String, F1r3flyRgbError> {
+        let data = self.to_binary()?;
+        Ok(armor::armor(
+            CONSIGNMENT_ARMOR_LABEL,
+            &[
+                ("Id", self.contract_id.to_string()),
+                ("Version", self.format_version().to_string()),
+            ],
+            &data,
+        ))
+    }
+
+    /// Deserialize consignment from ASCII-armored text
+    pub fn from_armored(text: &str) -> Result<Self, F1r3flyRgbError> {
+        let dearmored = armor::dearmor(CONSIGNMENT_ARMOR_LABEL, text)
+            .map_err(|e| F1r3flyRgbError::SerializationError(e.to_string()))?;
+        Self::from_binary(&dearmored.data)
+    }
+
+    /// Serialize consignment to JSON (for debugging)
+    pub fn to_json(&self) -> Result<Vec<u8>, F1r3flyRgbError> {
+        serde_json::to_vec(self).map_err(|e| F1r3flyRgbError::SerializationError(e.to_string()))
+    }
+
+    /// Deserialize consignment from JSON
+    ///
+    /// Rejects unsupported major versions, migrates older minor versions and
+    /// ignores unknown fields written by newer minor versions.
+    pub fn from_json(data: &[u8]) -> Result<Self, F1r3flyRgbError> {
+        let mut value: serde_json::Value = serde_json::from_slice(data)
+            .map_err(|e| F1r3flyRgbError::SerializationError(e.to_string()))?;
+        Self::migrate_json(&mut value)?;
+        let mut consignment: Self = serde_json::from_value(value)
+            .map_err(|e| F1r3flyRgbError::SerializationError(e.to_string()))?;
+        consignment.migrate();
+        Ok(consignment)
+    }
+
+    /// Upgrade a JSON consignment in place to the `ConsignmentVersion::CURRENT`
+    /// layout
+    ///
+    /// Older minor versions keep their `minor_version` so that validation
+    /// knows which fields were migrated; newer ones are recorded as
+    /// `ConsignmentVersion::CURRENT`, whose fields they were read with.
+    ///
+    /// # Returns
+    ///
+    /// The version the consignment was written with
+    pub fn migrate_json(
+        value: &mut serde_json::Value,
+    ) -> Result<ConsignmentVersion, F1r3flyRgbError> {
+        let object = value.as_object_mut().ok_or_else(|| {
+            F1r3flyRgbError::SerializationError("Consignment must be a JSON object".to_string())
+        })?;
+
+        let major = json_u16_field(object, "version")?.ok_or_else(|| {
+            F1r3flyRgbError::SerializationError("Consignment has no version field".to_string())
+        })?;
+        let minor = json_u16_field(object, "minor_version")?.unwrap_or(0);
+        let version = ConsignmentVersion::new(major, minor);
+        version.check_supported()?;
+
+        if version.is_newer_minor() {
+            log::warn!(
+                "Consignment version {} is newer than {}; ignoring unknown fields",
+                version,
+                ConsignmentVersion::CURRENT
+            );
+            object.insert(
+                "minor_version".to_string(),
+                ConsignmentVersion::CURRENT.minor.into(),
+            );
+        } else {
+            for migration in JSON_MIGRATIONS_V1.iter().skip(minor as usize) {
+                migration(object);
+            }
+            object.insert("minor_version".to_string(), minor.into());
+        }
+
+        Ok(version)
+    }
+
+    /// Fill in what consignments written before 1.1 leave unknown
+    ///
+    /// Anchor methods are inferred from the anchors with
+    /// `AnchorMethod::infer_legacy`, for the transfer itself and for each
+    /// anchored history entry. Validation still reports the transfer's
+    /// inferred method as a warning (see `validate_report()`).
+    fn migrate(&mut self) {
+        if self.format_version() >= ConsignmentVersion::V1_1 {
+            return;
+        }
+        if !self.is_genesis && self.anchor_method.is_none() {
+            self.anchor_method = Some(AnchorMethod::infer_legacy(&self.bitcoin_anchor));
+        }
+        for entry in &mut self.history {
+            if let (None, Some(anchor)) = (entry.anchor_method, entry.bitcoin_anchor.as_ref()) {
+                entry.anchor_method = Some(AnchorMethod::infer_legacy(anchor));
+            }
+        }
     }
 
     /// Get contract ID
@@ -406,6 +1458,419 @@ impl F1r3flyConsignment {
     pub fn seals(&self) -> &SmallOrdMap<u16, WTxoSeal> {
         &self.seals
     }
+
+    /// Get provenance history
+    pub fn history(&self) -> &[HistoryEntry] {
+        &self.history
+    }
+}
+
+/// Verify that a witness transaction commits to the given state hash
+///
+/// Dispatches on the anchor method; an anchor whose proof doesn't fit its
+/// method (e.g. a Tapret anchor without a Tapret proof) is rejected. Anchors
+/// carrying an MPC path are checked from the state hash up to the tree root
+/// that the witness transaction commits to.
+fn verify_witness_commitment(
+    witness_tx: &Tx,
+    contract_id: ContractId,
+    state_hash: [u8; 32],
+    anchor: &Anchor,
+    method: AnchorMethod,
+) -> Result<(), F1r3flyRgbError> {
+    let commitment = anchor_commitment(anchor, contract_id, state_hash).map_err(|e| {
+        F1r3flyRgbError::InvalidConsignment(format!("MPC proof verification failed: {}", e))
+    })?;
+
+    match method {
+        AnchorMethod::Tapret { output } => {
+            log::debug!(
+                "Verifying Bitcoin anchor with Tapret proof (output {})",
+                output
+            );
+
+            let tapret_proof = anchor.dbc_proof.as_ref().ok_or_else(|| {
+                F1r3flyRgbError::InvalidConsignment(
+                    "Malformed Tapret anchor: no Tapret proof".to_string(),
+                )
+            })?;
+
+            check_tapret_host(witness_tx, output as usize).map_err(|e| {
+                F1r3flyRgbError::InvalidConsignment(format!("Tapret host check failed: {}", e))
+            })?;
+
+            // Perform full cryptographic verification
+            verify_tapret_proof_in_tx(witness_tx, commitment, tapret_proof).map_err(|e| {
+                F1r3flyRgbError::InvalidConsignment(format!(
+                    "Tapret proof verification failed: {}",
+                    e
+                ))
+            })?;
+
+            log::debug!("✓ Tapret proof cryptographically verified");
+        }
+        AnchorMethod::OpReturn { output } => {
+            log::debug!(
+                "Verifying Bitcoin anchor with OP_RETURN (output {})",
+                output
+            );
+
+            if anchor.dbc_proof.is_some() {
+                return Err(F1r3flyRgbError::InvalidConsignment(
+                    "Malformed OP_RETURN anchor: carries a Tapret proof".to_string(),
+                ));
+            }
+
+            // Opret DBC verification on the declared output (state hash or MPC root)
+            verify_opret_proof_in_tx(witness_tx, output as usize, commitment).map_err(|e| {
+                F1r3flyRgbError::InvalidConsignment(format!("OP_RETURN verification failed: {}", e))
+            })?;
+
+            log::debug!("✓ OP_RETURN commitment cryptographically verified");
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolve seals to the Bitcoin outpoints they define
+///
+/// `WOutpoint::Wout` seals point into the witness transaction and need it for resolution.
+fn resolve_seal_outpoints(
+    seals: &SmallOrdMap<u16, WTxoSeal>,
+    witness_tx: Option<&Tx>,
+) -> Vec<Outpoint> {
+    seals
+        .values()
+        .filter_map(|seal| match seal.primary {
+            WOutpoint::Extern(outpoint) => Some(outpoint),
+            WOutpoint::Wout(vout) => witness_tx.map(|tx| Outpoint::new(tx.txid(), vout)),
+        })
+        .collect()
+}
+
+/// Amount received at a witness mapping, with the seal key holding it
+///
+/// The witness identifier holds the amount until the recipient claims it,
+/// the real UTXO afterwards. The real UTXO may hold other balance as well,
+/// so after the claim the amount comes from the contract's claim record,
+/// which must name this witness identifier; without one nothing was received.
+pub(crate) async fn mapped_balance(
+    executor: &F1r3flyExecutor,
+    contract_id: ContractId,
+    witness_tx: &Tx,
+    mapping: &WitnessMapping,
+) -> Result<(SealKey, u64), F1r3flyRgbError> {
+    let witness_key = mapping.witness_id;
+    let balance = query_balance(executor, contract_id, &witness_key).await?;
+    if balance != 0 {
+        return Ok((witness_key, balance));
+    }
+
+    // Already claimed: amount moved to the real UTXO
+    let utxo_key = SealKey::from(Outpoint::new(witness_tx.txid(), mapping.expected_vout));
+    let amount = match query_claim(executor, contract_id, &utxo_key).await? {
+        Some(record) if record.witness_id == witness_key => record.amount,
+        _ => 0,
+    };
+    Ok((utxo_key, amount))
+}
+
+/// Check whether a transaction spends at least one of the given outpoints
+fn spends_any(tx: &Tx, outpoints: &[Outpoint]) -> bool {
+    tx.inputs
+        .iter()
+        .any(|input| outpoints.contains(&input.prev_output))
+}
+
+// ============================================================================
+// Binary Wire Format
+// ============================================================================
+
+/// Strict-encoded consignment body
+///
+/// Mirrors `F1r3flyConsignment` with bounded collections; strings are stored
+/// as UTF-8 blobs. Field order is part of the format.
+#[derive(Clone, Debug, StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_F1R3FLY_RGB)]
+struct ConsignmentWire {
+    contract_id: ContractId,
+    registry_uri: SmallBlob,
+    methods: TinyVec<SmallBlob>,
+    rholang_source: MediumBlob,
+    f1r3fly_proof: StateProofWire,
+    bitcoin_anchor: Anchor,
+    seals: SmallOrdMap<u16, WTxoSeal>,
+    witness_txs: SmallVec<Tx>,
+    is_genesis: bool,
+    witness_mapping: Option<WitnessMappingWire>,
+    history: SmallVec<HistoryEntryWire>,
+    anchor_method: Option<AnchorMethodWire>,
+    opid: Option<Opid>,
+}
+
+impl StrictSerialize for ConsignmentWire {}
+impl StrictDeserialize for ConsignmentWire {}
+
+#[derive(Clone, Debug, StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_F1R3FLY_RGB)]
+struct StateProofWire {
+    block_hash: SmallBlob,
+    state_hash: [u8; 32],
+    deploy_id: SmallBlob,
+}
+
+#[derive(Clone, Debug, StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_F1R3FLY_RGB)]
+struct WitnessMappingWire {
+    witness_id: SmallBlob,
+    recipient_address: SmallBlob,
+    expected_vout: u32,
+}
+
+#[derive(Clone, Debug, StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_F1R3FLY_RGB)]
+pub(crate) struct AnchorMethodWire {
+    /// 0 = Tapret, 1 = OP_RETURN
+    method: u8,
+    output: u32,
+}
+
+impl From<AnchorMethod> for AnchorMethodWire {
+    fn from(method: AnchorMethod) -> Self {
+        match method {
+            AnchorMethod::Tapret { output } => Self { method: 0, output },
+            AnchorMethod::OpReturn { output } => Self { method: 1, output },
+        }
+    }
+}
+
+impl TryFrom<AnchorMethodWire> for AnchorMethod {
+    type Error = F1r3flyRgbError;
+
+    fn try_from(wire: AnchorMethodWire) -> Result<Self, Self::Error> {
+        match wire.method {
+            0 => Ok(Self::Tapret {
+                output: wire.output,
+            }),
+            1 => Ok(Self::OpReturn {
+                output: wire.output,
+            }),
+            tag => Err(F1r3flyRgbError::SerializationError(format!(
+                "Unknown anchor method tag: {}",
+                tag
+            ))),
+        }
+    }
+}
+
+#[derive(Clone, Debug, StrictType, StrictDumb, StrictEncode, StrictDecode)]
+#[strict_type(lib = LIB_NAME_F1R3FLY_RGB)]
+struct HistoryEntryWire {
+    /// 0 = issue, 1 = transfer, 2 = claim
+    operation: u8,
+    opid: Opid,
+    f1r3fly_proof: StateProofWire,
+    bitcoin_anchor: Option<Anchor>,
+    witness_tx: Option<Tx>,
+    seals: SmallOrdMap<u16, WTxoSeal>,
+    anchor_method: Option<AnchorMethodWire>,
+}
+
+impl TryFrom<&F1r3flyConsignment> for ConsignmentWire {
+    type Error = F1r3flyRgbError;
+
+    fn try_from(consignment: &F1r3flyConsignment) -> Result<Self, Self::Error> {
+        let metadata = &consignment.contract_metadata;
+        let methods = metadata
+            .methods
+            .iter()
+            .map(|method| wire_blob(method, "method name"))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            contract_id: consignment.contract_id,
+            registry_uri: wire_blob(&metadata.registry_uri, "registry URI")?,
+            methods: wire_confine(methods, "method list")?,
+            rholang_source: wire_confine(
+                metadata.rholang_source.as_bytes().to_vec(),
+                "Rholang source",
+            )?,
+            f1r3fly_proof: StateProofWire::try_from(&consignment.f1r3fly_proof)?,
+            bitcoin_anchor: consignment.bitcoin_anchor.clone(),
+            seals: consignment.seals.clone(),
+            witness_txs: wire_confine(consignment.witness_txs.clone(), "witness transactions")?,
+            is_genesis: consignment.is_genesis,
+            witness_mapping: consignment
+                .witness_mapping
+                .as_ref()
+                .map(|mapping| {
+                    Ok::<_, F1r3flyRgbError>(WitnessMappingWire {
+                        witness_id: wire_blob(&mapping.witness_id.to_string(), "witness ID")?,
+                        recipient_address: wire_blob(
+                            &mapping.recipient_address,
+                            "recipient address",
+                        )?,
+                        expected_vout: mapping.expected_vout,
+                    })
+                })
+                .transpose()?,
+            history: wire_confine(
+                consignment
+                    .history
+                    .iter()
+                    .map(HistoryEntryWire::try_from)
+                    .collect::<Result<Vec<_>, _>>()?,
+                "history",
+            )?,
+            anchor_method: consignment.anchor_method.map(AnchorMethodWire::from),
+            opid: consignment.opid,
+        })
+    }
+}
+
+impl ConsignmentWire {
+    fn into_consignment(
+        self,
+        version: ConsignmentVersion,
+    ) -> Result<F1r3flyConsignment, F1r3flyRgbError> {
+        Ok(F1r3flyConsignment {
+            version: version.major,
+            minor_version: version.minor,
+            contract_id: self.contract_id,
+            contract_metadata: ContractMetadata {
+                registry_uri: wire_string(self.registry_uri.release(), "registry URI")?,
+                methods: self
+                    .methods
+                    .release()
+                    .into_iter()
+                    .map(|method| wire_string(method.release(), "method name"))
+                    .collect::<Result<_, _>>()?,
+                rholang_source: wire_string(self.rholang_source.release(), "Rholang source")?,
+            },
+            f1r3fly_proof: self.f1r3fly_proof.try_into()?,
+            bitcoin_anchor: self.bitcoin_anchor,
+            anchor_method: self.anchor_method.map(AnchorMethod::try_from).transpose()?,
+            opid: self.opid,
+            seals: self.seals,
+            witness_txs: self.witness_txs.release(),
+            is_genesis: self.is_genesis,
+            witness_mapping: self
+                .witness_mapping
+                .map(|mapping| {
+                    Ok::<_, F1r3flyRgbError>(WitnessMapping {
+                        witness_id: wire_string(mapping.witness_id.release(), "witness ID")?
+                            .parse()
+                            .map_err(F1r3flyRgbError::InvalidSealKey)?,
+                        recipient_address: wire_string(
+                            mapping.recipient_address.release(),
+                            "recipient address",
+                        )?,
+                        expected_vout: mapping.expected_vout,
+                    })
+                })
+                .transpose()?,
+            history: self
+                .history
+                .release()
+                .into_iter()
+                .map(HistoryEntry::try_from)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl TryFrom<&F1r3flyStateProof> for StateProofWire {
+    type Error = F1r3flyRgbError;
+
+    fn try_from(proof: &F1r3flyStateProof) -> Result<Self, Self::Error> {
+        Ok(Self {
+            block_hash: wire_blob(&proof.block_hash, "block hash")?,
+            state_hash: proof.state_hash,
+            deploy_id: wire_blob(&proof.deploy_id, "deploy ID")?,
+        })
+    }
+}
+
+impl TryFrom<StateProofWire> for F1r3flyStateProof {
+    type Error = F1r3flyRgbError;
+
+    fn try_from(wire: StateProofWire) -> Result<Self, Self::Error> {
+        Ok(Self {
+            block_hash: wire_string(wire.block_hash.release(), "block hash")?,
+            state_hash: wire.state_hash,
+            deploy_id: wire_string(wire.deploy_id.release(), "deploy ID")?,
+        })
+    }
+}
+
+impl TryFrom<&HistoryEntry> for HistoryEntryWire {
+    type Error = F1r3flyRgbError;
+
+    fn try_from(entry: &HistoryEntry) -> Result<Self, Self::Error> {
+        Ok(Self {
+            operation: match entry.operation {
+                HistoryOperation::Issue => 0,
+                HistoryOperation::Transfer => 1,
+                HistoryOperation::Claim => 2,
+            },
+            opid: entry.opid,
+            f1r3fly_proof: StateProofWire::try_from(&entry.f1r3fly_proof)?,
+            bitcoin_anchor: entry.bitcoin_anchor.clone(),
+            witness_tx: entry.witness_tx.clone(),
+            seals: entry.seals.clone(),
+            anchor_method: entry.anchor_method.map(AnchorMethodWire::from),
+        })
+    }
+}
+
+impl TryFrom<HistoryEntryWire> for HistoryEntry {
+    type Error = F1r3flyRgbError;
+
+    fn try_from(wire: HistoryEntryWire) -> Result<Self, Self::Error> {
+        let operation = match wire.operation {
+            0 => HistoryOperation::Issue,
+            1 => HistoryOperation::Transfer,
+            2 => HistoryOperation::Claim,
+            tag => {
+                return Err(F1r3flyRgbError::SerializationError(format!(
+                    "Unknown history operation tag: {}",
+                    tag
+                )))
+            }
+        };
+        Ok(Self {
+            operation,
+            opid: wire.opid,
+            f1r3fly_proof: wire.f1r3fly_proof.try_into()?,
+            bitcoin_anchor: wire.bitcoin_anchor,
+            anchor_method: wire.anchor_method.map(AnchorMethod::try_from).transpose()?,
+            witness_tx: wire.witness_tx,
+            seals: wire.seals,
+        })
+    }
+}
+
+/// Confine a collection for the wire format, naming the field on overflow
+fn wire_confine<C, T>(collection: C, field: &str) -> Result<T, F1r3flyRgbError>
+where
+    T: TryFrom<C>,
+    T::Error: std::fmt::Display,
+{
+    T::try_from(collection)
+        .map_err(|e| F1r3flyRgbError::SerializationError(format!("{} too large: {}", field, e)))
+}
+
+/// Encode string as a wire blob
+fn wire_blob(value: &str, field: &str) -> Result<SmallBlob, F1r3flyRgbError> {
+    wire_confine(value.as_bytes().to_vec(), field)
+}
+
+/// Decode wire blob as UTF-8 string
+fn wire_string(bytes: Vec<u8>, field: &str) -> Result<String, F1r3flyRgbError> {
+    String::from_utf8(bytes).map_err(|e| {
+        F1r3flyRgbError::SerializationError(format!("{} is not valid UTF-8: {}", field, e))
+    })
 }
 
 // Custom serialization for ContractId
@@ -3,16 +3,63 @@
 //! This module provides a unified API for interacting with a single F1r3fly-RGB
 //! contract, coordinating the executor, tracker, and metadata.
 
+use crate::witness_tx::RECIPIENT_VOUT;
 use crate::{
-    BitcoinAnchorTracker, ContractMetadata, F1r3flyExecutionResult, F1r3flyExecutor,
-    F1r3flyRgbError, RholangContractLibrary,
+    extract_seal, generate_freeze_claim_signature, generate_nonce, generate_revert_claim_signature,
+    generate_transfer_signature, generate_transfer_with_change_signature, get_recipient_pubkey,
+    BitcoinAnchorTracker, ContractMetadata, F1r3flyConsignment, F1r3flyExecutionResult,
+    F1r3flyExecutor, F1r3flyRgbError, ParsedInvoice, RholangContractLibrary, SealKey,
+    WitnessMapping, WitnessTxBuilder,
 };
 use amplify::confinement::SmallOrdMap;
-use bp::seals::{TxoSeal, WTxoSeal};
-use hypersonic::ContractId;
+use bp::seals::{TxoSeal, WOutpoint, WTxoSeal};
+use bp::{Sats, Txid, Vout};
+use bpstd::psbt::Psbt;
+use hypersonic::{ContractId, Opid};
 use rgb::Pile;
+use rgb_invoice::RgbBeneficiary;
+use secp256k1::{PublicKey, SecretKey};
 use strict_types::StrictVal;
 
+/// Transfer produced by `F1r3flyRgbContract::send()`
+#[derive(Clone, Debug)]
+pub struct SentTransfer {
+    /// RGB operation ID of the transfer
+    pub opid: Opid,
+
+    /// Consignment to deliver to the recipient
+    pub consignment: F1r3flyConsignment,
+
+    /// Unsigned witness PSBT with the commitment embedded
+    pub psbt: Psbt,
+
+    /// Witness transaction ID (tracked as `Tentative`)
+    pub txid: Txid,
+
+    /// Absolute fee of the witness transaction
+    pub fee: Sats,
+
+    /// Witness mapping of the change output holding the remainder of the
+    /// spent seal (`None` if the whole balance was sent), to claim it once
+    /// the witness confirms
+    pub change_mapping: Option<WitnessMapping>,
+}
+
+/// Claim of a witness balance recorded by the contract for a real UTXO
+///
+/// See `F1r3flyRgbContract::claim_of()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClaimRecord {
+    /// Witness identifier the balance was claimed from
+    pub witness_id: SealKey,
+
+    /// Amount moved to the real UTXO by the claim
+    pub amount: u64,
+
+    /// Frozen by the issuer after the witness lost confirmation
+    pub frozen: bool,
+}
+
 /// High-level API for a single F1r3fly-RGB contract
 ///
 /// Coordinates F1r3fly execution, Bitcoin anchor tracking, and contract metadata.
@@ -165,10 +212,14 @@ impl F1r3flyRgbContract {
                 vec![
                     "issue".to_string(),
                     "transfer".to_string(),
+                    "transferWithChange".to_string(),
                     "balanceOf".to_string(),
                     "getMetadata".to_string(),
                     "claim".to_string(),
                     "ownerOf".to_string(),
+                    "freezeClaim".to_string(),
+                    "revertClaim".to_string(),
+                    "claimOf".to_string(),
                 ],
             )
             .await?;
@@ -261,6 +312,306 @@ impl F1r3flyRgbContract {
         Ok(result)
     }
 
+    /// Send tokens to an invoice
+    ///
+    /// Runs the whole transfer flow and returns a consignment ready for
+    /// delivery together with the unsigned witness PSBT:
+    ///
+    /// 1. Check the invoice (contract, amount, taproot `WitnessOut` address
+    ///    paid by `psbt_builder`) and the balance of `from_seal`
+    /// 2. Compose the witness transaction with a placeholder commitment, so
+    ///    coin selection and fee errors surface before anything executes
+    /// 3. Sign and execute `transferWithChange` on F1r3fly, moving the amount
+    ///    to the recipient's witness identifier and the remainder of
+    ///    `from_seal` to the witness identifier of the change output (owned by
+    ///    `signer`) in one call; `transfer` if nothing remains
+    /// 4. Compose the witness transaction committing to the new state hash;
+    ///    its recipient and change outputs must keep the dry run's witness
+    ///    identifiers, which the transfer credited
+    /// 5. Record the recipient and change seals, witness, anchor and fee-bump
+    ///    builder in the tracker (see `BitcoinAnchorTracker::add_witness_tx`)
+    /// 6. Build the consignment and persist the tracker
+    ///
+    /// `psbt_builder` pays the invoice address and carries the wallet's UTXOs,
+    /// fee rate, change key and commitment type; `send` sets its state hash and
+    /// adds `from_seal` to the spent seals.
+    ///
+    /// The recipient's witness identifier is registered to the output key of
+    /// the invoice address (see `get_recipient_pubkey`), which the recipient
+    /// signs its claim with.
+    ///
+    /// The F1r3fly transfer can't be undone. If a later step fails, the
+    /// operation's tracker records are discarded and `SendIncomplete` is
+    /// returned with its operation ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `invoice` - Recipient's invoice
+    /// * `from_seal` - Seal holding the tokens to send
+    /// * `signer` - Key owning `from_seal` on F1r3fly
+    /// * `psbt_builder` - Witness transaction builder
+    ///
+    /// # Errors
+    ///
+    /// `SendFailed` if the invoice, balance or witness transaction is rejected
+    /// (including a non-taproot invoice address or a remainder without a
+    /// change output), the executor's error if the F1r3fly transfer fails
+    /// (nothing changed in both cases), and `SendIncomplete` for failures
+    /// after it
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use f1r3fly_rgb::{parse_invoice, F1r3flyRgbContract, WitnessTxBuilder};
+    /// # use bp::seals::TxoSeal;
+    /// # async fn example(mut contract: F1r3flyRgbContract, from_seal: TxoSeal, key: secp256k1::SecretKey, builder: WitnessTxBuilder) -> Result<(), Box<dyn std::error::Error>> {
+    /// let invoice = parse_invoice("rgb:tb1p...@contract:xFS2u...:~:0:100")?;
+    /// let sent = contract.send(&invoice, &from_seal, &key, builder).await?;
+    ///
+    /// // Sign and broadcast sent.psbt, deliver sent.consignment
+    /// let bytes = sent.consignment.to_bytes()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send(
+        &mut self,
+        invoice: &ParsedInvoice,
+        from_seal: &TxoSeal,
+        signer: &SecretKey,
+        psbt_builder: WitnessTxBuilder,
+    ) -> Result<SentTransfer, F1r3flyRgbError> {
+        // Invoice
+        if invoice.contract_id != self.contract_id {
+            return Err(F1r3flyRgbError::SendFailed(format!(
+                "Invoice is for contract {}, not {}",
+                invoice.contract_id, self.contract_id
+            )));
+        }
+        let amount = invoice
+            .amount
+            .filter(|amount| *amount > 0)
+            .ok_or_else(|| F1r3flyRgbError::SendFailed("Invoice has no amount".to_string()))?;
+        let RgbBeneficiary::WitnessOut(wout) = &invoice.beneficiary else {
+            return Err(F1r3flyRgbError::SendFailed(
+                "Invoice beneficiary is not a WitnessOut address".to_string(),
+            ));
+        };
+        if wout.script_pubkey().as_slice() != psbt_builder.recipient_script().as_slice() {
+            return Err(F1r3flyRgbError::SendFailed(format!(
+                "Witness transaction pays {}, not the invoice address",
+                psbt_builder.recipient_address()
+            )));
+        }
+        let recipient_pubkey = get_recipient_pubkey(&invoice.beneficiary)
+            .map_err(|e| F1r3flyRgbError::SendFailed(e.to_string()))?;
+
+        // Balance
+        let from = SealKey::from(from_seal);
+        let balance = query_balance(&self.executor, self.contract_id, &from).await?;
+        if balance < amount {
+            return Err(F1r3flyRgbError::SendFailed(format!(
+                "Seal {} holds {}, invoice requests {}",
+                from, balance, amount
+            )));
+        }
+
+        // Dry run: the commitment doesn't affect coin selection, fees or the
+        // output layout
+        let builder = psbt_builder.spend_seal(from_seal.primary);
+        let dry_run = builder
+            .clone()
+            .build()
+            .map_err(|e| F1r3flyRgbError::SendFailed(e.to_string()))?;
+
+        // The spent seal is emptied; its remainder goes to the change output
+        let remainder = balance - amount;
+        let change_mapping = match (remainder, dry_run.change_mapping) {
+            (0, _) => None,
+            (_, Some(mapping)) => Some(mapping),
+            (_, None) => {
+                return Err(F1r3flyRgbError::SendFailed(format!(
+                    "Remainder of {} needs a change output",
+                    remainder
+                )))
+            }
+        };
+
+        // F1r3fly transfer: amount and change move together, under one state hash
+        let to = dry_run.witness_mapping.witness_id;
+        let change = change_mapping.as_ref().map(|mapping| mapping.witness_id);
+        let result = self
+            .signed_transfer(
+                &from,
+                &to,
+                amount,
+                change.as_ref(),
+                &recipient_pubkey,
+                signer,
+            )
+            .await?;
+        let opid = result.opid;
+
+        // Witness, tracker and consignment; rolled back on failure
+        self.record_transfer(
+            invoice,
+            builder,
+            result,
+            dry_run.witness_mapping,
+            change_mapping,
+        )
+        .map_err(|reason| {
+            if self.tracker.discard_op(opid) {
+                if let Err(e) = self.tracker.try_commit_transaction() {
+                    log::warn!("Rollback of {} not persisted: {}", opid, e);
+                }
+            }
+            log::error!("Send of {} incomplete: {}", opid, reason);
+            F1r3flyRgbError::SendIncomplete {
+                opid: opid.to_string(),
+                reason,
+            }
+        })
+    }
+
+    /// Sign and execute a `transfer`, registering `to_pubkey` as owner of `to`
+    ///
+    /// With `change`, executes `transferWithChange` instead, which also moves
+    /// the rest of `from` to `change` (owned by `signer`).
+    async fn signed_transfer(
+        &mut self,
+        from: &SealKey,
+        to: &SealKey,
+        amount: u64,
+        change: Option<&SealKey>,
+        to_pubkey: &PublicKey,
+        signer: &SecretKey,
+    ) -> Result<F1r3flyExecutionResult, F1r3flyRgbError> {
+        let nonce = generate_nonce();
+        let signature = match change {
+            Some(change) => {
+                generate_transfer_with_change_signature(from, to, amount, change, nonce, signer)
+            }
+            None => generate_transfer_signature(from, to, amount, nonce, signer),
+        }
+        .map_err(|e| F1r3flyRgbError::SendFailed(format!("Signing failed: {}", e)))?;
+        let (from, to) = (from.to_string(), to.to_string());
+        let to_pubkey = hex::encode(to_pubkey.serialize_uncompressed());
+
+        let mut params = vec![
+            ("from", StrictVal::from(from.as_str())),
+            ("to", StrictVal::from(to.as_str())),
+            ("amount", StrictVal::from(amount)),
+            ("toPubKey", StrictVal::from(to_pubkey.as_str())),
+        ];
+        let method = match change {
+            Some(change) => {
+                log::info!(
+                    "Sending {} from {} to {}, change to {}",
+                    amount,
+                    from,
+                    to,
+                    change
+                );
+                params.push(("change", StrictVal::from(change.to_string().as_str())));
+                "transferWithChange"
+            }
+            None => {
+                log::info!("Sending {} from {} to {}", amount, from, to);
+                "transfer"
+            }
+        };
+        params.push(("nonce", StrictVal::from(nonce)));
+        params.push(("fromSignatureHex", StrictVal::from(signature.as_str())));
+
+        self.executor
+            .call_method(self.contract_id, method, &params)
+            .await
+    }
+
+    /// Compose the witness, track the transfer and build its consignment
+    ///
+    /// `witness_mapping` and `change_mapping` are those of the dry run, whose
+    /// witness identifiers the F1r3fly transfer credited; the final witness
+    /// transaction must map the same ones.
+    fn record_transfer(
+        &mut self,
+        invoice: &ParsedInvoice,
+        builder: WitnessTxBuilder,
+        result: F1r3flyExecutionResult,
+        witness_mapping: WitnessMapping,
+        change_mapping: Option<WitnessMapping>,
+    ) -> Result<SentTransfer, String> {
+        let opid = result.opid;
+        // Kept configured for fee bumps, which re-embed the same commitment
+        let builder = builder
+            .state_hash(result.state_hash)
+            .contract_id(self.contract_id);
+        let witness = builder
+            .clone()
+            .build()
+            .map_err(|e| format!("witness transaction failed: {}", e))?;
+
+        // Tokens sit at the dry run's witness identifiers; outputs must back them
+        if witness.witness_mapping != witness_mapping {
+            return Err(format!(
+                "witness transaction maps {} to output {}, transfer credited {} at output {}",
+                witness.witness_mapping.witness_id,
+                witness.witness_mapping.expected_vout,
+                witness_mapping.witness_id,
+                witness_mapping.expected_vout
+            ));
+        }
+        if let Some(mapping) = &change_mapping {
+            if witness.change_mapping.as_ref() != Some(mapping) {
+                return Err(format!(
+                    "witness transaction has no change output for {}",
+                    mapping.witness_id
+                ));
+            }
+        }
+
+        // Recipient seal on the witness output
+        let mut seal = extract_seal(&invoice.beneficiary).map_err(|e| e.to_string())?;
+        seal.primary = WOutpoint::Wout(Vout::from(RECIPIENT_VOUT));
+        let mut seals = SmallOrdMap::new();
+        seals.insert(0u16, seal).map_err(|e| e.to_string())?;
+        // Sender's change seal on the change output
+        if let Some(mapping) = &change_mapping {
+            seals
+                .insert(1u16, WTxoSeal::from(mapping.witness_id))
+                .map_err(|e| e.to_string())?;
+        }
+
+        self.tracker.add_seals(opid, seals.clone());
+        let txid = self.tracker.add_witness_tx(opid, builder, &witness);
+
+        let mut consignment = F1r3flyConsignment::new(self, result, seals, Vec::new(), false)
+            .map_err(|e| e.to_string())?;
+        consignment
+            .replace_witness(&witness)
+            .map_err(|e| e.to_string())?;
+
+        self.tracker
+            .try_commit_transaction()
+            .map_err(|e| format!("tracker not persisted: {}", e))?;
+
+        log::info!(
+            "Transfer {} ready: witness {}, fee {} sats",
+            opid,
+            txid,
+            witness.fee.sats()
+        );
+
+        Ok(SentTransfer {
+            opid,
+            consignment,
+            psbt: witness.psbt,
+            txid,
+            fee: witness.fee,
+            change_mapping,
+        })
+    }
+
     /// Query token balance for a seal
     ///
     /// Queries the F1r3fly shard for the current balance of a given seal (UTXO).
@@ -285,41 +636,14 @@ impl F1r3flyRgbContract {
     /// # }
     /// ```
     pub async fn balance(&self, seal: &TxoSeal) -> Result<u64, F1r3flyRgbError> {
-        // Serialize seal using its primary outpoint (txid:vout)
-        // This is the standard Bitcoin UTXO identifier format
-        let seal_id = Self::serialize_seal(seal);
+        // Balances are keyed by the seal's primary outpoint (txid:vout)
+        let seal_key = SealKey::from(seal);
 
         log::info!("📊 CONTRACT: balance() called");
         log::info!("  Input seal: {:?}", seal);
-        log::info!("  Serialized seal_id: {}", seal_id);
-        log::info!("  StrictVal type: {:?}", StrictVal::from(seal_id.clone()));
+        log::info!("  Seal key: {}", seal_key);
 
-        let result = self
-            .executor
-            .query_state(
-                self.contract_id,
-                "balanceOf",
-                &[("seal", StrictVal::from(seal_id.clone()))],
-            )
-            .await?;
-
-        log::info!("  Query result: {:?}", result);
-
-        // Parse balance from JSON result
-        // query_state returns serde_json::Value
-        result
-            .as_u64()
-            .or_else(|| {
-                result
-                    .as_i64()
-                    .and_then(|n| if n >= 0 { Some(n as u64) } else { None })
-            })
-            .ok_or_else(|| {
-                F1r3flyRgbError::InvalidStateFormat(format!(
-                    "Expected unsigned integer for balance, got: {:?}",
-                    result
-                ))
-            })
+        query_balance(&self.executor, self.contract_id, &seal_key).await
     }
 
     /// Query the owner of a UTXO
@@ -351,9 +675,9 @@ impl F1r3flyRgbContract {
         log::info!("📊 CONTRACT: owner_of() called");
 
         // Serialize seal to query parameter
-        let seal_id = Self::serialize_seal(seal);
+        let seal_id = SealKey::from(seal).to_string();
         log::info!("  Input seal: {:?}", seal);
-        log::info!("  Serialized seal_id: {}", seal_id);
+        log::info!("  Seal key: {}", seal_id);
 
         // Query the contract
         let result = self
@@ -385,9 +709,139 @@ impl F1r3flyRgbContract {
         }
     }
 
+    /// Query the claim recorded for a real UTXO
+    ///
+    /// Returns `None` if the UTXO didn't receive a balance through `claim`.
+    pub async fn claim_of(
+        &self,
+        real_utxo: &SealKey,
+    ) -> Result<Option<ClaimRecord>, F1r3flyRgbError> {
+        query_claim(&self.executor, self.contract_id, real_utxo).await
+    }
+
+    /// Freeze (or unfreeze) a claimed UTXO whose witness lost confirmation
+    ///
+    /// Issuer side of `BitcoinAnchorTracker::reorged_ops()`: while frozen,
+    /// transfers from `real_utxo` are rejected. Unfreeze once the witness is
+    /// mined again, or use `revert_claim()` if it won't be.
+    ///
+    /// # Arguments
+    ///
+    /// * `real_utxo` - UTXO the balance was claimed to
+    /// * `freeze` - `true` to freeze the claim, `false` to unfreeze it
+    /// * `signer` - Deployer key, as for `issue`
+    ///
+    /// # Errors
+    ///
+    /// `ClaimFailed` if no claim is recorded for `real_utxo` or the contract
+    /// didn't apply the call
+    pub async fn freeze_claim(
+        &mut self,
+        real_utxo: &SealKey,
+        freeze: bool,
+        signer: &SecretKey,
+    ) -> Result<(), F1r3flyRgbError> {
+        if self.claim_of(real_utxo).await?.is_none() {
+            return Err(F1r3flyRgbError::ClaimFailed(format!(
+                "No claim recorded for {}",
+                real_utxo
+            )));
+        }
+
+        let real_utxo_id = real_utxo.to_string();
+        let action = if freeze { "freeze" } else { "unfreeze" };
+        let nonce = generate_nonce();
+        let signature = generate_freeze_claim_signature(real_utxo, freeze, nonce, signer)
+            .map_err(|e| F1r3flyRgbError::ClaimFailed(format!("Signing failed: {}", e)))?;
+
+        log::info!("Claim of {}: {}", real_utxo, action);
+        self.executor
+            .call_method(
+                self.contract_id,
+                "freezeClaim",
+                &[
+                    ("real_utxo", StrictVal::from(real_utxo_id.as_str())),
+                    ("action", StrictVal::from(action)),
+                    ("nonce", StrictVal::from(nonce)),
+                    ("signatureHex", StrictVal::from(signature.as_str())),
+                ],
+            )
+            .await?;
+
+        // The contract reports rejections in its return value only
+        match self.claim_of(real_utxo).await? {
+            Some(record) if record.frozen == freeze => Ok(()),
+            _ => Err(F1r3flyRgbError::ClaimFailed(format!(
+                "{} of {} not applied (signer is not the deployer?)",
+                action, real_utxo
+            ))),
+        }
+    }
+
+    /// Undo a claim whose witness transaction will not confirm
+    ///
+    /// Moves the claimed amount from `real_utxo` back to the witness
+    /// identifier and restores its owner, so the recipient can claim again if
+    /// the witness is ever mined. Part of the amount may already have been
+    /// transferred onward; only what `real_utxo` still holds is moved.
+    ///
+    /// # Arguments
+    ///
+    /// * `real_utxo` - UTXO the balance was claimed to
+    /// * `signer` - Deployer key, as for `issue`
+    ///
+    /// # Returns
+    ///
+    /// Amount restored to the witness identifier
+    ///
+    /// # Errors
+    ///
+    /// `ClaimFailed` if no claim is recorded for `real_utxo` or the contract
+    /// didn't apply the call
+    pub async fn revert_claim(
+        &mut self,
+        real_utxo: &SealKey,
+        signer: &SecretKey,
+    ) -> Result<u64, F1r3flyRgbError> {
+        let record = self.claim_of(real_utxo).await?.ok_or_else(|| {
+            F1r3flyRgbError::ClaimFailed(format!("No claim recorded for {}", real_utxo))
+        })?;
+        let before = query_balance(&self.executor, self.contract_id, &record.witness_id).await?;
+
+        let real_utxo_id = real_utxo.to_string();
+        let nonce = generate_nonce();
+        let signature = generate_revert_claim_signature(real_utxo, nonce, signer)
+            .map_err(|e| F1r3flyRgbError::ClaimFailed(format!("Signing failed: {}", e)))?;
+
+        log::info!("Reverting claim of {} to {}", real_utxo, record.witness_id);
+        self.executor
+            .call_method(
+                self.contract_id,
+                "revertClaim",
+                &[
+                    ("real_utxo", StrictVal::from(real_utxo_id.as_str())),
+                    ("nonce", StrictVal::from(nonce)),
+                    ("signatureHex", StrictVal::from(signature.as_str())),
+                ],
+            )
+            .await?;
+
+        // The contract reports rejections in its return value only
+        if self.claim_of(real_utxo).await?.is_some() {
+            return Err(F1r3flyRgbError::ClaimFailed(format!(
+                "Revert of {} not applied (signer is not the deployer?)",
+                real_utxo
+            )));
+        }
+        let after = query_balance(&self.executor, self.contract_id, &record.witness_id).await?;
+
+        Ok(after.saturating_sub(before))
+    }
+
     /// Serialize a TxoSeal to a stable string identifier
     ///
-    /// Uses the primary outpoint (txid:vout) as the seal identifier.
+    /// Uses the primary outpoint (txid:vout) as the seal identifier; see
+    /// `SealKey` for the format and parsing.
     /// This format is:
     /// - Deterministic: Same seal always produces the same ID
     /// - Standard: Matches Bitcoin UTXO format
@@ -408,23 +862,7 @@ impl F1r3flyRgbContract {
     /// # }
     /// ```
     pub fn serialize_seal(seal: &TxoSeal) -> String {
-        use amplify::ByteArray;
-
-        // Extract primary outpoint (txid + vout)
-        let outpoint = seal.primary;
-
-        // bp::Txid stores bytes in internal (little-endian) format
-        // We need to reverse them to get the standard Bitcoin display format (big-endian)
-        // This must match the format used in asset issuance and claim operations
-        let mut txid_bytes = outpoint.txid.to_byte_array();
-        txid_bytes.reverse(); // Convert from little-endian to big-endian
-        let txid_hex = hex::encode(txid_bytes);
-
-        // Get vout as u32
-        let vout = outpoint.vout.into_u32();
-
-        // Format as "txid:vout" (standard Bitcoin outpoint format)
-        format!("{}:{}", txid_hex, vout)
+        SealKey::from(seal).to_string()
     }
 
     /// Get contract ID
@@ -457,3 +895,86 @@ impl F1r3flyRgbContract {
         &self.tracker
     }
 }
+
+/// Query the Rho20 balance registered under a seal key
+///
+/// The key is either a real UTXO or a witness-relative output.
+pub(crate) async fn query_balance(
+    executor: &F1r3flyExecutor,
+    contract_id: ContractId,
+    seal_key: &SealKey,
+) -> Result<u64, F1r3flyRgbError> {
+    let seal_id = seal_key.to_string();
+    let result = executor
+        .query_state(
+            contract_id,
+            "balanceOf",
+            &[("seal", StrictVal::from(seal_id.as_str()))],
+        )
+        .await?;
+
+    log::info!("  Query result: {:?}", result);
+
+    // Parse balance from JSON result
+    // query_state returns serde_json::Value
+    result
+        .as_u64()
+        .or_else(|| {
+            result
+                .as_i64()
+                .and_then(|n| if n >= 0 { Some(n as u64) } else { None })
+        })
+        .ok_or_else(|| {
+            F1r3flyRgbError::InvalidStateFormat(format!(
+                "Expected unsigned integer for balance, got: {:?}",
+                result
+            ))
+        })
+}
+
+/// Claim recorded by the Rho20 contract for a real UTXO
+///
+/// # Returns
+///
+/// The claim record, or `None` if the UTXO didn't receive a balance through
+/// `claim`
+pub(crate) async fn query_claim(
+    executor: &F1r3flyExecutor,
+    contract_id: ContractId,
+    real_utxo: &SealKey,
+) -> Result<Option<ClaimRecord>, F1r3flyRgbError> {
+    let real_utxo = real_utxo.to_string();
+    let result = executor
+        .query_state(
+            contract_id,
+            "claimOf",
+            &[("real_utxo", StrictVal::from(real_utxo.as_str()))],
+        )
+        .await?;
+
+    let invalid =
+        || F1r3flyRgbError::InvalidStateFormat(format!("Expected claim record, got: {:?}", result));
+    let record = result.as_object().ok_or_else(invalid)?;
+    if record.is_empty() {
+        return Ok(None);
+    }
+    let witness_id = record
+        .get("witness_id")
+        .and_then(|id| id.as_str())
+        .ok_or_else(invalid)?
+        .parse()
+        .map_err(F1r3flyRgbError::InvalidSealKey)?;
+    let amount = record
+        .get("amount")
+        .and_then(|amount| amount.as_u64())
+        .ok_or_else(invalid)?;
+    let frozen = record
+        .get("frozen")
+        .and_then(|frozen| frozen.as_bool())
+        .ok_or_else(invalid)?;
+    Ok(Some(ClaimRecord {
+        witness_id,
+        amount,
+        frozen,
+    }))
+}
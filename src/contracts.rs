@@ -3,10 +3,51 @@
 //! Provides a unified interface for managing multiple F1r3fly-RGB contracts,
 //! similar to RGB's `Contracts` type.
 
-use crate::{F1r3flyExecutor, F1r3flyRgbContract, F1r3flyRgbError, RholangContractLibrary};
-use hypersonic::ContractId;
+use crate::consignment::mapped_balance;
+use crate::{
+    F1r3flyConsignment, F1r3flyExecutor, F1r3flyRgbContract, F1r3flyRgbError,
+    RholangContractLibrary, SealKey, WitnessMapping,
+};
+use bp::{Outpoint, Txid};
+use hypersonic::{ContractId, Opid};
+use rgb::{Pile, WitnessStatus};
 use std::collections::HashMap;
 
+/// Summary of a consignment accepted by `F1r3flyRgbContracts::accept_consignment()`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AcceptedConsignment {
+    /// Contract the consignment is for
+    pub contract_id: ContractId,
+
+    /// Operation the seals, witness and anchor were filed under
+    pub opid: Opid,
+
+    /// Whether the contract was imported from the consignment
+    pub new_contract: bool,
+
+    /// Whether this was a genesis consignment
+    pub is_genesis: bool,
+
+    /// Witness transaction added to the tracker (transfers only)
+    pub witness_id: Option<Txid>,
+
+    /// Outpoints of the received seals
+    pub seals: Vec<Outpoint>,
+
+    /// F1r3fly seal key holding the received balance (transfers with a
+    /// witness mapping: the witness key until claimed, then the UTXO)
+    pub seal_id: Option<SealKey>,
+
+    /// Witness mapping of the recipient output (transfers only), used to
+    /// claim the balance once the witness confirms (see `ClaimManager`)
+    pub witness_mapping: Option<WitnessMapping>,
+
+    /// Amount received at `seal_id`, as queried from F1r3fly: the witness
+    /// key's balance until claimed, the claim record's amount after (zero
+    /// without a witness mapping, where no recipient seal can be identified)
+    pub amount: u64,
+}
+
 /// Collection of F1r3fly-RGB contracts
 ///
 /// Manages multiple contracts with a shared executor. Provides high-level
@@ -109,10 +150,14 @@ impl F1r3flyRgbContracts {
                 vec![
                     "issue".to_string(),
                     "transfer".to_string(),
+                    "transferWithChange".to_string(),
                     "balanceOf".to_string(),
                     "getMetadata".to_string(),
                     "claim".to_string(),
                     "ownerOf".to_string(),
+                    "freezeClaim".to_string(),
+                    "revertClaim".to_string(),
+                    "claimOf".to_string(),
                 ],
             )
             .await?;
@@ -250,6 +295,137 @@ impl F1r3flyRgbContracts {
         self.contracts.contains_key(id)
     }
 
+    /// Validate a received consignment and file it into the collection
+    ///
+    /// 1. Decodes the consignment (any format) and runs `validate()`;
+    ///    transfers must carry exactly one witness transaction
+    /// 2. Imports the contract from `contract_metadata` if it is new, and
+    ///    registers it with the executor
+    /// 3. Adds the seals, witness transaction (as `Tentative` unless already
+    ///    known) and anchor to the contract's tracker under the consigned
+    ///    operation ID
+    /// 4. Commits the tracker (a no-op unless it has a persistence store)
+    ///
+    /// The received amount is queried from F1r3fly at the recipient's seal
+    /// (see `AcceptedConsignment::amount`); a witness mapping whose seal
+    /// received nothing is rejected. Balances
+    /// are queried before anything is changed, so a failure leaves the
+    /// collection as it was. Accepting the same consignment twice is
+    /// harmless.
+    ///
+    /// # Returns
+    ///
+    /// Summary of the received allocation
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use f1r3fly_rgb::F1r3flyRgbContracts;
+    /// # async fn example(mut contracts: F1r3flyRgbContracts, bytes: Vec<u8>) -> Result<(), Box<dyn std::error::Error>> {
+    /// let accepted = contracts.accept_consignment(&bytes).await?;
+    /// println!("Received {} of {}", accepted.amount, accepted.contract_id);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn accept_consignment(
+        &mut self,
+        bytes: &[u8],
+    ) -> Result<AcceptedConsignment, F1r3flyRgbError> {
+        let consignment = F1r3flyConsignment::from_bytes(bytes)?;
+        // The anchor and witness mapping describe a single witness
+        if !consignment.is_genesis && consignment.witness_txs.len() > 1 {
+            return Err(F1r3flyRgbError::InvalidConsignment(format!(
+                "Transfer consignment carries {} witness transactions, expected one",
+                consignment.witness_txs.len()
+            )));
+        }
+        consignment.validate(&self.executor).await?;
+
+        let contract_id = consignment.contract_id;
+        let opid = consignment.operation_id();
+        let seals = consignment.seal_outpoints();
+        let witness_tx = consignment
+            .witness_txs
+            .first()
+            .filter(|_| !consignment.is_genesis);
+
+        // Received amount, read from the recipient's seal
+        let (seal_id, amount) = match (witness_tx, &consignment.witness_mapping) {
+            (Some(witness_tx), Some(mapping)) => {
+                let (seal_id, amount) =
+                    mapped_balance(&self.executor, contract_id, witness_tx, mapping).await?;
+                if amount == 0 {
+                    return Err(F1r3flyRgbError::InvalidConsignment(format!(
+                        "Recipient seal {} received nothing of {}",
+                        seal_id, contract_id
+                    )));
+                }
+                (Some(seal_id), amount)
+            }
+            _ => (None, 0),
+        };
+
+        // Contract
+        let metadata = consignment.contract_metadata.clone();
+        if self.executor.get_contract_metadata(contract_id).is_none() {
+            self.executor
+                .register_contract(contract_id, metadata.clone());
+        }
+        let new_contract = !self.contracts.contains_key(&contract_id);
+        if new_contract {
+            let contract = F1r3flyRgbContract::new(contract_id, self.executor.clone(), metadata)?;
+            self.contracts.insert(contract_id, contract);
+            log::info!("Imported contract {} from consignment", contract_id);
+        }
+
+        // Tracker
+        let tracker = self
+            .contracts
+            .get_mut(&contract_id)
+            .expect("Contract registered above")
+            .tracker_mut();
+        tracker.add_seals(opid, consignment.seals.clone());
+        let witness_id = witness_tx.map(|tx| {
+            let txid = tx.txid();
+            // Keep a status the chain sync already established
+            let status = match tracker.has_witness(txid) {
+                true => tracker.witness_status(txid),
+                false => WitnessStatus::Tentative,
+            };
+            tracker.add_witness(opid, txid, tx, &consignment.bitcoin_anchor, status);
+            match consignment.anchor_method {
+                Some(method) => {
+                    tracker.add_anchor_with_method(opid, consignment.bitcoin_anchor.clone(), method)
+                }
+                None => tracker.add_anchor(opid, consignment.bitcoin_anchor.clone()),
+            }
+            txid
+        });
+        tracker
+            .try_commit_transaction()
+            .map_err(|e| F1r3flyRgbError::PersistenceFailed(e.to_string()))?;
+
+        log::info!(
+            "Accepted consignment for {}: {} at {} seal(s) (operation {})",
+            contract_id,
+            amount,
+            seals.len(),
+            opid
+        );
+
+        Ok(AcceptedConsignment {
+            contract_id,
+            opid,
+            new_contract,
+            is_genesis: consignment.is_genesis,
+            witness_id,
+            seals,
+            seal_id,
+            witness_mapping: consignment.witness_mapping.clone(),
+            amount,
+        })
+    }
+
     /// Register a loaded contract
     ///
     /// Used during state restoration to add contracts back to the collection.
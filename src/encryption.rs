@@ -0,0 +1,535 @@
+//! Encryption at rest for persisted tracker and executor state
+//!
+//! Persistence files reveal a wallet's UTXO history, opids and anchors. They
+//! can optionally be sealed with XChaCha20-Poly1305 under an `EncryptionKey`,
+//! either supplied by the application (32 bytes) or derived from a
+//! passphrase with Argon2id.
+//!
+//! # File Format
+//!
+//! `ENCRYPTED_MAGIC` (8 bytes) || version (u8) || KDF (u8: 0 = raw key,
+//! 1 = Argon2id) || salt (16 bytes) || Argon2 memory KiB, iterations,
+//! parallelism (u32 LE each) || nonce (24 bytes) || ciphertext and tag.
+//! The header is authenticated as associated data, so KDF parameters can't
+//! be swapped without detection. A fresh nonce is drawn for every write.
+//!
+//! With a key configured, a file without the magic is rejected rather than
+//! read as plaintext, so whoever can write the file can't swap in unsealed
+//! state. Existing plaintext files are migrated with `rekey_file()`, which
+//! changes the key (or adds or removes encryption) of a file and its `.bak`
+//! backup. Argon2 costs in a header are capped (`MAX_KDF_MEMORY_KIB` and
+//! friends) before deriving, so a crafted file can't force a huge allocation.
+
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use zeroize::Zeroizing;
+
+use crate::anchor_store::{replace_atomic, sidecar_path, write_atomic, PersistenceLock};
+use crate::bitcoin_anchor::BitcoinAnchorError;
+
+/// Magic bytes at the start of an encrypted file
+pub const ENCRYPTED_MAGIC: [u8; 8] = *b"F1RGBENC";
+
+/// Current encrypted file format version
+pub const ENCRYPTION_VERSION: u8 = 1;
+
+const KDF_RAW: u8 = 0;
+const KDF_ARGON2ID: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const HEADER_LEN: usize = 8 + 1 + 1 + SALT_LEN + 12 + NONCE_LEN;
+
+/// Largest Argon2 memory cost accepted from a file header (1 GiB)
+pub const MAX_KDF_MEMORY_KIB: u32 = 1024 * 1024;
+
+/// Largest Argon2 iteration count accepted from a file header
+pub const MAX_KDF_ITERATIONS: u32 = 64;
+
+/// Largest Argon2 parallelism accepted from a file header
+pub const MAX_KDF_PARALLELISM: u32 = 16;
+
+/// Errors sealing or opening an encrypted file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EncryptionError {
+    /// File is encrypted but no key was configured
+    MissingKey,
+    /// A key is configured but the file is plaintext; migrate it with
+    /// `rekey_file()`
+    NotEncrypted(String),
+    /// Wrong key, or the file was modified
+    Authentication,
+    /// Truncated header, unknown version or KDF
+    InvalidFormat(String),
+    /// Key derivation failed (invalid Argon2 parameters)
+    KeyDerivation(String),
+    /// Reading or writing the file failed
+    Io(String),
+}
+
+impl fmt::Display for EncryptionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingKey => write!(f, "File is encrypted and no key is configured"),
+            Self::NotEncrypted(path) => write!(
+                f,
+                "{} is not encrypted but a key is configured; encrypt it with rekey_file()",
+                path
+            ),
+            Self::Authentication => write!(f, "Wrong key or corrupted file"),
+            Self::InvalidFormat(msg) => write!(f, "Invalid encrypted file: {}", msg),
+            Self::KeyDerivation(msg) => write!(f, "Key derivation failed: {}", msg),
+            Self::Io(msg) => write!(f, "I/O error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for EncryptionError {}
+
+impl From<EncryptionError> for BitcoinAnchorError {
+    fn from(e: EncryptionError) -> Self {
+        match e {
+            EncryptionError::Io(msg) => BitcoinAnchorError::PersistenceError(msg),
+            e => BitcoinAnchorError::Encryption(e.to_string()),
+        }
+    }
+}
+
+/// Argon2id cost parameters stored in the file header
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct KdfParams {
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+impl KdfParams {
+    /// Reject costs above the `MAX_KDF_*` limits
+    fn check_limits(&self) -> Result<(), EncryptionError> {
+        if self.memory_kib > MAX_KDF_MEMORY_KIB
+            || self.iterations > MAX_KDF_ITERATIONS
+            || self.parallelism > MAX_KDF_PARALLELISM
+        {
+            return Err(EncryptionError::InvalidFormat(format!(
+                "Argon2 cost m={} KiB, t={}, p={} exceeds the maximum",
+                self.memory_kib, self.iterations, self.parallelism
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+enum Secret {
+    Passphrase(Zeroizing<String>),
+    Raw(Zeroizing<[u8; 32]>),
+}
+
+/// Key derived for one salt, reused until a file with another salt is read
+struct Derived {
+    salt: [u8; SALT_LEN],
+    params: KdfParams,
+    key: Zeroizing<[u8; 32]>,
+}
+
+/// Key for encrypted persistence files
+///
+/// Cheap to clone; a passphrase is stretched once per salt and the derived
+/// key cached, so auto-saves don't pay for Argon2 on every commit.
+#[derive(Clone)]
+pub struct EncryptionKey {
+    secret: Secret,
+    derived: Arc<Mutex<Option<Derived>>>,
+}
+
+impl EncryptionKey {
+    /// Key derived from a passphrase with Argon2id
+    pub fn passphrase(passphrase: impl Into<String>) -> Self {
+        Self::with_secret(Secret::Passphrase(Zeroizing::new(passphrase.into())))
+    }
+
+    /// 32-byte key supplied by the application (e.g. from a keystore)
+    pub fn from_bytes(key: [u8; 32]) -> Self {
+        Self::with_secret(Secret::Raw(Zeroizing::new(key)))
+    }
+
+    fn with_secret(secret: Secret) -> Self {
+        Self {
+            secret,
+            derived: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Cipher and KDF header fields for a new file
+    fn for_write(
+        &self,
+    ) -> Result<(XChaCha20Poly1305, u8, [u8; SALT_LEN], KdfParams), EncryptionError> {
+        match &self.secret {
+            Secret::Raw(key) => Ok((
+                cipher(key),
+                KDF_RAW,
+                [0u8; SALT_LEN],
+                KdfParams {
+                    memory_kib: 0,
+                    iterations: 0,
+                    parallelism: 0,
+                },
+            )),
+            Secret::Passphrase(passphrase) => {
+                let mut derived = self.derived.lock().expect("Key cache poisoned");
+                if derived.is_none() {
+                    let mut salt = [0u8; SALT_LEN];
+                    OsRng.fill_bytes(&mut salt);
+                    let params = KdfParams::default();
+                    let key = derive(passphrase, &salt, params)?;
+                    *derived = Some(Derived { salt, params, key });
+                }
+                let derived = derived.as_ref().expect("Derived above");
+                Ok((
+                    cipher(&derived.key),
+                    KDF_ARGON2ID,
+                    derived.salt,
+                    derived.params,
+                ))
+            }
+        }
+    }
+
+    /// Cipher for a file with the given KDF header fields
+    fn for_read(
+        &self,
+        kdf: u8,
+        salt: [u8; SALT_LEN],
+        params: KdfParams,
+    ) -> Result<XChaCha20Poly1305, EncryptionError> {
+        match (&self.secret, kdf) {
+            (Secret::Raw(key), KDF_RAW) => Ok(cipher(key)),
+            (Secret::Passphrase(passphrase), KDF_ARGON2ID) => {
+                let mut derived = self.derived.lock().expect("Key cache poisoned");
+                match derived.as_ref() {
+                    Some(cached) if cached.salt == salt && cached.params == params => {
+                        Ok(cipher(&cached.key))
+                    }
+                    _ => {
+                        let key = derive(passphrase, &salt, params)?;
+                        let cipher = cipher(&key);
+                        *derived = Some(Derived { salt, params, key });
+                        Ok(cipher)
+                    }
+                }
+            }
+            // A passphrase can't open a raw-key file and vice versa
+            (_, KDF_RAW | KDF_ARGON2ID) => Err(EncryptionError::Authentication),
+            (_, kdf) => Err(EncryptionError::InvalidFormat(format!(
+                "unknown KDF {}",
+                kdf
+            ))),
+        }
+    }
+}
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.secret {
+            Secret::Passphrase(_) => "passphrase",
+            Secret::Raw(_) => "raw",
+        };
+        f.debug_struct("EncryptionKey")
+            .field("kind", &kind)
+            .finish_non_exhaustive()
+    }
+}
+
+fn cipher(key: &[u8; 32]) -> XChaCha20Poly1305 {
+    XChaCha20Poly1305::new(key.into())
+}
+
+fn derive(
+    passphrase: &str,
+    salt: &[u8; SALT_LEN],
+    params: KdfParams,
+) -> Result<Zeroizing<[u8; 32]>, EncryptionError> {
+    let params = Params::new(
+        params.memory_kib,
+        params.iterations,
+        params.parallelism,
+        Some(32),
+    )
+    .map_err(|e| EncryptionError::KeyDerivation(e.to_string()))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|e| EncryptionError::KeyDerivation(e.to_string()))?;
+    Ok(key)
+}
+
+/// Whether `data` is an encrypted file
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(&ENCRYPTED_MAGIC)
+}
+
+/// Encrypt `plaintext` into the encrypted file format
+pub fn encrypt(key: &EncryptionKey, plaintext: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+    let (cipher, kdf, salt, params) = key.for_write()?;
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+    let mut data = Vec::with_capacity(HEADER_LEN + plaintext.len() + 16);
+    data.extend_from_slice(&ENCRYPTED_MAGIC);
+    data.push(ENCRYPTION_VERSION);
+    data.push(kdf);
+    data.extend_from_slice(&salt);
+    data.extend_from_slice(&params.memory_kib.to_le_bytes());
+    data.extend_from_slice(&params.iterations.to_le_bytes());
+    data.extend_from_slice(&params.parallelism.to_le_bytes());
+    data.extend_from_slice(&nonce);
+
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad: &data,
+            },
+        )
+        .map_err(|_| EncryptionError::Authentication)?;
+    data.extend_from_slice(&ciphertext);
+    Ok(data)
+}
+
+/// Decrypt data in the encrypted file format
+pub fn decrypt(key: &EncryptionKey, data: &[u8]) -> Result<Vec<u8>, EncryptionError> {
+    if !is_encrypted(data) {
+        return Err(EncryptionError::InvalidFormat(
+            "missing magic number".to_string(),
+        ));
+    }
+    if data.len() < HEADER_LEN {
+        return Err(EncryptionError::InvalidFormat(
+            "truncated header".to_string(),
+        ));
+    }
+    let (header, ciphertext) = data.split_at(HEADER_LEN);
+    if header[8] != ENCRYPTION_VERSION {
+        return Err(EncryptionError::InvalidFormat(format!(
+            "version {} is not supported (expected {})",
+            header[8], ENCRYPTION_VERSION
+        )));
+    }
+
+    let u32_at = |at: usize| u32::from_le_bytes(header[at..at + 4].try_into().expect("4 bytes"));
+    let salt: [u8; SALT_LEN] = header[10..10 + SALT_LEN].try_into().expect("Salt length");
+    let params = KdfParams {
+        memory_kib: u32_at(26),
+        iterations: u32_at(30),
+        parallelism: u32_at(34),
+    };
+    params.check_limits()?;
+    let nonce = XNonce::from_slice(&header[38..38 + NONCE_LEN]);
+
+    key.for_read(header[9], salt, params)?
+        .decrypt(
+            nonce,
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| EncryptionError::Authentication)
+}
+
+/// Read a file, decrypting it with `key`
+///
+/// Without a key the file must be plaintext; with one it must be encrypted.
+///
+/// # Errors
+/// `MissingKey` if the file is encrypted and `key` is `None`;
+/// `NotEncrypted` if it is plaintext and `key` is set
+pub fn read_file(path: &Path, key: Option<&EncryptionKey>) -> Result<Vec<u8>, EncryptionError> {
+    let data = std::fs::read(path).map_err(|e| EncryptionError::Io(e.to_string()))?;
+    match (key, is_encrypted(&data)) {
+        (Some(key), true) => decrypt(key, &data),
+        (Some(_), false) => Err(EncryptionError::NotEncrypted(format!("{:?}", path))),
+        (None, true) => Err(EncryptionError::MissingKey),
+        (None, false) => Ok(data),
+    }
+}
+
+/// Write a file crash-safely (keeping `<path>.bak`), encrypted if `key` is set
+pub fn write_file(
+    path: &Path,
+    data: &[u8],
+    key: Option<&EncryptionKey>,
+) -> Result<(), EncryptionError> {
+    let sealed;
+    let data = match key {
+        Some(key) => {
+            sealed = encrypt(key, data)?;
+            &sealed
+        }
+        None => data,
+    };
+    write_atomic(path, data).map_err(|e| EncryptionError::Io(e.to_string()))
+}
+
+/// Change the key of a file and its `.bak` backup
+///
+/// `None` as `old` reads a plaintext file; `None` as `new` writes plaintext,
+/// so this also adds or removes encryption. The file is rewritten first; if
+/// interrupted before the backup is, the new file is still readable with
+/// `new`. Takes the advisory lock on `path`, so fails with `Locked` while a
+/// tracker is persisting to it; use `BitcoinAnchorTracker::rekey()` then.
+///
+/// # Errors
+/// Returns error if a file can't be decrypted with `old` or written
+pub fn rekey_file<P: AsRef<Path>>(
+    path: P,
+    old: Option<&EncryptionKey>,
+    new: Option<&EncryptionKey>,
+) -> Result<(), BitcoinAnchorError> {
+    let path = path.as_ref();
+    let _lock = PersistenceLock::acquire(path)?;
+    rekey_locked(path, old, new)
+}
+
+/// `rekey_file()` for callers already holding the lock on `path`
+pub(crate) fn rekey_locked(
+    path: &Path,
+    old: Option<&EncryptionKey>,
+    new: Option<&EncryptionKey>,
+) -> Result<(), BitcoinAnchorError> {
+    let backup = sidecar_path(path, "bak");
+    let mut previous = match backup.exists() {
+        true => Some(read_file(&backup, old)?),
+        false => None,
+    };
+
+    if path.exists() {
+        let current = read_file(path, old)?;
+        // Copies the old-key file to the backup, which is replaced below
+        write_file(path, &current, new)?;
+        previous.get_or_insert(current);
+    }
+
+    if let Some(previous) = previous {
+        let data = match new {
+            Some(key) => encrypt(key, &previous)?,
+            None => previous,
+        };
+        // The backup gets no backup of its own, which would keep the old key
+        replace_atomic(&backup, &data)
+            .map_err(|e| BitcoinAnchorError::PersistenceError(e.to_string()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const PLAINTEXT: &[u8] = b"{\"witnesses\":[]}";
+
+    #[test]
+    fn test_encrypt_round_trip() {
+        for key in [
+            EncryptionKey::from_bytes([1u8; 32]),
+            EncryptionKey::passphrase("correct horse battery staple"),
+        ] {
+            let sealed = encrypt(&key, PLAINTEXT).unwrap();
+            assert!(is_encrypted(&sealed));
+            assert!(!sealed.windows(PLAINTEXT.len()).any(|w| w == PLAINTEXT));
+            assert_eq!(decrypt(&key, &sealed).unwrap(), PLAINTEXT);
+
+            // A fresh nonce is drawn for every write
+            assert_ne!(encrypt(&key, PLAINTEXT).unwrap(), sealed);
+        }
+    }
+
+    #[test]
+    fn test_decrypt_wrong_key() {
+        let sealed = encrypt(&EncryptionKey::from_bytes([1u8; 32]), PLAINTEXT).unwrap();
+        assert_eq!(
+            decrypt(&EncryptionKey::from_bytes([2u8; 32]), &sealed),
+            Err(EncryptionError::Authentication)
+        );
+        assert_eq!(
+            decrypt(&EncryptionKey::passphrase("wrong"), &sealed),
+            Err(EncryptionError::Authentication)
+        );
+
+        let sealed = encrypt(&EncryptionKey::passphrase("right"), PLAINTEXT).unwrap();
+        assert_eq!(
+            decrypt(&EncryptionKey::passphrase("wrong"), &sealed),
+            Err(EncryptionError::Authentication)
+        );
+    }
+
+    #[test]
+    fn test_decrypt_tampered() {
+        let key = EncryptionKey::from_bytes([1u8; 32]);
+        let sealed = encrypt(&key, PLAINTEXT).unwrap();
+
+        let mut ciphertext = sealed.clone();
+        *ciphertext.last_mut().unwrap() ^= 1;
+        assert_eq!(
+            decrypt(&key, &ciphertext),
+            Err(EncryptionError::Authentication)
+        );
+
+        // The header is authenticated too
+        let mut header = sealed.clone();
+        header[HEADER_LEN - 1] ^= 1;
+        assert_eq!(decrypt(&key, &header), Err(EncryptionError::Authentication));
+
+        assert!(matches!(
+            decrypt(&key, &sealed[..HEADER_LEN - 1]),
+            Err(EncryptionError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_decrypt_rejects_excessive_kdf_cost() {
+        let key = EncryptionKey::passphrase("passphrase");
+        let mut sealed = encrypt(&key, PLAINTEXT).unwrap();
+        sealed[26..30].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        // Rejected before deriving, not after a 4 TiB allocation
+        assert!(matches!(
+            decrypt(&key, &sealed),
+            Err(EncryptionError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_file_rejects_plaintext_with_key() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("state.json");
+        let key = EncryptionKey::from_bytes([1u8; 32]);
+        write_file(&path, PLAINTEXT, None).unwrap();
+
+        assert_eq!(read_file(&path, None).unwrap(), PLAINTEXT);
+        assert!(matches!(
+            read_file(&path, Some(&key)),
+            Err(EncryptionError::NotEncrypted(_))
+        ));
+
+        // Plaintext files are migrated explicitly
+        rekey_file(&path, None, Some(&key)).unwrap();
+        assert_eq!(read_file(&path, Some(&key)).unwrap(), PLAINTEXT);
+        assert_eq!(read_file(&path, None), Err(EncryptionError::MissingKey));
+    }
+}
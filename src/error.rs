@@ -3,6 +3,7 @@
 //! Minimal, production-ready error handling for F1r3node deployment
 //! and RGB contract execution.
 
+use crate::seal_key::SealKeyError;
 use std::error::Error as StdError;
 use std::fmt;
 
@@ -41,6 +42,97 @@ pub enum F1r3flyRgbError {
 
     /// Serialization error
     SerializationError(String),
+
+    /// Consignment format version not supported by this library
+    UnsupportedVersion { found: String, supported: String },
+
+    /// Consignment does not pay the invoice it was checked against
+    InvoiceMismatch(InvoiceMismatch),
+
+    /// Reading or writing persisted state failed (I/O or decryption)
+    PersistenceFailed(String),
+
+    /// Transfer rejected before anything was executed or recorded
+    SendFailed(String),
+
+    /// Transfer executed on F1r3fly, but its witness or consignment could not
+    /// be completed; the tracker records of the operation were rolled back
+    SendIncomplete { opid: String, reason: String },
+
+    /// Claim of a received balance was not applied on F1r3fly
+    ClaimFailed(String),
+
+    /// Seal identifier is not a canonical F1r3fly seal key
+    InvalidSealKey(SealKeyError),
+}
+
+/// Reason a consignment does not satisfy an invoice
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InvoiceMismatch {
+    /// Consignment is for a different contract
+    ContractId { expected: String, found: String },
+
+    /// Invoice beneficiary is not a `WitnessOut` address
+    UnsupportedBeneficiary,
+
+    /// Consignment has no witness transaction (e.g. genesis)
+    MissingWitnessTransaction,
+
+    /// Consignment has no witness mapping for the recipient output
+    MissingWitnessMapping,
+
+    /// Witness transaction has no output at the mapped vout
+    MissingOutput { vout: u32, outputs: usize },
+
+    /// Witness output does not pay the invoice address
+    Beneficiary { vout: u32, expected: String },
+
+    /// Balance at the resulting seal differs from the invoice amount
+    Amount { expected: u64, found: u64 },
+}
+
+impl fmt::Display for InvoiceMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ContractId { expected, found } => {
+                write!(
+                    f,
+                    "contract ID {} does not match invoice contract {}",
+                    found, expected
+                )
+            }
+            Self::UnsupportedBeneficiary => {
+                write!(f, "invoice beneficiary is not a WitnessOut address")
+            }
+            Self::MissingWitnessTransaction => {
+                write!(f, "consignment has no witness transaction")
+            }
+            Self::MissingWitnessMapping => {
+                write!(f, "consignment has no witness mapping")
+            }
+            Self::MissingOutput { vout, outputs } => {
+                write!(
+                    f,
+                    "witness transaction has no output {} ({} outputs)",
+                    vout, outputs
+                )
+            }
+            Self::Beneficiary { vout, expected } => {
+                write!(
+                    f,
+                    "witness output {} does not pay invoice address {}",
+                    vout, expected
+                )
+            }
+            Self::Amount { expected, found } => {
+                write!(
+                    f,
+                    "received amount {} does not match invoice amount {}",
+                    found, expected
+                )
+            }
+        }
+    }
 }
 
 impl fmt::Display for F1r3flyRgbError {
@@ -80,6 +172,35 @@ impl fmt::Display for F1r3flyRgbError {
             Self::SerializationError(msg) => {
                 write!(f, "Serialization error: {}", msg)
             }
+            Self::UnsupportedVersion { found, supported } => {
+                write!(
+                    f,
+                    "Unsupported consignment version {} (supported: {})",
+                    found, supported
+                )
+            }
+            Self::InvoiceMismatch(mismatch) => {
+                write!(f, "Consignment does not match invoice: {}", mismatch)
+            }
+            Self::PersistenceFailed(msg) => {
+                write!(f, "Persistence failed: {}", msg)
+            }
+            Self::SendFailed(msg) => {
+                write!(f, "Send failed: {}", msg)
+            }
+            Self::SendIncomplete { opid, reason } => {
+                write!(
+                    f,
+                    "Send incomplete: operation {} executed on F1r3fly, but {}",
+                    opid, reason
+                )
+            }
+            Self::ClaimFailed(msg) => {
+                write!(f, "Claim failed: {}", msg)
+            }
+            Self::InvalidSealKey(err) => {
+                write!(f, "Invalid seal key: {}", err)
+            }
         }
     }
 }
@@ -18,8 +18,10 @@ use node_cli::rholang_helpers::convert_rholang_to_json;
 use secp256k1::{PublicKey, Secp256k1, SecretKey};
 use serde_json::{json, Value};
 use std::collections::HashMap;
+use std::path::Path;
 use strict_types::StrictVal;
 
+use crate::encryption::{self, EncryptionKey};
 use crate::F1r3flyRgbError;
 
 /// Result of a F1r3fly execution (deploy or method call)
@@ -88,6 +90,53 @@ pub struct ContractMetadata {
     pub rholang_source: String,
 }
 
+/// Persistable executor state: derivation settings and contract registry
+///
+/// Restores an executor across sessions with `F1r3flyExecutor::restore()`.
+/// The registry reveals which contracts a wallet uses, so `save()` can
+/// encrypt the file (see `encryption`).
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ExecutorSnapshot {
+    /// Next derivation index
+    pub derivation_index: u32,
+
+    /// Whether deployments derive a new key
+    pub auto_derive: bool,
+
+    /// Registered contracts, keyed by hex contract ID
+    pub contracts: Vec<(String, ContractMetadata)>,
+}
+
+impl ExecutorSnapshot {
+    /// Write the snapshot as JSON, encrypted if `key` is set
+    ///
+    /// Writes crash-safely, keeping the previous file as `<path>.bak`.
+    pub fn save<P: AsRef<Path>>(
+        &self,
+        path: P,
+        key: Option<&EncryptionKey>,
+    ) -> Result<(), F1r3flyRgbError> {
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|e| F1r3flyRgbError::SerializationError(e.to_string()))?;
+        encryption::write_file(path.as_ref(), &json, key)
+            .map_err(|e| F1r3flyRgbError::PersistenceFailed(e.to_string()))
+    }
+
+    /// Read a snapshot written by `save()`
+    ///
+    /// With a key, a plaintext snapshot is rejected until migrated with
+    /// `rekey_file()`.
+    pub fn load<P: AsRef<Path>>(
+        path: P,
+        key: Option<&EncryptionKey>,
+    ) -> Result<Self, F1r3flyRgbError> {
+        let json = encryption::read_file(path.as_ref(), key)
+            .map_err(|e| F1r3flyRgbError::PersistenceFailed(e.to_string()))?;
+        serde_json::from_slice(&json)
+            .map_err(|e| F1r3flyRgbError::SerializationError(e.to_string()))
+    }
+}
+
 /// F1r3fly Executor - Production implementation
 ///
 /// This executor provides the complete API for F1r3fly operations:
@@ -261,6 +310,44 @@ impl F1r3flyExecutor {
         &self.contracts
     }
 
+    /// Capture derivation settings and the contract registry for persistence
+    pub fn snapshot(&self) -> ExecutorSnapshot {
+        let mut contracts: Vec<_> = self
+            .contracts
+            .iter()
+            .map(|(id, metadata)| (hex::encode(id.to_byte_array()), metadata.clone()))
+            .collect();
+        contracts.sort_by(|a, b| a.0.cmp(&b.0));
+
+        ExecutorSnapshot {
+            derivation_index: self.derivation_index,
+            auto_derive: self.auto_derive,
+            contracts,
+        }
+    }
+
+    /// Restore state captured by `snapshot()`
+    ///
+    /// Replaces the contract registry and derivation settings; the connection
+    /// is kept.
+    pub fn restore(&mut self, snapshot: ExecutorSnapshot) -> Result<(), F1r3flyRgbError> {
+        let mut contracts = HashMap::with_capacity(snapshot.contracts.len());
+        for (id, metadata) in snapshot.contracts {
+            let bytes: [u8; 32] = hex::decode(&id)
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| {
+                    F1r3flyRgbError::SerializationError(format!("Invalid contract ID {}", id))
+                })?;
+            contracts.insert(ContractId::from(bytes), metadata);
+        }
+
+        self.contracts = contracts;
+        self.derivation_index = snapshot.derivation_index;
+        self.auto_derive = snapshot.auto_derive;
+        Ok(())
+    }
+
     /// Check if a F1r3fly block is finalized
     ///
     /// Used for consignment validation to verify F1r3fly state is immutable.
@@ -1090,7 +1177,7 @@ fn compute_registry_uri_from_child_key(child_key: &SecretKey) -> Result<String,
 ///
 /// # Returns
 /// A 32-byte ContractId derived from the URI
-fn derive_contract_id_from_uri(registry_uri: &str) -> ContractId {
+pub(crate) fn derive_contract_id_from_uri(registry_uri: &str) -> ContractId {
     use blake2::digest::consts::U32;
     use blake2::{Blake2b, Digest};
 
@@ -1285,7 +1372,7 @@ fn parse_rholang_result(json_response: &Value) -> Result<Value, F1r3flyRgbError>
 ///
 /// # Returns
 /// A 32-byte array suitable for RGB commitment
-fn compute_state_hash(block_hash: &str, deploy_id: &str) -> [u8; 32] {
+pub(crate) fn compute_state_hash(block_hash: &str, deploy_id: &str) -> [u8; 32] {
     use blake2::digest::consts::U32;
     use blake2::{Blake2b, Digest};
 
@@ -404,6 +404,62 @@ pub fn get_recipient_address(
     }
 }
 
+/// Get the recipient's public key from RgbBeneficiary
+///
+/// For a taproot `WitnessOut` address, returns the output key with even Y.
+/// `F1r3flyRgbContract::send()` registers it as owner of the witness output,
+/// so the recipient signs its claim with the secret key of that output key
+/// (the tweaked key, negated if its Y is odd).
+///
+/// # Arguments
+///
+/// * `beneficiary` - Beneficiary from parsed invoice
+///
+/// # Errors
+///
+/// Returns error if:
+/// - Beneficiary is AuthToken (not supported in Phase 3)
+/// - Address is not a taproot (P2TR) address
+///
+/// # Example
+///
+/// ```ignore
+/// use f1r3fly_rgb::invoice::{parse_invoice, get_recipient_pubkey};
+///
+/// let parsed = parse_invoice("rgb:bcrt1p...@contract:xFS2u...:~:0:100")?;
+/// let pubkey = get_recipient_pubkey(&parsed.beneficiary)?;
+/// ```
+pub fn get_recipient_pubkey(
+    beneficiary: &RgbBeneficiary,
+) -> Result<secp256k1::PublicKey, F1r3flyRgbError> {
+    match beneficiary {
+        RgbBeneficiary::WitnessOut(wout) => {
+            // P2TR script: OP_1 OP_PUSHBYTES_32 <output key>
+            let script = wout.script_pubkey();
+            let output_key = match script.as_slice() {
+                [0x51, 0x20, key @ ..] if key.len() == 32 => key,
+                _ => {
+                    return Err(F1r3flyRgbError::InvalidResponse(
+                        "Recipient public key needs a taproot (P2TR) invoice address".to_string(),
+                    ))
+                }
+            };
+            let x_only = secp256k1::XOnlyPublicKey::from_slice(output_key).map_err(|e| {
+                F1r3flyRgbError::InvalidResponse(format!("Invalid taproot output key: {}", e))
+            })?;
+
+            Ok(secp256k1::PublicKey::from_x_only_public_key(
+                x_only,
+                secp256k1::Parity::Even,
+            ))
+        }
+        RgbBeneficiary::Token(_) => Err(F1r3flyRgbError::InvalidResponse(
+            "Cannot extract public key from AuthToken beneficiary. Use WitnessOut invoices."
+                .to_string(),
+        )),
+    }
+}
+
 // ============================================================================
 // Internal Helper Functions
 // ============================================================================
@@ -34,42 +34,81 @@
 //! ).await?;
 //! ```
 
+/// Strict type library name for F1r3fly-RGB data types
+pub const LIB_NAME_F1R3FLY_RGB: &str = "F1r3flyRGB";
+
 // Public modules
+pub mod anchor_schema;
+pub mod anchor_store;
+pub mod armor;
 pub mod bitcoin_anchor;
+pub mod chain;
+pub mod claim;
 pub mod consignment;
 pub mod contract;
 pub mod contract_library;
 pub mod contracts;
+pub mod encryption;
 pub mod error;
 pub mod executor;
 pub mod invoice;
+pub mod multi_protocol;
 pub mod opreturn;
+pub mod seal_key;
 pub mod signature_utils;
 pub mod tapret;
+pub mod validation;
+pub mod witness_tx;
 
 // Re-exports for convenience
-pub use bitcoin_anchor::{AnchorConfig, BitcoinAnchorError, BitcoinAnchorTracker};
-pub use consignment::{F1r3flyConsignment, F1r3flyStateProof, WitnessMapping};
-pub use contract::F1r3flyRgbContract;
+pub use anchor_schema::{check_file, Migration, SchemaReport, MIGRATIONS, TRACKER_SCHEMA_VERSION};
+pub use anchor_store::{
+    open_store, open_store_with_key, AnchorStore, JsonStore, LogStore, MemoryStore, StorageBackend,
+    StoredSeal,
+};
+pub use bitcoin_anchor::{
+    AnchorConfig, AnchorMethod, BitcoinAnchorError, BitcoinAnchorTracker, ConsistencyReport,
+    MergeReport, OutpointSeal, PrunePolicy, PruneReport, StatusKind, WitnessFilter,
+};
+pub use chain::{ChainSource, MockChain, MockChainError, SyncEvent, TxStatus};
+pub use claim::{
+    ClaimEvent, ClaimManager, ClaimStatus, PendingClaim, CLAIMS_VERSION,
+    DEFAULT_CLAIM_CONFIRMATIONS,
+};
+pub use consignment::{
+    ConsignmentFormat, ConsignmentVersion, F1r3flyConsignment, F1r3flyStateProof, HistoryEntry,
+    HistoryOperation, WitnessMapping, CONSIGNMENT_MAGIC, MAX_HISTORY_DEPTH,
+};
+pub use contract::{ClaimRecord, F1r3flyRgbContract, SentTransfer};
 pub use contract_library::RholangContractLibrary;
-pub use contracts::F1r3flyRgbContracts;
-pub use error::F1r3flyRgbError;
-pub use executor::{ContractMetadata, F1r3flyExecutionResult, F1r3flyExecutor};
+pub use contracts::{AcceptedConsignment, F1r3flyRgbContracts};
+pub use encryption::{rekey_file, EncryptionError, EncryptionKey};
+pub use error::{F1r3flyRgbError, InvoiceMismatch};
+pub use executor::{ContractMetadata, ExecutorSnapshot, F1r3flyExecutionResult, F1r3flyExecutor};
+pub use multi_protocol::{MpcError, MultiCommitment};
 pub use opreturn::{
-    create_opreturn_anchor, embed_opreturn_commitment, extract_opreturn_commitment, OpReturnError,
+    add_opret_host, create_opreturn_anchor, embed_opret_commitment_psbt, embed_opreturn_commitment,
+    extract_opreturn_commitment, verify_opret_proof_in_tx, OpReturnError,
 };
+pub use seal_key::{SealKey, SealKeyError, WITNESS_KEY_PREFIX};
 pub use signature_utils::{
-    generate_claim_signature, generate_issue_signature, generate_nonce, generate_transfer_signature,
+    generate_claim_signature, generate_freeze_claim_signature, generate_issue_signature,
+    generate_nonce, generate_revert_claim_signature, generate_transfer_signature,
+    generate_transfer_with_change_signature,
 };
 pub use tapret::{
-    create_anchor, create_tapret_anchor, create_test_psbt_with_taproot, embed_tapret_commitment,
-    extract_tapret_commitment, verify_tapret_commitment, verify_tapret_proof_in_tx, TapretError,
+    check_tapret_host, create_anchor, create_mpc_anchor, create_tapret_anchor,
+    create_tapret_anchor_in_psbt, create_tapret_mpc_anchors, create_test_psbt_with_taproot,
+    embed_tapret_commitment, extract_tapret_commitment, select_tapret_host,
+    verify_tapret_commitment, verify_tapret_proof_in_tx, TapretError, TapretHost,
 };
+pub use validation::{CheckKind, CheckStatus, CommitmentType, ValidationCheck, ValidationReport};
+pub use witness_tx::{witness_id, Utxo, WitnessTx, WitnessTxBuilder, WitnessTxError, MIN_FEE_RATE};
 
 // Re-export invoice module API
 pub use invoice::{
-    address_to_beneficiary, extract_seal, generate_invoice, get_recipient_address, parse_invoice,
-    GeneratedInvoice, InvoiceRequest, ParsedInvoice,
+    address_to_beneficiary, extract_seal, generate_invoice, get_recipient_address,
+    get_recipient_pubkey, parse_invoice, GeneratedInvoice, InvoiceRequest, ParsedInvoice,
 };
 
 // Re-export commonly used RGB types
@@ -0,0 +1,296 @@
+//! Multi-protocol commitments (MPC) for anchoring several contracts at once
+//!
+//! A single Tapret (or OP_RETURN) output commits to the root of an MPC tree.
+//! Each contract's state hash is a leaf message placed under the contract's
+//! protocol ID, so one Bitcoin transaction can anchor operations of many
+//! contracts. Each consignment carries only its own Merkle path in
+//! `Anchor::mpc_proof` / `Anchor::mpc_protocol`.
+//!
+//! # Flow
+//!
+//! ```rust,no_run
+//! use f1r3fly_rgb::multi_protocol::MultiCommitment;
+//! # use f1r3fly_rgb::ContractId;
+//! # fn example(a: ContractId, b: ContractId, hash_a: [u8; 32], hash_b: [u8; 32]) -> Result<(), Box<dyn std::error::Error>> {
+//! let mpc = MultiCommitment::build(&[(a, hash_a), (b, hash_b)])?;
+//!
+//! // Commit the root in the witness transaction...
+//! let root = mpc.root_bytes();
+//!
+//! // ...and hand each contract its own path
+//! let (protocol_a, proof_a) = mpc.proof_for(a)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Legacy Anchors
+//!
+//! Anchors created before MPC support commit the raw state hash and carry
+//! `strict_dumb()` placeholders for the MPC fields. `anchor_commitment()`
+//! recognises them and returns the state hash unchanged.
+
+use amplify::ByteArray;
+use bp::seals::{mpc, Anchor};
+use hypersonic::ContractId;
+use std::collections::BTreeMap;
+use strict_encoding::StrictDumb;
+
+pub type Result<T> = std::result::Result<T, MpcError>;
+
+#[derive(Debug)]
+pub enum MpcError {
+    /// No contracts to commit to
+    Empty,
+    /// Same contract appears twice in one commitment
+    DuplicateProtocol(String),
+    /// MPC tree construction failed (e.g. too many protocols)
+    TreeConstruction(String),
+    /// Contract is not part of this commitment
+    UnknownProtocol(String),
+    /// Anchor proof is for another contract
+    ProtocolMismatch { expected: String, found: String },
+    /// Merkle path doesn't convolve for the given message
+    InvalidProof(String),
+}
+
+impl std::fmt::Display for MpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "MPC commitment requires at least one contract"),
+            Self::DuplicateProtocol(id) => write!(f, "Contract {} committed twice", id),
+            Self::TreeConstruction(msg) => write!(f, "MPC tree construction failed: {}", msg),
+            Self::UnknownProtocol(id) => write!(f, "Contract {} is not in the MPC tree", id),
+            Self::ProtocolMismatch { expected, found } => {
+                write!(
+                    f,
+                    "MPC protocol mismatch: expected {}, found {}",
+                    expected, found
+                )
+            }
+            Self::InvalidProof(msg) => write!(f, "Invalid MPC proof: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for MpcError {}
+
+/// MPC protocol ID of a contract
+pub fn protocol_id(contract_id: ContractId) -> mpc::ProtocolId {
+    mpc::ProtocolId::from(contract_id.to_byte_array())
+}
+
+/// MPC message for a F1r3fly state hash
+pub fn message(state_hash: [u8; 32]) -> mpc::Message {
+    mpc::Message::from(state_hash)
+}
+
+/// Check whether an anchor predates MPC support (raw state hash commitment)
+pub fn is_legacy_anchor(anchor: &Anchor) -> bool {
+    anchor.mpc_protocol == mpc::ProtocolId::strict_dumb()
+        && anchor.mpc_proof == mpc::MerkleProof::strict_dumb()
+}
+
+/// Compute the value an anchor must commit to in the witness transaction
+///
+/// For MPC anchors, checks that the proof belongs to `contract_id` and
+/// convolves the Merkle path from `state_hash` up to the tree root. For
+/// legacy anchors, returns `state_hash` itself.
+pub fn anchor_commitment(
+    anchor: &Anchor,
+    contract_id: ContractId,
+    state_hash: [u8; 32],
+) -> Result<[u8; 32]> {
+    if is_legacy_anchor(anchor) {
+        return Ok(state_hash);
+    }
+
+    let expected = protocol_id(contract_id);
+    if anchor.mpc_protocol != expected {
+        return Err(MpcError::ProtocolMismatch {
+            expected: expected.to_string(),
+            found: anchor.mpc_protocol.to_string(),
+        });
+    }
+
+    let root = anchor
+        .mpc_proof
+        .convolve(expected, message(state_hash))
+        .map_err(|e| MpcError::InvalidProof(format!("{:?}", e)))?;
+
+    Ok(root.to_byte_array())
+}
+
+/// MPC tree over the state hashes of several contracts
+#[derive(Clone, Debug)]
+pub struct MultiCommitment {
+    /// Tree root committed in the witness transaction
+    root: mpc::Commitment,
+
+    /// Merkle block used to extract per-contract proofs
+    block: mpc::MerkleBlock,
+
+    /// Contracts included in the tree
+    contracts: BTreeMap<mpc::ProtocolId, ContractId>,
+}
+
+impl MultiCommitment {
+    /// Build an MPC tree from (contract, state hash) pairs
+    ///
+    /// # Errors
+    /// Returns error if the list is empty, a contract appears twice, or the
+    /// tree can't be constructed.
+    pub fn build(entries: &[(ContractId, [u8; 32])]) -> Result<Self> {
+        use commit_verify::{CommitId, TryCommitVerify};
+
+        if entries.is_empty() {
+            return Err(MpcError::Empty);
+        }
+
+        let mut source = mpc::MultiSource::default();
+        let mut contracts = BTreeMap::new();
+        for (contract_id, state_hash) in entries {
+            let protocol = protocol_id(*contract_id);
+            if contracts.insert(protocol, *contract_id).is_some() {
+                return Err(MpcError::DuplicateProtocol(contract_id.to_string()));
+            }
+            source
+                .messages
+                .insert(protocol, message(*state_hash))
+                .map_err(|e| MpcError::TreeConstruction(e.to_string()))?;
+        }
+
+        let tree = mpc::MerkleTree::try_commit(&source)
+            .map_err(|e| MpcError::TreeConstruction(e.to_string()))?;
+        let root = tree.commit_id();
+        let block = mpc::MerkleBlock::from(&tree);
+
+        log::debug!(
+            "MPC tree built: {} contract(s), root {}",
+            contracts.len(),
+            hex::encode(root.to_byte_array())
+        );
+
+        Ok(Self {
+            root,
+            block,
+            contracts,
+        })
+    }
+
+    /// Tree root commitment
+    pub fn root(&self) -> mpc::Commitment {
+        self.root
+    }
+
+    /// Tree root as bytes, ready for `embed_tapret_commitment` / `embed_opreturn_commitment`
+    pub fn root_bytes(&self) -> [u8; 32] {
+        self.root.to_byte_array()
+    }
+
+    /// Contracts included in the tree
+    pub fn contracts(&self) -> impl Iterator<Item = ContractId> + '_ {
+        self.contracts.values().copied()
+    }
+
+    /// Extract the Merkle path for one contract
+    ///
+    /// # Returns
+    /// Protocol ID and Merkle proof to store in that contract's `Anchor`
+    pub fn proof_for(
+        &self,
+        contract_id: ContractId,
+    ) -> Result<(mpc::ProtocolId, mpc::MerkleProof)> {
+        let protocol = protocol_id(contract_id);
+        if !self.contracts.contains_key(&protocol) {
+            return Err(MpcError::UnknownProtocol(contract_id.to_string()));
+        }
+
+        let proof = self
+            .block
+            .to_merkle_proof(protocol)
+            .map_err(|e| MpcError::InvalidProof(format!("{:?}", e)))?;
+
+        Ok((protocol, proof))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bp::seals::mmb;
+    use commit_verify::ReservedBytes;
+
+    fn contract(seed: u8) -> ContractId {
+        ContractId::from([seed; 32])
+    }
+
+    fn anchor_with(protocol: mpc::ProtocolId, proof: mpc::MerkleProof) -> Anchor {
+        Anchor {
+            mmb_proof: mmb::BundleProof::strict_dumb(),
+            mpc_protocol: protocol,
+            mpc_proof: proof,
+            dbc_proof: None,
+            fallback_proof: ReservedBytes::strict_dumb(),
+        }
+    }
+
+    #[test]
+    fn test_mpc_proofs_convolve_to_root() {
+        let entries = [
+            (contract(1), [0x11u8; 32]),
+            (contract(2), [0x22u8; 32]),
+            (contract(3), [0x33u8; 32]),
+        ];
+        let mpc = MultiCommitment::build(&entries).expect("MPC tree should build");
+        assert_eq!(mpc.contracts().count(), 3);
+
+        for (contract_id, state_hash) in entries {
+            let (protocol, proof) = mpc.proof_for(contract_id).expect("Proof should exist");
+            let anchor = anchor_with(protocol, proof);
+            assert!(!is_legacy_anchor(&anchor));
+
+            let committed =
+                anchor_commitment(&anchor, contract_id, state_hash).expect("Proof should convolve");
+            assert_eq!(committed, mpc.root_bytes());
+
+            // Another state hash under the same path yields another root
+            let other = anchor_commitment(&anchor, contract_id, [0xFFu8; 32]);
+            assert!(other.map_or(true, |root| root != mpc.root_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_mpc_rejects_foreign_proof() {
+        let mpc = MultiCommitment::build(&[(contract(1), [0x11u8; 32])]).unwrap();
+        let (protocol, proof) = mpc.proof_for(contract(1)).unwrap();
+        let anchor = anchor_with(protocol, proof);
+
+        assert!(matches!(
+            anchor_commitment(&anchor, contract(2), [0x11u8; 32]),
+            Err(MpcError::ProtocolMismatch { .. })
+        ));
+        assert!(matches!(
+            mpc.proof_for(contract(2)),
+            Err(MpcError::UnknownProtocol(_))
+        ));
+    }
+
+    #[test]
+    fn test_mpc_build_errors() {
+        assert!(matches!(MultiCommitment::build(&[]), Err(MpcError::Empty)));
+        assert!(matches!(
+            MultiCommitment::build(&[(contract(1), [0u8; 32]), (contract(1), [1u8; 32])]),
+            Err(MpcError::DuplicateProtocol(_))
+        ));
+    }
+
+    #[test]
+    fn test_legacy_anchor_commits_raw_hash() {
+        let anchor = Anchor::strict_dumb();
+        assert!(is_legacy_anchor(&anchor));
+        assert_eq!(
+            anchor_commitment(&anchor, contract(1), [0x42u8; 32]).unwrap(),
+            [0x42u8; 32]
+        );
+    }
+}
@@ -10,17 +10,52 @@
 //! - **OP_RETURN**: Explicit data field, compatible with Lightning (for channels)
 //!
 //! Both methods embed the same F1r3fly state hash, just in different ways.
+//!
+//! # Opret Compatibility
+//!
+//! Commitments follow bp-core's Opret DBC scheme (`OpretFirst`):
+//! - The commitment lives in the **first** OP_RETURN output of the transaction
+//! - Its script is exactly `OP_RETURN OP_PUSHBYTES_32 <commitment>` (34 bytes)
+//! - Later OP_RETURN outputs may carry unrelated data
+//!
+//! RGB anchors encode Opret as `dbc_proof: None`; the `OpretProof` itself
+//! carries no data and is checked with `verify_opret_proof_in_tx`.
 
 use bitcoin::{Amount, ScriptBuf, Transaction, TxOut};
+use bp::dbc::opret::{OpretFirst, OpretProof};
+use bpstd::psbt::Psbt;
+use commit_verify::{mpc as mpc_cv, EmbedCommitVerify};
+
+use crate::tapret::first_signed_input;
+use crate::AnchorMethod;
 
 pub type Result<T> = std::result::Result<T, OpReturnError>;
 
+/// Length of an Opret commitment script: OP_RETURN + OP_PUSHBYTES_32 + 32 bytes
+pub const OPRET_SCRIPT_LEN: usize = 34;
+
+const OP_RETURN: u8 = 0x6a;
+const OP_PUSHBYTES_32: u8 = 0x20;
+
 #[derive(Debug)]
 pub enum OpReturnError {
-    InvalidOutputIndex { index: usize, max: usize },
+    InvalidOutputIndex {
+        index: usize,
+        max: usize,
+    },
     CommitmentFailed(String),
     ExtractionFailed(String),
     NotOpReturn,
+    /// Output is an OP_RETURN, but an earlier output already is
+    NotFirstOpReturn {
+        index: usize,
+        first: usize,
+    },
+    /// PSBT input already carries signatures that modifying outputs would invalidate
+    AlreadySigned {
+        input: usize,
+    },
+    VerificationFailed(String),
 }
 
 impl std::fmt::Display for OpReturnError {
@@ -32,6 +67,17 @@ impl std::fmt::Display for OpReturnError {
             Self::CommitmentFailed(msg) => write!(f, "OP_RETURN commitment failed: {}", msg),
             Self::ExtractionFailed(msg) => write!(f, "OP_RETURN extraction failed: {}", msg),
             Self::NotOpReturn => write!(f, "Output is not an OP_RETURN"),
+            Self::NotFirstOpReturn { index, first } => write!(
+                f,
+                "Output {} is not the first OP_RETURN output (first is {})",
+                index, first
+            ),
+            Self::AlreadySigned { input } => {
+                write!(f, "PSBT input {} is already signed", input)
+            }
+            Self::VerificationFailed(msg) => {
+                write!(f, "Opret proof verification failed: {}", msg)
+            }
         }
     }
 }
@@ -52,6 +98,10 @@ impl std::error::Error for OpReturnError {}
 /// # Returns
 /// The output index where OP_RETURN was inserted
 ///
+/// # Errors
+/// Returns error if an OP_RETURN output already precedes `output_index`,
+/// since Opret commitments must be in the first OP_RETURN output
+///
 /// # Example
 ///
 /// ```rust,no_run
@@ -79,6 +129,17 @@ pub fn embed_opreturn_commitment(
         });
     }
 
+    // Commitment must end up in the first OP_RETURN output
+    if let Some(first) = tx.output[..output_index]
+        .iter()
+        .position(|output| output.script_pubkey.is_op_return())
+    {
+        return Err(OpReturnError::NotFirstOpReturn {
+            index: output_index,
+            first,
+        });
+    }
+
     // Create OP_RETURN output with state hash
     let opreturn_output = TxOut {
         value: Amount::ZERO,
@@ -95,6 +156,96 @@ pub fn embed_opreturn_commitment(
     Ok(output_index)
 }
 
+/// Add an empty OP_RETURN output to a PSBT to host an Opret commitment
+///
+/// # Returns
+/// Index of the new output
+///
+/// # Errors
+/// Returns error if the PSBT already has an OP_RETURN output or is signed
+pub fn add_opret_host(psbt: &mut Psbt) -> Result<usize> {
+    use bp::Sats;
+    use bpstd::ScriptPubkey;
+
+    ensure_unsigned(psbt)?;
+
+    if let Some(first) = psbt
+        .outputs()
+        .position(|output| output.script.is_op_return())
+    {
+        return Err(OpReturnError::CommitmentFailed(format!(
+            "PSBT already has an OP_RETURN output ({})",
+            first
+        )));
+    }
+
+    let output = psbt
+        .construct_output(ScriptPubkey::op_return(&[]), Sats::ZERO)
+        .map_err(|e| {
+            OpReturnError::CommitmentFailed(format!("PSBT outputs not modifiable: {:?}", e))
+        })?;
+
+    Ok(output.index())
+}
+
+/// Embed F1r3fly state hash as Opret commitment in PSBT output
+///
+/// The output must be the first OP_RETURN output of the PSBT and must still
+/// be an empty placeholder (see `add_opret_host`).
+///
+/// # Arguments
+/// * `psbt` - Mutable unsigned PSBT
+/// * `output_index` - Placeholder OP_RETURN output
+/// * `state_hash` - F1r3fly state hash (or MPC root)
+///
+/// # Returns
+/// OpretProof for verification with `verify_opret_proof_in_tx`
+pub fn embed_opret_commitment_psbt(
+    psbt: &mut Psbt,
+    output_index: usize,
+    state_hash: [u8; 32],
+) -> Result<OpretProof> {
+    ensure_unsigned(psbt)?;
+
+    let first = psbt
+        .outputs()
+        .position(|output| output.script.is_op_return());
+    let mut outputs: Vec<_> = psbt.outputs_mut().collect();
+
+    if output_index >= outputs.len() {
+        return Err(OpReturnError::InvalidOutputIndex {
+            index: output_index,
+            max: outputs.len(),
+        });
+    }
+    match first {
+        None => return Err(OpReturnError::NotOpReturn),
+        Some(first) if first != output_index => {
+            return Err(OpReturnError::NotFirstOpReturn {
+                index: output_index,
+                first,
+            })
+        }
+        Some(_) => {}
+    }
+
+    let output = &mut outputs[output_index];
+
+    output
+        .set_opret_host()
+        .map_err(|e| OpReturnError::CommitmentFailed(format!("set_opret_host failed: {:?}", e)))?;
+
+    let proof = output
+        .opret_commit(mpc_cv::Commitment::from(state_hash))
+        .map_err(|e| OpReturnError::CommitmentFailed(format!("opret_commit failed: {:?}", e)))?;
+
+    log::info!("✅ Opret commitment embedded in PSBT");
+    log::debug!("   State hash: {}", hex::encode(state_hash));
+    log::debug!("   Output index: {}", output_index);
+
+    Ok(proof)
+}
+
 /// Extract OP_RETURN commitment from Bitcoin transaction
 ///
 /// Reads the embedded F1r3fly state hash from an OP_RETURN output.
@@ -111,7 +262,8 @@ pub fn embed_opreturn_commitment(
 /// Returns error if:
 /// - Output index is out of bounds
 /// - Output is not an OP_RETURN
-/// - OP_RETURN data is not 32 bytes
+/// - Output is not the first OP_RETURN output
+/// - Script is not exactly `OP_RETURN OP_PUSHBYTES_32 <32 bytes>`
 ///
 /// # Example
 ///
@@ -134,47 +286,95 @@ pub fn extract_opreturn_commitment(tx: &bp::Tx, output_index: usize) -> Result<[
         });
     }
 
-    let output = &tx.outputs[output_index];
-
     // Get script bytes as a slice
-    let script_bytes = output.script_pubkey.as_slice();
+    let script_bytes = tx.outputs[output_index].script_pubkey.as_slice();
 
-    // Check if OP_RETURN (first byte should be 0x6a)
-    if script_bytes.is_empty() || script_bytes[0] != 0x6a {
+    // Check if OP_RETURN
+    if script_bytes.first() != Some(&OP_RETURN) {
         return Err(OpReturnError::NotOpReturn);
     }
 
-    // Extract data from OP_RETURN script
-    // Format: OP_RETURN <push_opcode> <32_bytes>
+    // Only the first OP_RETURN output carries the commitment
+    let first = tx
+        .outputs
+        .iter()
+        .position(|output| output.script_pubkey.as_slice().first() == Some(&OP_RETURN))
+        .unwrap_or(output_index);
+    if first != output_index {
+        return Err(OpReturnError::NotFirstOpReturn {
+            index: output_index,
+            first,
+        });
+    }
 
-    // Minimum length: OP_RETURN (1) + PUSHBYTES_32 (1) + data (32) = 34
-    if script_bytes.len() < 34 {
+    // Format: OP_RETURN OP_PUSHBYTES_32 <32_bytes>, nothing else
+    if script_bytes.len() != OPRET_SCRIPT_LEN || script_bytes[1] != OP_PUSHBYTES_32 {
         return Err(OpReturnError::ExtractionFailed(format!(
-            "OP_RETURN data too short: {} bytes (expected at least 34)",
+            "OP_RETURN script is not a 32-byte commitment ({} bytes)",
             script_bytes.len()
         )));
     }
 
-    // Extract 32-byte hash (skip OP_RETURN opcode and length prefix)
-    let hash: [u8; 32] = script_bytes[2..34].try_into().map_err(|_| {
+    let hash: [u8; 32] = script_bytes[2..].try_into().map_err(|_| {
         OpReturnError::ExtractionFailed("Failed to extract 32-byte hash".to_string())
     })?;
 
     Ok(hash)
 }
 
+/// Verify Opret commitment in a Bitcoin transaction
+///
+/// Checks that `output_index` is the Opret host and runs bp-core's Opret
+/// DBC verification: the first OP_RETURN output is restored to an empty
+/// placeholder, the commitment is re-embedded and the result must match
+/// the transaction exactly.
+///
+/// # Arguments
+/// * `tx` - Witness transaction
+/// * `output_index` - Output declared by the anchor method
+/// * `state_hash` - Expected state hash (or MPC root)
+pub fn verify_opret_proof_in_tx(
+    tx: &bp::Tx,
+    output_index: usize,
+    state_hash: [u8; 32],
+) -> Result<()> {
+    let extracted = extract_opreturn_commitment(tx, output_index)?;
+    if extracted != state_hash {
+        return Err(OpReturnError::VerificationFailed(format!(
+            "hash mismatch. Expected: {}, Found: {}",
+            hex::encode(state_hash),
+            hex::encode(extracted)
+        )));
+    }
+
+    let commitment = mpc_cv::Commitment::from(state_hash);
+    <bp::Tx as EmbedCommitVerify<mpc_cv::Commitment, OpretFirst>>::verify(
+        tx,
+        &commitment,
+        &OpretProof::default(),
+    )
+    .map_err(|e| OpReturnError::VerificationFailed(format!("{:?}", e)))?;
+
+    log::debug!("✅ Opret proof cryptographically verified");
+    log::debug!("   State hash: {}", hex::encode(state_hash));
+    log::debug!("   Txid: {}", tx.txid());
+
+    Ok(())
+}
+
 /// Create an anchor for OP_RETURN commitment
 ///
 /// Creates an RGB anchor structure for OP_RETURN-based state commitments.
-/// Unlike Tapret which has cryptographic proofs, OP_RETURN anchors are
-/// simple references to the commitment in the transaction.
+/// RGB anchors mark Opret commitments with `dbc_proof: None`; the Opret
+/// proof carries no data and is re-derived by `verify_opret_proof_in_tx`.
 ///
 /// # Arguments
 /// * `state_hash` - F1r3fly state hash (32 bytes)
 /// * `output_index` - Output index containing the OP_RETURN (typically 0)
 ///
 /// # Returns
-/// RGB Anchor structure for tracking the commitment
+/// RGB Anchor structure for tracking the commitment, and the `AnchorMethod`
+/// recording which output carries the OP_RETURN
 ///
 /// # Example
 ///
@@ -182,23 +382,141 @@ pub fn extract_opreturn_commitment(tx: &bp::Tx, output_index: usize) -> Result<[
 /// use f1r3fly_rgb::create_opreturn_anchor;
 ///
 /// # fn example(state_hash: [u8; 32]) -> Result<(), Box<dyn std::error::Error>> {
-/// let anchor = create_opreturn_anchor(state_hash, 0);
-/// // Use anchor for consignment creation
+/// let (anchor, method) = create_opreturn_anchor(state_hash, 0);
+/// // Store with tracker.add_anchor_with_method(opid, anchor, method)
 /// # Ok(())
 /// # }
 /// ```
-pub fn create_opreturn_anchor(_state_hash: [u8; 32], _output_index: usize) -> bp::seals::Anchor {
+pub fn create_opreturn_anchor(
+    _state_hash: [u8; 32],
+    output_index: usize,
+) -> (bp::seals::Anchor, AnchorMethod) {
     use bp::seals::{mmb, mpc, Anchor};
     use commit_verify::ReservedBytes;
     use strict_encoding::StrictDumb;
 
-    // OP_RETURN anchors use simple structure without DBC proofs
-    // The commitment is validated by checking the OP_RETURN data directly
-    Anchor {
+    // Opret anchors carry no DBC proof; the commitment is validated by
+    // re-embedding it into the first OP_RETURN output
+    let anchor = Anchor {
         mmb_proof: mmb::BundleProof::strict_dumb(),
         mpc_protocol: mpc::ProtocolId::strict_dumb(),
         mpc_proof: mpc::MerkleProof::strict_dumb(),
-        dbc_proof: None, // OP_RETURN doesn't have DBC proof like Tapret
+        dbc_proof: None, // Opret is encoded as the absence of a Tapret proof
         fallback_proof: ReservedBytes::strict_dumb(),
+    };
+    let method = AnchorMethod::OpReturn {
+        output: output_index as u32,
+    };
+
+    (anchor, method)
+}
+
+fn ensure_unsigned(psbt: &Psbt) -> Result<()> {
+    match first_signed_input(psbt) {
+        Some(input) => Err(OpReturnError::AlreadySigned { input }),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amplify::confinement::Confined;
+    use bp::{LockTime, Sats, Tx, TxOut as BpTxOut};
+    use bpstd::ScriptPubkey;
+
+    fn tx_with_outputs(scripts: Vec<ScriptPubkey>) -> Tx {
+        let outputs = scripts
+            .into_iter()
+            .map(|script_pubkey| BpTxOut {
+                value: Sats::ZERO,
+                script_pubkey,
+            })
+            .collect::<Vec<_>>();
+        Tx {
+            version: bp::TxVer::V2,
+            inputs: Confined::try_from(vec![]).unwrap(),
+            outputs: Confined::try_from(outputs).unwrap(),
+            lock_time: LockTime::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_opret_first_output_rules() {
+        let state_hash = [0x42u8; 32];
+        let other_data = ScriptPubkey::op_return(b"unrelated data");
+
+        // Commitment first, unrelated OP_RETURN after: valid
+        let tx = tx_with_outputs(vec![
+            ScriptPubkey::op_return(&state_hash),
+            other_data.clone(),
+        ]);
+        assert_eq!(extract_opreturn_commitment(&tx, 0).unwrap(), state_hash);
+        assert!(verify_opret_proof_in_tx(&tx, 0, state_hash).is_ok());
+        assert!(verify_opret_proof_in_tx(&tx, 0, [0xFFu8; 32]).is_err());
+
+        // Unrelated OP_RETURN first: commitment is not the Opret host
+        let tx = tx_with_outputs(vec![other_data, ScriptPubkey::op_return(&state_hash)]);
+        assert!(matches!(
+            extract_opreturn_commitment(&tx, 1),
+            Err(OpReturnError::NotFirstOpReturn { index: 1, first: 0 })
+        ));
+        assert!(extract_opreturn_commitment(&tx, 0).is_err());
+
+        // Trailing bytes after the 32-byte push are rejected
+        let mut long = state_hash.to_vec();
+        long.push(0);
+        let tx = tx_with_outputs(vec![ScriptPubkey::op_return(&long)]);
+        assert!(matches!(
+            extract_opreturn_commitment(&tx, 0),
+            Err(OpReturnError::ExtractionFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_embed_opreturn_after_existing_opreturn() {
+        let mut tx = Transaction {
+            version: bitcoin::transaction::Version::TWO,
+            lock_time: bitcoin::absolute::LockTime::ZERO,
+            input: vec![],
+            output: vec![TxOut {
+                value: Amount::ZERO,
+                script_pubkey: ScriptBuf::new_op_return(&[1, 2, 3, 4]),
+            }],
+        };
+
+        assert!(matches!(
+            embed_opreturn_commitment(&mut tx, 1, [0u8; 32]),
+            Err(OpReturnError::NotFirstOpReturn { index: 1, first: 0 })
+        ));
+        assert_eq!(embed_opreturn_commitment(&mut tx, 0, [0u8; 32]).unwrap(), 0);
+        assert_eq!(tx.output[0].script_pubkey.len(), OPRET_SCRIPT_LEN);
+    }
+
+    #[test]
+    fn test_psbt_opret_commitment() {
+        let state_hash = [0x24u8; 32];
+        let internal_key = crate::tapret::create_test_psbt_with_taproot()
+            .outputs()
+            .next()
+            .and_then(|output| output.tap_internal_key)
+            .unwrap();
+        let mut psbt = Psbt::from_tx(tx_with_outputs(vec![ScriptPubkey::p2tr_key_only(
+            internal_key,
+        )]));
+
+        let index = add_opret_host(&mut psbt).expect("OP_RETURN host should be added");
+        assert_eq!(index, 1);
+        assert!(add_opret_host(&mut psbt).is_err());
+        assert!(matches!(
+            embed_opret_commitment_psbt(&mut psbt, 0, state_hash),
+            Err(OpReturnError::NotFirstOpReturn { index: 0, first: 1 })
+        ));
+
+        embed_opret_commitment_psbt(&mut psbt, index, state_hash)
+            .expect("Opret commitment should embed");
+
+        let tx: Tx = psbt.to_unsigned_tx().into();
+        assert!(verify_opret_proof_in_tx(&tx, index, state_hash).is_ok());
     }
 }
@@ -0,0 +1,394 @@
+//! Seal identifiers used as F1r3fly state keys
+//!
+//! The Rho20 contract keys balances and owners by string. Two forms exist:
+//!
+//! - **Outpoint**: `<txid>:<vout>` for a real UTXO, with the txid in the usual
+//!   Bitcoin display order (byte-reversed from `Txid`'s internal bytes)
+//! - **Witness**: `witness:<hash>:<vout>` for output `vout` of a witness
+//!   transaction whose txid isn't known when F1r3fly executes the transfer;
+//!   `hash` is the first 8 bytes of SHA-256 of the first seal the witness
+//!   transaction spends and the output's address (see `SealKey::witness`)
+//!
+//! A key formatted any other way (txid in internal byte order, uppercase hex,
+//! `vout` with leading zeros) names a different, empty balance on F1r3fly.
+//! `SealKey` formats the canonical string only, and parsing rejects the rest.
+//!
+//! # Example
+//!
+//! ```rust
+//! use f1r3fly_rgb::{SealKey, Txid};
+//! use bp::Outpoint;
+//!
+//! # fn example(spent: Outpoint, witness_txid: Txid) {
+//! let witness = SealKey::witness(spent, "bcrt1qexample", 1);
+//! let parsed: SealKey = witness.to_string().parse().unwrap();
+//! assert_eq!(parsed, witness);
+//!
+//! // Once the witness transaction is known
+//! let utxo = witness.resolve(witness_txid);
+//! assert!(!utxo.is_witness());
+//! # }
+//! ```
+
+use amplify::ByteArray;
+use bp::seals::{Noise, TxoSeal, TxoSealExt, WOutpoint, WTxoSeal};
+use bp::{Outpoint, Txid, Vout};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::str::FromStr;
+use strict_types::StrictDumb;
+
+/// Prefix of witness-relative keys
+pub const WITNESS_KEY_PREFIX: &str = "witness:";
+
+/// Key of a seal's balance and owner in F1r3fly state
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SealKey {
+    /// Real UTXO
+    Outpoint(Outpoint),
+
+    /// Output of a witness transaction, identified by the seal it spends and
+    /// the output's address
+    Witness { hash: [u8; 8], vout: u32 },
+}
+
+/// Seal key that can't be parsed or converted
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SealKeyError {
+    /// Neither `<txid>:<vout>` nor `witness:<hash>:<vout>`
+    InvalidFormat(String),
+
+    /// Txid is not 64 lowercase hex characters
+    InvalidTxid(String),
+
+    /// Witness hash is not 16 lowercase hex characters
+    InvalidHash(String),
+
+    /// Output index is not a canonical decimal `u32`
+    InvalidVout(String),
+
+    /// Seal on a witness output, without the witness transaction ID
+    UnresolvedSeal(u32),
+
+    /// Witness key used where an outpoint is required
+    NotAnOutpoint(String),
+}
+
+impl fmt::Display for SealKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFormat(key) => write!(f, "{:?} is not a seal key", key),
+            Self::InvalidTxid(txid) => {
+                write!(f, "Txid {:?} is not 64 lowercase hex characters", txid)
+            }
+            Self::InvalidHash(hash) => write!(
+                f,
+                "Witness hash {:?} is not 16 lowercase hex characters",
+                hash
+            ),
+            Self::InvalidVout(vout) => write!(f, "Output index {:?} is not canonical", vout),
+            Self::UnresolvedSeal(vout) => write!(
+                f,
+                "Seal on witness output {} needs the witness transaction ID",
+                vout
+            ),
+            Self::NotAnOutpoint(key) => write!(f, "{} is not an outpoint", key),
+        }
+    }
+}
+
+impl std::error::Error for SealKeyError {}
+
+impl SealKey {
+    /// Key of output `vout` of the witness transaction paying `address`
+    ///
+    /// `spent` is the first seal the witness transaction spends (its first
+    /// input). Only one transaction can spend it, so the key is unique per
+    /// transfer even when the address and output index are reused, and the
+    /// recipient can recompute it from the witness transaction.
+    pub fn witness(spent: Outpoint, address: &str, vout: u32) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(Self::Outpoint(spent).to_string().as_bytes());
+        hasher.update(b"|");
+        hasher.update(address.as_bytes());
+        let mut hash = [0u8; 8];
+        hash.copy_from_slice(&hasher.finalize()[..8]);
+        Self::Witness { hash, vout }
+    }
+
+    /// Witness key as derived by 1.0 consignments, from the address alone
+    ///
+    /// Not bound to the spent seal, so reusing an address and output index
+    /// collides; only recognized when validating legacy consignments.
+    pub(crate) fn legacy_witness(address: &str, vout: u32) -> Self {
+        let mut hash = [0u8; 8];
+        hash.copy_from_slice(&Sha256::digest(address.as_bytes())[..8]);
+        Self::Witness { hash, vout }
+    }
+
+    /// Key of the UTXO a seal definition points to
+    ///
+    /// Seals on a witness output (`WOutpoint::Wout`) need the witness
+    /// transaction ID.
+    pub fn from_seal(seal: &WTxoSeal, witness_txid: Option<Txid>) -> Result<Self, SealKeyError> {
+        match (seal.primary, witness_txid) {
+            (WOutpoint::Extern(outpoint), _) => Ok(Self::Outpoint(outpoint)),
+            (WOutpoint::Wout(vout), Some(txid)) => Ok(Self::Outpoint(Outpoint::new(txid, vout))),
+            (WOutpoint::Wout(vout), None) => Err(SealKeyError::UnresolvedSeal(vout.into_u32())),
+        }
+    }
+
+    /// Real UTXO of a witness key once the witness transaction is known
+    ///
+    /// Outpoint keys are returned unchanged.
+    pub fn resolve(self, witness_txid: Txid) -> Self {
+        match self {
+            Self::Witness { vout, .. } => Self::Outpoint(Outpoint::new(witness_txid, vout)),
+            outpoint => outpoint,
+        }
+    }
+
+    /// Outpoint of a UTXO key
+    pub fn outpoint(&self) -> Option<Outpoint> {
+        match self {
+            Self::Outpoint(outpoint) => Some(*outpoint),
+            Self::Witness { .. } => None,
+        }
+    }
+
+    /// Output index
+    pub fn vout(&self) -> u32 {
+        match self {
+            Self::Outpoint(outpoint) => outpoint.vout.into_u32(),
+            Self::Witness { vout, .. } => *vout,
+        }
+    }
+
+    /// Whether the key is witness-relative
+    pub fn is_witness(&self) -> bool {
+        matches!(self, Self::Witness { .. })
+    }
+}
+
+impl fmt::Display for SealKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Outpoint(outpoint) => {
+                // Txid bytes are stored little-endian; display order is reversed
+                let mut txid = outpoint.txid.to_byte_array();
+                txid.reverse();
+                write!(f, "{}:{}", hex::encode(txid), outpoint.vout.into_u32())
+            }
+            Self::Witness { hash, vout } => {
+                write!(f, "{}{}:{}", WITNESS_KEY_PREFIX, hex::encode(hash), vout)
+            }
+        }
+    }
+}
+
+impl FromStr for SealKey {
+    type Err = SealKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(rest) = s.strip_prefix(WITNESS_KEY_PREFIX) {
+            let (hash, vout) = rest
+                .split_once(':')
+                .ok_or_else(|| SealKeyError::InvalidFormat(s.to_string()))?;
+            return Ok(Self::Witness {
+                hash: parse_lower_hex::<8>(hash)
+                    .ok_or_else(|| SealKeyError::InvalidHash(hash.to_string()))?,
+                vout: parse_vout(vout)?,
+            });
+        }
+
+        let (txid, vout) = s
+            .split_once(':')
+            .ok_or_else(|| SealKeyError::InvalidFormat(s.to_string()))?;
+        let mut bytes = parse_lower_hex::<32>(txid)
+            .ok_or_else(|| SealKeyError::InvalidTxid(txid.to_string()))?;
+        bytes.reverse();
+        Ok(Self::Outpoint(Outpoint::new(
+            Txid::from_byte_array(bytes),
+            parse_vout(vout)?,
+        )))
+    }
+}
+
+/// Decode exactly `N` bytes of lowercase hex
+fn parse_lower_hex<const N: usize>(s: &str) -> Option<[u8; N]> {
+    if s.len() != N * 2 || !s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+        return None;
+    }
+    hex::decode(s).ok()?.try_into().ok()
+}
+
+/// Parse a decimal `u32` without sign or leading zeros
+fn parse_vout(s: &str) -> Result<u32, SealKeyError> {
+    s.parse::<u32>()
+        .ok()
+        .filter(|vout| vout.to_string() == s)
+        .ok_or_else(|| SealKeyError::InvalidVout(s.to_string()))
+}
+
+impl From<Outpoint> for SealKey {
+    fn from(outpoint: Outpoint) -> Self {
+        Self::Outpoint(outpoint)
+    }
+}
+
+impl From<&TxoSeal> for SealKey {
+    fn from(seal: &TxoSeal) -> Self {
+        Self::Outpoint(seal.primary)
+    }
+}
+
+/// Seal on the key's outpoint, without fallback
+///
+/// Keys don't carry the seal's blinding, so the noise is the dumb value;
+/// the seal identifies the same F1r3fly balance.
+impl TryFrom<SealKey> for TxoSeal {
+    type Error = SealKeyError;
+
+    fn try_from(key: SealKey) -> Result<Self, Self::Error> {
+        let outpoint = key
+            .outpoint()
+            .ok_or_else(|| SealKeyError::NotAnOutpoint(key.to_string()))?;
+        Ok(TxoSeal {
+            primary: outpoint,
+            secondary: TxoSealExt::Noise(Noise::strict_dumb()),
+        })
+    }
+}
+
+/// Seal definition on the key's outpoint, or on the witness output for
+/// witness keys; the noise is the dumb value
+impl From<SealKey> for WTxoSeal {
+    fn from(key: SealKey) -> Self {
+        let primary = match key {
+            SealKey::Outpoint(outpoint) => WOutpoint::Extern(outpoint),
+            SealKey::Witness { vout, .. } => WOutpoint::Wout(Vout::from(vout)),
+        };
+        WTxoSeal {
+            primary,
+            secondary: TxoSealExt::Noise(Noise::strict_dumb()),
+        }
+    }
+}
+
+impl Serialize for SealKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SealKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let key = String::deserialize(deserializer)?;
+        key.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn txid() -> Txid {
+        let mut bytes = [0u8; 32];
+        bytes[0] = 0xab;
+        bytes[31] = 0x01;
+        Txid::from_byte_array(bytes)
+    }
+
+    #[test]
+    fn test_outpoint_key_uses_display_byte_order() {
+        let key = SealKey::from(Outpoint::new(txid(), 3u32));
+        let s = key.to_string();
+        assert!(s.starts_with("01"));
+        assert!(s.ends_with("ab:3"));
+        assert_eq!(s.parse::<SealKey>().unwrap(), key);
+    }
+
+    #[test]
+    fn test_witness_key_round_trip() {
+        let key = SealKey::witness(Outpoint::new(txid(), 0u32), "bcrt1qexample", 1);
+        let s = key.to_string();
+        assert!(s.starts_with(WITNESS_KEY_PREFIX));
+        assert_eq!(s.len(), WITNESS_KEY_PREFIX.len() + 16 + 2);
+        assert_eq!(s.parse::<SealKey>().unwrap(), key);
+        assert_eq!(
+            key.resolve(txid()),
+            SealKey::from(Outpoint::new(txid(), 1u32))
+        );
+    }
+
+    #[test]
+    fn test_witness_key_unique_per_spent_seal() {
+        let spent = Outpoint::new(txid(), 0u32);
+        let key = SealKey::witness(spent, "bcrt1qexample", 1);
+        assert_eq!(key, SealKey::witness(spent, "bcrt1qexample", 1));
+
+        // Same address and output, another transfer
+        let other = Outpoint::new(txid(), 1u32);
+        assert_ne!(key, SealKey::witness(other, "bcrt1qexample", 1));
+        assert_ne!(key, SealKey::witness(spent, "bcrt1qother", 1));
+    }
+
+    #[test]
+    fn test_legacy_witness_key_not_bound_to_spent_seal() {
+        let legacy = SealKey::legacy_witness("bcrt1qexample", 1);
+        assert!(legacy.is_witness());
+        assert_eq!(legacy.vout(), 1);
+        assert_ne!(
+            legacy,
+            SealKey::witness(Outpoint::new(txid(), 0u32), "bcrt1qexample", 1)
+        );
+        assert_ne!(legacy, SealKey::legacy_witness("bcrt1qother", 1));
+    }
+
+    #[test]
+    fn test_non_canonical_keys_rejected() {
+        let canonical = SealKey::from(Outpoint::new(txid(), 0u32)).to_string();
+        assert!(matches!(
+            canonical.to_uppercase().parse::<SealKey>(),
+            Err(SealKeyError::InvalidTxid(_))
+        ));
+        assert!(matches!(
+            canonical.replace(":0", ":00").parse::<SealKey>(),
+            Err(SealKeyError::InvalidVout(_))
+        ));
+        assert!(matches!(
+            "9b7b09e4cd136021:0".parse::<SealKey>(),
+            Err(SealKeyError::InvalidTxid(_))
+        ));
+        assert!(matches!(
+            "witness:abc:0".parse::<SealKey>(),
+            Err(SealKeyError::InvalidHash(_))
+        ));
+        assert!(matches!(
+            "alice".parse::<SealKey>(),
+            Err(SealKeyError::InvalidFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_seal_conversions() {
+        let outpoint = Outpoint::new(txid(), 2u32);
+        let key = SealKey::from(outpoint);
+        let seal = TxoSeal::try_from(key).unwrap();
+        assert_eq!(SealKey::from(&seal), key);
+        assert_eq!(SealKey::from_seal(&WTxoSeal::from(key), None), Ok(key));
+
+        let witness = SealKey::witness(outpoint, "bcrt1qexample", 1);
+        assert!(TxoSeal::try_from(witness).is_err());
+        let wseal = WTxoSeal::from(witness);
+        assert_eq!(
+            SealKey::from_seal(&wseal, None),
+            Err(SealKeyError::UnresolvedSeal(1))
+        );
+        assert_eq!(
+            SealKey::from_seal(&wseal, Some(txid())),
+            Ok(witness.resolve(txid()))
+        );
+    }
+}
@@ -2,11 +2,17 @@
 //!
 //! Provides helper functions for generating ECDSA signatures that match
 //! Rholang's signature verification requirements using protobuf encoding.
+//!
+//! Seal identifiers are signed in their string form, so any `Display` value
+//! is accepted: a `SealKey` for the canonical key, or a plain string.
 
 use blake2::digest::consts::U32;
 use blake2::{Blake2b, Digest as Blake2Digest};
+use f1r3fly_models::rhoapi::expr::ExprInstance;
+use f1r3fly_models::rhoapi::{ETuple, Expr, Par};
 use prost::Message as ProstMessage;
 use secp256k1::{Message, SecretKey};
+use std::fmt;
 
 /// Generate signature for issue() method call
 ///
@@ -15,7 +21,7 @@ use secp256k1::{Message, SecretKey};
 /// `(recipient, amount, nonce)`.
 ///
 /// # Arguments
-/// * `recipient` - The recipient identifier (typically a seal ID like "txid:vout")
+/// * `recipient` - The recipient's seal key (`SealKey`, or its string form)
 /// * `amount` - The amount of tokens to issue
 /// * `nonce` - A unique nonce for replay protection
 /// * `signing_key` - The secp256k1 private key to sign with
@@ -28,7 +34,7 @@ use secp256k1::{Message, SecretKey};
 /// let signature = generate_issue_signature("alice", 1000, 12345, &private_key)?;
 /// ```
 pub fn generate_issue_signature(
-    recipient: &str,
+    recipient: &(impl fmt::Display + ?Sized),
     amount: u64,
     nonce: u64,
     signing_key: &SecretKey,
@@ -102,8 +108,8 @@ pub fn generate_issue_signature(
 /// `(from, to, amount, nonce)`.
 ///
 /// # Arguments
-/// * `from` - The sender's identifier (typically a seal ID like "txid:vout")
-/// * `to` - The recipient's identifier (typically a seal ID like "txid:vout")
+/// * `from` - The sender's seal key (`SealKey`, or its string form)
+/// * `to` - The recipient's seal key (a UTXO or a witness output)
 /// * `amount` - The amount of tokens to transfer
 /// * `nonce` - A unique nonce for replay protection
 /// * `signing_key` - The secp256k1 private key to sign with
@@ -113,11 +119,11 @@ pub fn generate_issue_signature(
 ///
 /// # Example
 /// ```ignore
-/// let signature = generate_transfer_signature("alice_utxo", "bob_utxo", 100, 67890, &private_key)?;
+/// let signature = generate_transfer_signature(&alice_key, &bob_key, 100, 67890, &private_key)?;
 /// ```
 pub fn generate_transfer_signature(
-    from: &str,
-    to: &str,
+    from: &(impl fmt::Display + ?Sized),
+    to: &(impl fmt::Display + ?Sized),
     amount: u64,
     nonce: u64,
     signing_key: &SecretKey,
@@ -195,6 +201,49 @@ pub fn generate_transfer_signature(
     Ok(hex::encode(signature.serialize_der()))
 }
 
+/// Generate signature for transferWithChange() method call
+///
+/// Creates a signature that matches the Rholang contract's verification using protobuf encoding.
+/// The message format must exactly match what Rholang's `.toByteArray()` produces for the tuple
+/// `(from, to, amount, change, nonce)`.
+///
+/// # Arguments
+/// * `from` - The sender's seal key (`SealKey`, or its string form)
+/// * `to` - The recipient's seal key (a UTXO or a witness output)
+/// * `amount` - The amount of tokens to transfer
+/// * `change` - The seal key receiving the rest of `from`'s balance
+/// * `nonce` - A unique nonce for replay protection
+/// * `signing_key` - The secp256k1 private key to sign with
+///
+/// # Returns
+/// Hex-encoded DER signature string that can be passed to the Rholang `transferWithChange()` method
+///
+/// # Example
+/// ```ignore
+/// let signature = generate_transfer_with_change_signature(
+///     &alice_key, &bob_key, 100, &change_key, 67890, &private_key,
+/// )?;
+/// ```
+pub fn generate_transfer_with_change_signature(
+    from: &(impl fmt::Display + ?Sized),
+    to: &(impl fmt::Display + ?Sized),
+    amount: u64,
+    change: &(impl fmt::Display + ?Sized),
+    nonce: u64,
+    signing_key: &SecretKey,
+) -> Result<String, Box<dyn std::error::Error>> {
+    Ok(sign_tuple(
+        vec![
+            ExprInstance::GString(from.to_string()),
+            ExprInstance::GString(to.to_string()),
+            ExprInstance::GInt(amount as i64),
+            ExprInstance::GString(change.to_string()),
+            ExprInstance::GInt(nonce as i64),
+        ],
+        signing_key,
+    ))
+}
+
 /// Generate signature for claim() method call
 ///
 /// Creates a signature that matches the Rholang contract's verification using protobuf encoding.
@@ -202,8 +251,8 @@ pub fn generate_transfer_signature(
 /// `(witness_id, real_utxo)`.
 ///
 /// # Arguments
-/// * `witness_id` - The temporary witness identifier (e.g. "witness:a3467636:0")
-/// * `real_utxo` - The actual Bitcoin UTXO identifier (e.g. "9b7b09e4cd136021:0")
+/// * `witness_id` - The witness key holding the balance (`SealKey::witness`)
+/// * `real_utxo` - The key of the actual Bitcoin UTXO
 /// * `signing_key` - The secp256k1 private key to sign with
 ///
 /// # Returns
@@ -211,11 +260,11 @@ pub fn generate_transfer_signature(
 ///
 /// # Example
 /// ```ignore
-/// let signature = generate_claim_signature("witness:abc:0", "real_txid:0", &private_key)?;
+/// let signature = generate_claim_signature(&witness_key, &utxo_key, &private_key)?;
 /// ```
 pub fn generate_claim_signature(
-    witness_id: &str,
-    real_utxo: &str,
+    witness_id: &(impl fmt::Display + ?Sized),
+    real_utxo: &(impl fmt::Display + ?Sized),
     signing_key: &SecretKey,
 ) -> Result<String, Box<dyn std::error::Error>> {
     // Build protobuf Par structure for tuple: (witness_id, real_utxo)
@@ -272,6 +321,112 @@ pub fn generate_claim_signature(
     Ok(hex::encode(signature.serialize_der()))
 }
 
+/// Generate signature for freezeClaim() method call
+///
+/// Signs the tuple `(action, real_utxo, nonce)` where `action` is `"freeze"` or
+/// `"unfreeze"`. Must be signed with the deployer key, like `issue()`.
+///
+/// # Arguments
+/// * `real_utxo` - The UTXO the balance was claimed to
+/// * `freeze` - `true` to freeze the claim, `false` to unfreeze it
+/// * `nonce` - A unique nonce for replay protection
+/// * `signing_key` - The deployer's secp256k1 private key
+///
+/// # Returns
+/// Hex-encoded DER signature string that can be passed to the Rholang `freezeClaim()` method
+///
+/// # Example
+/// ```ignore
+/// let signature = generate_freeze_claim_signature(&utxo_key, true, nonce, &deployer_key)?;
+/// ```
+pub fn generate_freeze_claim_signature(
+    real_utxo: &(impl fmt::Display + ?Sized),
+    freeze: bool,
+    nonce: u64,
+    signing_key: &SecretKey,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let action = if freeze { "freeze" } else { "unfreeze" };
+    generate_claim_action_signature(action, real_utxo, nonce, signing_key)
+}
+
+/// Generate signature for revertClaim() method call
+///
+/// Signs the tuple `("revert", real_utxo, nonce)`. Must be signed with the
+/// deployer key, like `issue()`.
+///
+/// # Arguments
+/// * `real_utxo` - The UTXO the balance was claimed to
+/// * `nonce` - A unique nonce for replay protection
+/// * `signing_key` - The deployer's secp256k1 private key
+///
+/// # Returns
+/// Hex-encoded DER signature string that can be passed to the Rholang `revertClaim()` method
+///
+/// # Example
+/// ```ignore
+/// let signature = generate_revert_claim_signature(&utxo_key, nonce, &deployer_key)?;
+/// ```
+pub fn generate_revert_claim_signature(
+    real_utxo: &(impl fmt::Display + ?Sized),
+    nonce: u64,
+    signing_key: &SecretKey,
+) -> Result<String, Box<dyn std::error::Error>> {
+    generate_claim_action_signature("revert", real_utxo, nonce, signing_key)
+}
+
+/// Sign `(action, real_utxo, nonce)` for the claim reorg methods
+///
+/// The action tag keeps a freeze signature from being replayed as a revert.
+fn generate_claim_action_signature(
+    action: &str,
+    real_utxo: &(impl fmt::Display + ?Sized),
+    nonce: u64,
+    signing_key: &SecretKey,
+) -> Result<String, Box<dyn std::error::Error>> {
+    Ok(sign_tuple(
+        vec![
+            ExprInstance::GString(action.to_string()),
+            ExprInstance::GString(real_utxo.to_string()),
+            ExprInstance::GInt(nonce as i64),
+        ],
+        signing_key,
+    ))
+}
+
+/// Blake2b-256 hash of a tuple as Rholang's `.toByteArray()` encodes it
+///
+/// Each element is a single-expression `Par`, like a string or integer literal.
+fn tuple_message_hash(elements: Vec<ExprInstance>) -> [u8; 32] {
+    let par = Par {
+        exprs: vec![Expr {
+            expr_instance: Some(ExprInstance::ETupleBody(ETuple {
+                ps: elements
+                    .into_iter()
+                    .map(|element| Par {
+                        exprs: vec![Expr {
+                            expr_instance: Some(element),
+                        }],
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            })),
+        }],
+        ..Default::default()
+    };
+
+    let mut hasher = Blake2b::<U32>::new();
+    hasher.update(par.encode_to_vec());
+    hasher.finalize().into()
+}
+
+/// Sign a tuple for Rholang's `secpVerify`, as a hex-encoded DER signature
+fn sign_tuple(elements: Vec<ExprInstance>, signing_key: &SecretKey) -> String {
+    let secp = secp256k1::Secp256k1::new();
+    let message = Message::from_digest(tuple_message_hash(elements));
+    hex::encode(secp.sign_ecdsa(&message, signing_key).serialize_der())
+}
+
 /// Generate a unique nonce for replay protection
 ///
 /// Creates a nonce using timestamp (seconds since epoch) combined with a random component.
@@ -308,6 +463,12 @@ pub fn generate_nonce() -> u64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::SealKey;
+    use bp::{Outpoint, Txid};
+
+    fn utxo_key(seed: u8) -> SealKey {
+        SealKey::from(Outpoint::new(Txid::from([seed; 32]), 0))
+    }
 
     #[test]
     fn test_nonce_uniqueness() {
@@ -348,7 +509,7 @@ mod tests {
     fn test_transfer_signature_generation() {
         let private_key = SecretKey::from_slice(&[0x42; 32]).expect("valid key");
         let signature =
-            generate_transfer_signature("alice_utxo:0", "bob_utxo:0", 100, 67890, &private_key)
+            generate_transfer_signature(&utxo_key(1), &utxo_key(2), 100, 67890, &private_key)
                 .unwrap();
 
         // DER signatures are typically 70-72 bytes (140-144 hex chars)
@@ -362,10 +523,10 @@ mod tests {
         let nonce = 67890u64; // Fixed for determinism
 
         let sig1 =
-            generate_transfer_signature("alice_utxo:0", "bob_utxo:0", 100, nonce, &private_key)
+            generate_transfer_signature(&utxo_key(1), &utxo_key(2), 100, nonce, &private_key)
                 .unwrap();
         let sig2 =
-            generate_transfer_signature("alice_utxo:0", "bob_utxo:0", 100, nonce, &private_key)
+            generate_transfer_signature(&utxo_key(1), &utxo_key(2), 100, nonce, &private_key)
                 .unwrap();
 
         assert_eq!(
@@ -373,4 +534,126 @@ mod tests {
             "Same inputs should produce same transfer signature"
         );
     }
+
+    #[test]
+    fn test_tuple_hash_matches_signed_messages() {
+        let private_key = SecretKey::from_slice(&[0x42; 32]).expect("valid key");
+        let (from, to) = (utxo_key(1), utxo_key(2));
+
+        let issue = generate_issue_signature(&from, 1000, 12345, &private_key).unwrap();
+        assert!(verifies(
+            &issue,
+            vec![
+                ExprInstance::GString(from.to_string()),
+                ExprInstance::GInt(1000),
+                ExprInstance::GInt(12345),
+            ],
+            &private_key
+        ));
+
+        let transfer = generate_transfer_signature(&from, &to, 100, 67890, &private_key).unwrap();
+        assert!(verifies(
+            &transfer,
+            transfer_tuple(&from, &to),
+            &private_key
+        ));
+
+        let claim = generate_claim_signature(&from, &to, &private_key).unwrap();
+        assert!(verifies(
+            &claim,
+            vec![
+                ExprInstance::GString(from.to_string()),
+                ExprInstance::GString(to.to_string()),
+            ],
+            &private_key
+        ));
+    }
+
+    #[test]
+    fn test_transfer_with_change_signature() {
+        let private_key = SecretKey::from_slice(&[0x42; 32]).expect("valid key");
+        let (from, to, change) = (utxo_key(1), utxo_key(2), utxo_key(3));
+        let signature =
+            generate_transfer_with_change_signature(&from, &to, 100, &change, 67890, &private_key)
+                .unwrap();
+
+        assert!(verifies(
+            &signature,
+            vec![
+                ExprInstance::GString(from.to_string()),
+                ExprInstance::GString(to.to_string()),
+                ExprInstance::GInt(100),
+                ExprInstance::GString(change.to_string()),
+                ExprInstance::GInt(67890),
+            ],
+            &private_key
+        ));
+        // Change is part of the message: not usable as a plain transfer
+        assert!(!verifies(
+            &signature,
+            transfer_tuple(&from, &to),
+            &private_key
+        ));
+        let other = SecretKey::from_slice(&[0x43; 32]).expect("valid key");
+        assert!(!verifies(
+            &generate_transfer_with_change_signature(&from, &to, 100, &change, 67890, &other)
+                .unwrap(),
+            vec![
+                ExprInstance::GString(from.to_string()),
+                ExprInstance::GString(to.to_string()),
+                ExprInstance::GInt(100),
+                ExprInstance::GString(change.to_string()),
+                ExprInstance::GInt(67890),
+            ],
+            &private_key
+        ));
+    }
+
+    #[test]
+    fn test_claim_reorg_signatures() {
+        let private_key = SecretKey::from_slice(&[0x42; 32]).expect("valid key");
+        let utxo = utxo_key(3);
+        let action = |action: &str| {
+            vec![
+                ExprInstance::GString(action.to_string()),
+                ExprInstance::GString(utxo.to_string()),
+                ExprInstance::GInt(1),
+            ]
+        };
+
+        let freeze = generate_freeze_claim_signature(&utxo, true, 1, &private_key).unwrap();
+        let unfreeze = generate_freeze_claim_signature(&utxo, false, 1, &private_key).unwrap();
+        let revert = generate_revert_claim_signature(&utxo, 1, &private_key).unwrap();
+
+        assert!(verifies(&freeze, action("freeze"), &private_key));
+        assert!(verifies(&unfreeze, action("unfreeze"), &private_key));
+        assert!(verifies(&revert, action("revert"), &private_key));
+        // The action tag keeps signatures from being replayed across methods
+        assert!(!verifies(&freeze, action("revert"), &private_key));
+        assert!(!verifies(&freeze, action("unfreeze"), &private_key));
+    }
+
+    /// Tuple signed by `generate_transfer_signature(from, to, 100, 67890, ..)`
+    fn transfer_tuple(from: &SealKey, to: &SealKey) -> Vec<ExprInstance> {
+        vec![
+            ExprInstance::GString(from.to_string()),
+            ExprInstance::GString(to.to_string()),
+            ExprInstance::GInt(100),
+            ExprInstance::GInt(67890),
+        ]
+    }
+
+    /// Check a signature against the hash Rholang's `secpVerify` is given
+    fn verifies(signature: &str, elements: Vec<ExprInstance>, key: &SecretKey) -> bool {
+        let secp = secp256k1::Secp256k1::new();
+        let signature =
+            secp256k1::ecdsa::Signature::from_der(&hex::decode(signature).unwrap()).unwrap();
+        let message = Message::from_digest(tuple_message_hash(elements));
+        secp.verify_ecdsa(
+            &message,
+            &signature,
+            &secp256k1::PublicKey::from_secret_key(&secp, key),
+        )
+        .is_ok()
+    }
 }
@@ -5,17 +5,35 @@
 
 use bp::dbc::tapret::TapretProof;
 use bp::seals::{mmb, mpc, Anchor};
+use bp::{InternalPk, Sats};
 use bpstd::psbt::Psbt;
 use commit_verify::{mpc as mpc_cv, ReservedBytes};
 use strict_encoding::StrictDumb;
 
+use crate::multi_protocol::MultiCommitment;
+use crate::AnchorMethod;
+use hypersonic::ContractId;
+
 pub type Result<T> = std::result::Result<T, TapretError>;
 
 #[derive(Debug)]
 pub enum TapretError {
     NotTaprootOutput,
-    InvalidOutputIndex { index: usize, max: usize },
+    InvalidOutputIndex {
+        index: usize,
+        max: usize,
+    },
     CommitmentFailed(String),
+    /// PSBT input already carries signatures that modifying outputs would invalidate
+    AlreadySigned {
+        input: usize,
+    },
+    /// PSBT has no taproot output to host the commitment
+    NoTapretHost,
+    /// First taproot output has no internal key, so the wallet can't spend it
+    MissingInternalKey {
+        index: usize,
+    },
 }
 
 impl std::fmt::Display for TapretError {
@@ -26,6 +44,13 @@ impl std::fmt::Display for TapretError {
                 write!(f, "Invalid output index {} (max: {})", index, max)
             }
             Self::CommitmentFailed(msg) => write!(f, "Tapret commitment failed: {}", msg),
+            Self::AlreadySigned { input } => {
+                write!(f, "PSBT input {} is already signed", input)
+            }
+            Self::NoTapretHost => write!(f, "PSBT has no taproot output to host Tapret"),
+            Self::MissingInternalKey { index } => {
+                write!(f, "Taproot output {} has no internal key", index)
+            }
         }
     }
 }
@@ -38,7 +63,7 @@ impl std::error::Error for TapretError {}
 /// Useful for testing and wallet integration.
 pub fn create_test_psbt_with_taproot() -> Psbt {
     use amplify::confinement::Confined;
-    use bp::{secp256k1, LockTime, Sats, Tx, TxOut};
+    use bp::{secp256k1, LockTime, Tx, TxOut};
     use bpstd::ScriptPubkey;
 
     // Create valid P2TR script pubkey (34 bytes total)
@@ -78,44 +103,231 @@ pub fn create_test_psbt_with_taproot() -> Psbt {
 
 /// Create an anchor with Tapret proof for a given state hash
 ///
-/// Creates a valid PSBT, embeds Tapret commitment, and returns a proper
-/// Anchor with cryptographically valid dbc_proof.
+/// Creates a valid PSBT, commits the root of a single-contract MPC tree over
+/// the state hash, and returns a proper Anchor with cryptographically valid
+/// dbc_proof and the contract's MPC path.
 ///
 /// # Arguments
 ///
+/// * `contract_id` - Contract the state hash belongs to
 /// * `state_hash` - The F1r3fly state hash to commit
 ///
 /// # Returns
 ///
-/// Tuple of (Anchor, Tx) where:
-/// - Anchor contains the Tapret proof for validation
+/// Tuple of (Anchor, Tx, AnchorMethod) where:
+/// - Anchor contains the Tapret and MPC proofs for validation
 /// - Tx is the witness transaction for the consignment
+/// - AnchorMethod records the Tapret host output
 ///
 /// # Example
 ///
 /// ```rust,no_run
 /// use f1r3fly_rgb::create_tapret_anchor;
-///
+/// # use f1r3fly_rgb::ContractId;
+/// # fn example(contract_id: ContractId) {
 /// let state_hash = [0x42u8; 32];
-/// let (anchor, witness_tx) = create_tapret_anchor(state_hash).expect("Failed to create anchor");
+/// let (anchor, witness_tx, method) =
+///     create_tapret_anchor(contract_id, state_hash).expect("Failed to create anchor");
+/// # }
 /// ```
-pub fn create_tapret_anchor(state_hash: [u8; 32]) -> Result<(Anchor, bp::Tx)> {
-    use bp::Tx;
+pub fn create_tapret_anchor(
+    contract_id: ContractId,
+    state_hash: [u8; 32],
+) -> Result<(Anchor, bp::Tx, AnchorMethod)> {
+    let (mut anchors, tx, method) = create_tapret_mpc_anchors(&[(contract_id, state_hash)])?;
+    let (_, anchor) = anchors
+        .pop()
+        .ok_or_else(|| TapretError::CommitmentFailed("No anchor for contract".to_string()))?;
 
-    // Step 1: Create PSBT with Taproot output
-    let mut psbt = create_test_psbt_with_taproot();
+    Ok((anchor, tx, method))
+}
+
+/// How to find the Tapret host output in a wallet-built PSBT
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TapretHost {
+    /// Use the first taproot output the wallet holds the internal key for;
+    /// fail if there is none
+    Existing,
+
+    /// Use the first taproot output the wallet holds the internal key for,
+    /// or add a taproot change output with the given internal key and amount
+    /// if there is none
+    CreateChange {
+        internal_key: InternalPk,
+        amount: Sats,
+    },
+}
+
+/// Select (or create) the Tapret host output of a wallet-built PSBT
+///
+/// Tapret commitments must live in the first taproot output (see
+/// `check_tapret_host`), and the wallet must hold that output's internal
+/// key to spend it later. The host is the first taproot output with a
+/// `tap_internal_key`; if a foreign taproot output (e.g. a P2TR recipient)
+/// precedes it, the host is moved ahead of the foreign output. A change
+/// output created for `TapretHost::CreateChange` is placed the same way.
+/// Moving the host shifts the outputs between its old and new position by
+/// one, so callers must look up recipient indexes after this call.
+///
+/// Existing `tap_internal_key` and `tap_tree` data are left untouched; the
+/// commitment is added on top of them.
+///
+/// # Returns
+/// Index of the host output
+///
+/// # Errors
+/// Returns error if:
+/// - Any input is already signed
+/// - No taproot output has an internal key and `host` is `TapretHost::Existing`
+///   (`MissingInternalKey` if there are only foreign taproot outputs,
+///   `NoTapretHost` if there are none)
+pub fn select_tapret_host(psbt: &mut Psbt, host: TapretHost) -> Result<usize> {
+    use bpstd::ScriptPubkey;
 
-    // Step 2: Embed Tapret commitment with state hash
-    let tapret_proof = embed_tapret_commitment(&mut psbt, 0, state_hash)?;
+    ensure_unsigned(psbt)?;
 
-    // Step 3: Create anchor with Tapret proof
-    let anchor = create_anchor(&tapret_proof)?;
+    let first_taproot = psbt.outputs().position(|output| output.script.is_p2tr());
+    let owned = psbt
+        .outputs()
+        .position(|output| output.script.is_p2tr() && output.tap_internal_key.is_some());
 
-    // Step 4: Extract transaction for witness
-    // Convert PSBT back to Tx for witness storage
-    let tx: Tx = psbt.to_unsigned_tx().into();
+    let index = match (owned, host) {
+        (Some(index), _) => index,
+        (None, TapretHost::Existing) => {
+            return Err(match first_taproot {
+                Some(index) => TapretError::MissingInternalKey { index },
+                None => TapretError::NoTapretHost,
+            })
+        }
+        (
+            None,
+            TapretHost::CreateChange {
+                internal_key,
+                amount,
+            },
+        ) => {
+            let output = psbt
+                .construct_output(ScriptPubkey::p2tr_key_only(internal_key), amount)
+                .map_err(|e| {
+                    TapretError::CommitmentFailed(format!("PSBT outputs not modifiable: {:?}", e))
+                })?;
+            output.tap_internal_key = Some(internal_key);
+
+            log::debug!(
+                "Added taproot change output {} as Tapret host",
+                output.index()
+            );
+
+            output.index()
+        }
+    };
+
+    match first_taproot {
+        Some(foreign) if foreign < index => {
+            move_output(psbt, index, foreign);
+            log::debug!(
+                "Moved Tapret host output {} ahead of foreign taproot output {}",
+                index,
+                foreign
+            );
+            Ok(foreign)
+        }
+        _ => Ok(index),
+    }
+}
+
+/// Move output `from` to position `to < from`, shifting the outputs in
+/// between by one
+///
+/// Output data is swapped field by field since PSBT outputs carry their own
+/// index.
+fn move_output(psbt: &mut Psbt, from: usize, to: usize) {
+    use std::mem::swap;
+
+    let mut outputs: Vec<_> = psbt.outputs_mut().collect();
+    for pos in (to + 1..=from).rev() {
+        let (left, right) = outputs.split_at_mut(pos);
+        let (a, b) = (&mut *left[pos - 1], &mut *right[0]);
+        swap(&mut a.amount, &mut b.amount);
+        swap(&mut a.script, &mut b.script);
+        swap(&mut a.redeem_script, &mut b.redeem_script);
+        swap(&mut a.witness_script, &mut b.witness_script);
+        swap(&mut a.bip32_derivation, &mut b.bip32_derivation);
+        swap(&mut a.tap_internal_key, &mut b.tap_internal_key);
+        swap(&mut a.tap_tree, &mut b.tap_tree);
+        swap(&mut a.tap_bip32_derivation, &mut b.tap_bip32_derivation);
+        swap(&mut a.proprietary, &mut b.proprietary);
+        swap(&mut a.unknown, &mut b.unknown);
+    }
+}
+
+/// Create an anchor by committing to a wallet-built PSBT
+///
+/// Production counterpart of `create_tapret_anchor`: selects the Tapret host
+/// with `select_tapret_host`, embeds the root of a single-contract MPC tree
+/// over the state hash and returns the anchor with the contract's MPC path.
+/// The PSBT is modified in place and is ready for signing afterwards.
+///
+/// To anchor several contracts in one output, build a `MultiCommitment`,
+/// embed its root with `embed_tapret_commitment` and use
+/// `create_mpc_anchor` for each contract.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use f1r3fly_rgb::{create_tapret_anchor_in_psbt, ContractId, TapretHost};
+/// # use bpstd::psbt::Psbt;
+/// # fn example(mut psbt: Psbt, contract_id: ContractId, state_hash: [u8; 32]) -> Result<(), Box<dyn std::error::Error>> {
+/// let (anchor, method) =
+///     create_tapret_anchor_in_psbt(&mut psbt, TapretHost::Existing, contract_id, state_hash)?;
+/// // Sign and broadcast psbt; store anchor with tracker.add_anchor_with_method()
+/// # Ok(())
+/// # }
+/// ```
+pub fn create_tapret_anchor_in_psbt(
+    psbt: &mut Psbt,
+    host: TapretHost,
+    contract_id: ContractId,
+    state_hash: [u8; 32],
+) -> Result<(Anchor, AnchorMethod)> {
+    let commitment = MultiCommitment::build(&[(contract_id, state_hash)])
+        .map_err(|e| TapretError::CommitmentFailed(e.to_string()))?;
+    let (protocol, merkle_proof) = commitment
+        .proof_for(contract_id)
+        .map_err(|e| TapretError::CommitmentFailed(e.to_string()))?;
+
+    let output = select_tapret_host(psbt, host)?;
+    let tapret_proof = embed_tapret_commitment(psbt, output, commitment.root_bytes())?;
+    let anchor = create_mpc_anchor(&tapret_proof, protocol, merkle_proof)?;
+
+    Ok((
+        anchor,
+        AnchorMethod::Tapret {
+            output: output as u32,
+        },
+    ))
+}
 
-    Ok((anchor, tx))
+/// Refuse PSBTs with signed inputs
+///
+/// Adding a Tapret tweak changes an output script, which invalidates any
+/// signature committing to the outputs.
+fn ensure_unsigned(psbt: &Psbt) -> Result<()> {
+    match first_signed_input(psbt) {
+        Some(input) => Err(TapretError::AlreadySigned { input }),
+        None => Ok(()),
+    }
+}
+
+/// Index of the first PSBT input carrying a signature, if any
+pub(crate) fn first_signed_input(psbt: &Psbt) -> Option<usize> {
+    psbt.inputs().position(|input| {
+        !input.partial_sigs.is_empty()
+            || input.tap_key_sig.is_some()
+            || !input.tap_script_sig.is_empty()
+            || input.final_script_sig.is_some()
+            || input.final_witness.is_some()
+    })
 }
 
 /// Embed F1r3fly state hash as Tapret commitment in PSBT output
@@ -141,6 +353,8 @@ pub fn embed_tapret_commitment(
     output_index: usize,
     state_hash: [u8; 32],
 ) -> Result<TapretProof> {
+    ensure_unsigned(psbt)?;
+
     // Get mutable output
     let mut outputs: Vec<_> = psbt.outputs_mut().collect();
 
@@ -290,6 +504,37 @@ pub fn verify_tapret_commitment(
     Ok(())
 }
 
+/// Check that `output_index` is the Tapret host of a transaction
+///
+/// Tapret commitments live in the first taproot output; any other index
+/// indicates a malformed anchor.
+pub fn check_tapret_host(tx: &bp::Tx, output_index: usize) -> Result<()> {
+    let output = tx
+        .outputs
+        .get(output_index)
+        .ok_or(TapretError::InvalidOutputIndex {
+            index: output_index,
+            max: tx.outputs.len(),
+        })?;
+
+    if !output.script_pubkey.is_p2tr() {
+        return Err(TapretError::NotTaprootOutput);
+    }
+
+    let host = tx
+        .outputs
+        .iter()
+        .position(|output| output.script_pubkey.is_p2tr());
+    if host != Some(output_index) {
+        return Err(TapretError::CommitmentFailed(format!(
+            "Output {} is not the Tapret host (first taproot output is {:?})",
+            output_index, host
+        )));
+    }
+
+    Ok(())
+}
+
 /// Verify Tapret proof cryptographically in finalized transaction
 ///
 /// Performs full cryptographic verification: internal key, merkle path, and commitment embedding.
@@ -318,6 +563,10 @@ pub fn verify_tapret_proof_in_tx(
 /// Uses a real Tapret proof (genuine Bitcoin commitment) with placeholder
 /// MPC/MMB proofs since F1r3fly-RGB doesn't require full RGB client-side validation.
 ///
+/// The placeholder MPC proof marks a legacy anchor committing to the raw
+/// state hash, which binds no contract; new anchors are created with
+/// `create_mpc_anchor` (see `create_tapret_anchor`).
+///
 /// # Architecture Note
 /// F1r3fly-RGB uses F1r3node for contract execution and state management,
 /// while Bitcoin provides censorship-resistant anchoring via Tapret commitments.
@@ -340,6 +589,84 @@ pub fn create_anchor(proof: &TapretProof) -> Result<Anchor> {
     })
 }
 
+/// Create RGB Anchor from a Tapret proof over an MPC tree root
+///
+/// Same as `create_anchor`, but carries the contract's own MPC path so the
+/// anchor proves the state hash → tree root → Tapret commitment chain.
+///
+/// # Arguments
+/// * `proof` - Tapret proof from `embed_tapret_commitment` (over the MPC root)
+/// * `protocol` - Contract's MPC protocol ID
+/// * `merkle_proof` - Contract's path in the MPC tree
+pub fn create_mpc_anchor(
+    proof: &TapretProof,
+    protocol: mpc::ProtocolId,
+    merkle_proof: mpc::MerkleProof,
+) -> Result<Anchor> {
+    Ok(Anchor {
+        mmb_proof: mmb::BundleProof::strict_dumb(),
+        mpc_protocol: protocol,
+        mpc_proof: merkle_proof,
+        dbc_proof: Some(proof.clone()),
+        fallback_proof: ReservedBytes::strict_dumb(),
+    })
+}
+
+/// Anchor several contracts in a single Tapret commitment
+///
+/// Builds an MPC tree over the state hashes, commits its root in one Tapret
+/// output and returns one anchor per contract, each with its own Merkle path.
+///
+/// # Returns
+///
+/// Tuple of (anchors, Tx, AnchorMethod) where anchors are in input order
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use f1r3fly_rgb::create_tapret_mpc_anchors;
+/// # use f1r3fly_rgb::ContractId;
+/// # fn example(a: ContractId, b: ContractId) -> Result<(), Box<dyn std::error::Error>> {
+/// let (anchors, witness_tx, method) =
+///     create_tapret_mpc_anchors(&[(a, [0x11u8; 32]), (b, [0x22u8; 32])])?;
+/// assert_eq!(anchors.len(), 2);
+/// # Ok(())
+/// # }
+/// ```
+pub fn create_tapret_mpc_anchors(
+    entries: &[(ContractId, [u8; 32])],
+) -> Result<(Vec<(ContractId, Anchor)>, bp::Tx, AnchorMethod)> {
+    use bp::Tx;
+
+    let commitment = MultiCommitment::build(entries)
+        .map_err(|e| TapretError::CommitmentFailed(e.to_string()))?;
+
+    let mut psbt = create_test_psbt_with_taproot();
+    let output = 0;
+    let tapret_proof = embed_tapret_commitment(&mut psbt, output, commitment.root_bytes())?;
+
+    let mut anchors = Vec::with_capacity(entries.len());
+    for (contract_id, _) in entries {
+        let (protocol, merkle_proof) = commitment
+            .proof_for(*contract_id)
+            .map_err(|e| TapretError::CommitmentFailed(e.to_string()))?;
+        anchors.push((
+            *contract_id,
+            create_mpc_anchor(&tapret_proof, protocol, merkle_proof)?,
+        ));
+    }
+
+    let tx: Tx = psbt.to_unsigned_tx().into();
+
+    Ok((
+        anchors,
+        tx,
+        AnchorMethod::Tapret {
+            output: output as u32,
+        },
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -637,4 +964,194 @@ mod tests {
         verify_tapret_commitment(&psbt2, 0, state_hash2, &proof2)
             .expect("Unified verification should succeed");
     }
+
+    // ========================================================================
+    // Test 7: Multi-Protocol Commitment (several contracts, one output)
+    // ========================================================================
+
+    #[test]
+    fn test_tapret_mpc_anchors() {
+        use crate::multi_protocol::anchor_commitment;
+
+        let contract_a = ContractId::from([0xA1u8; 32]);
+        let contract_b = ContractId::from([0xB2u8; 32]);
+        let hash_a = [0x11u8; 32];
+        let hash_b = [0x22u8; 32];
+
+        let (anchors, tx, method) =
+            create_tapret_mpc_anchors(&[(contract_a, hash_a), (contract_b, hash_b)])
+                .expect("MPC anchoring should succeed");
+        assert_eq!(anchors.len(), 2);
+        assert_eq!(method, AnchorMethod::Tapret { output: 0 });
+
+        for ((contract_id, anchor), state_hash) in anchors.iter().zip([hash_a, hash_b]) {
+            let proof = anchor.dbc_proof.as_ref().expect("Tapret proof");
+            let root = anchor_commitment(anchor, *contract_id, state_hash)
+                .expect("MPC path should convolve");
+
+            // The Tapret output commits to the root, not to the raw state hash
+            assert!(verify_tapret_proof_in_tx(&tx, root, proof).is_ok());
+            assert!(verify_tapret_proof_in_tx(&tx, state_hash, proof).is_err());
+        }
+
+        // A contract's path doesn't prove another contract's state
+        let (_, anchor_a) = &anchors[0];
+        assert!(anchor_commitment(anchor_a, contract_b, hash_b).is_err());
+    }
+
+    // ========================================================================
+    // Test 8: Wallet PSBT Host Selection
+    // ========================================================================
+
+    #[test]
+    fn test_wallet_psbt_host_selection() {
+        use crate::multi_protocol::{anchor_commitment, is_legacy_anchor};
+
+        // Payment to a P2WPKH address followed by taproot change
+        let mut psbt = create_test_psbt_with_p2wpkh();
+        let internal_key = {
+            let secp = secp256k1::Secp256k1::new();
+            let secret_key = secp256k1::SecretKey::from_slice(&[7u8; 32]).unwrap();
+            let public_key = secp256k1::PublicKey::from_secret_key(&secp, &secret_key);
+            InternalPk::from(public_key.x_only_public_key().0)
+        };
+        let host = TapretHost::CreateChange {
+            internal_key,
+            amount: Sats::from(5000u64),
+        };
+
+        // No taproot output: Existing fails, CreateChange appends one
+        assert!(matches!(
+            select_tapret_host(&mut psbt.clone(), TapretHost::Existing),
+            Err(TapretError::NoTapretHost)
+        ));
+        let (contract_id, state_hash) = (ContractId::from([0x5Bu8; 32]), [0x5Au8; 32]);
+        let (anchor, method) =
+            create_tapret_anchor_in_psbt(&mut psbt, host, contract_id, state_hash)
+                .expect("Change output should host Tapret");
+        assert_eq!(method, AnchorMethod::Tapret { output: 1 });
+        assert_eq!(psbt.outputs().count(), 2);
+        assert_eq!(
+            psbt.outputs().nth(1).unwrap().tap_internal_key,
+            Some(internal_key)
+        );
+
+        let tx: Tx = psbt.to_unsigned_tx().into();
+        assert!(check_tapret_host(&tx, 1).is_ok());
+        assert!(!is_legacy_anchor(&anchor));
+        let root = anchor_commitment(&anchor, contract_id, state_hash).unwrap();
+        assert!(verify_tapret_proof_in_tx(&tx, root, anchor.dbc_proof.as_ref().unwrap()).is_ok());
+
+        // Taproot output the wallet doesn't hold the key for can't host
+        let mut foreign = create_test_psbt_with_taproot();
+        foreign.outputs_mut().next().unwrap().tap_internal_key = None;
+        assert!(matches!(
+            select_tapret_host(&mut foreign, TapretHost::Existing),
+            Err(TapretError::MissingInternalKey { index: 0 })
+        ));
+    }
+
+    #[test]
+    fn test_host_precedes_taproot_recipient() {
+        use crate::multi_protocol::anchor_commitment;
+        use bpstd::ScriptPubkey;
+
+        let internal_key = {
+            let secp = secp256k1::Secp256k1::new();
+            let secret_key = secp256k1::SecretKey::from_slice(&[7u8; 32]).unwrap();
+            let public_key = secp256k1::PublicKey::from_secret_key(&secp, &secret_key);
+            InternalPk::from(public_key.x_only_public_key().0)
+        };
+        let host = TapretHost::CreateChange {
+            internal_key,
+            amount: Sats::from(5000u64),
+        };
+
+        // Payment to a P2TR recipient at vout 0
+        let mut recipient = create_test_psbt_with_taproot();
+        recipient.outputs_mut().next().unwrap().tap_internal_key = None;
+        let recipient_script = recipient.outputs().next().unwrap().script.clone();
+
+        // Change is created ahead of the recipient
+        let mut psbt = recipient.clone();
+        let (contract_id, state_hash) = (ContractId::from([0x3Cu8; 32]), [0xC3u8; 32]);
+        let (anchor, method) =
+            create_tapret_anchor_in_psbt(&mut psbt, host, contract_id, state_hash)
+                .expect("Change output should host Tapret");
+        assert_eq!(method, AnchorMethod::Tapret { output: 0 });
+        let outputs: Vec<_> = psbt.outputs().collect();
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[0].tap_internal_key, Some(internal_key));
+        assert_eq!(outputs[1].script, recipient_script);
+        assert_eq!(outputs[1].amount, Sats::from(100_000u64));
+        assert_eq!(outputs[1].tap_internal_key, None);
+
+        let tx: Tx = psbt.to_unsigned_tx().into();
+        assert!(check_tapret_host(&tx, 0).is_ok());
+        let root = anchor_commitment(&anchor, contract_id, state_hash).unwrap();
+        assert!(verify_tapret_proof_in_tx(&tx, root, anchor.dbc_proof.as_ref().unwrap()).is_ok());
+
+        // Existing change after the recipient is moved ahead of it
+        let mut psbt = recipient;
+        let change = psbt
+            .construct_output(
+                ScriptPubkey::p2tr_key_only(internal_key),
+                Sats::from(5000u64),
+            )
+            .unwrap();
+        change.tap_internal_key = Some(internal_key);
+        assert_eq!(
+            select_tapret_host(&mut psbt, TapretHost::Existing).unwrap(),
+            0
+        );
+        let outputs: Vec<_> = psbt.outputs().collect();
+        assert_eq!(outputs[0].tap_internal_key, Some(internal_key));
+        assert_eq!(outputs[1].script, recipient_script);
+        let tx: Tx = psbt.to_unsigned_tx().into();
+        assert!(check_tapret_host(&tx, 0).is_ok());
+    }
+
+    // ========================================================================
+    // Test 9: Signed PSBTs Are Refused
+    // ========================================================================
+
+    #[test]
+    fn test_signed_psbt_refused() {
+        use bp::{Outpoint, SeqNo, SigScript, TxIn, Witness};
+
+        let mut tx = create_test_tx_with_taproot();
+        tx.inputs = Confined::try_from(vec![TxIn {
+            prev_output: Outpoint::coinbase(),
+            sig_script: SigScript::empty(),
+            sequence: SeqNo::ZERO,
+            witness: Witness::default(),
+        }])
+        .unwrap();
+        let mut psbt = create_test_psbt_with_taproot();
+        let mut signed = Psbt::from_tx(tx);
+        signed.outputs_mut().next().unwrap().tap_internal_key =
+            psbt.outputs().next().unwrap().tap_internal_key;
+
+        // Unsigned input is fine
+        assert!(select_tapret_host(&mut signed.clone(), TapretHost::Existing).is_ok());
+
+        signed.inputs_mut().next().unwrap().final_witness =
+            Some(Witness::from_consensus_stack(vec![vec![1u8; 64]]));
+        assert!(matches!(
+            create_tapret_anchor_in_psbt(
+                &mut signed,
+                TapretHost::Existing,
+                ContractId::from([0u8; 32]),
+                [0u8; 32]
+            ),
+            Err(TapretError::AlreadySigned { input: 0 })
+        ));
+        assert!(matches!(
+            embed_tapret_commitment(&mut signed, 0, [0u8; 32]),
+            Err(TapretError::AlreadySigned { input: 0 })
+        ));
+
+        // Fresh test PSBT still commits
+        assert!(embed_tapret_commitment(&mut psbt, 0, [0u8; 32]).is_ok());
+    }
 }
@@ -0,0 +1,314 @@
+//! Consignment validation reports
+//!
+//! `F1r3flyConsignment::validate_report()` runs every check it can and records
+//! the outcome of each one, instead of stopping at the first failure. Reports
+//! are serializable so wallets can display them and store them alongside the
+//! received asset.
+
+use crate::F1r3flyRgbError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Outcome of a single validation check
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    /// Check ran and succeeded
+    Passed,
+
+    /// Check ran and failed - consignment must be rejected
+    Failed,
+
+    /// Check does not apply or could not run (see reason)
+    Skipped,
+
+    /// Check succeeded with a caveat worth showing to the user
+    Warning,
+}
+
+/// Validation check performed on a consignment
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckKind {
+    /// Consignment format version is supported
+    FormatVersion,
+
+    /// Contract ID matches the contract's registry URI
+    ContractIdentity,
+
+    /// State hash matches the F1r3fly block hash and deploy ID
+    StateProof,
+
+    /// F1r3fly block is finalized
+    Finality,
+
+    /// Bitcoin commitment (Tapret or OP_RETURN) matches the state hash
+    Commitment,
+
+    /// Seals are present
+    Seals,
+
+    /// Witness mapping points at a matching witness output
+    WitnessMapping,
+
+    /// Provenance history back to issuance
+    History,
+}
+
+impl fmt::Display for CheckKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::FormatVersion => "Format version",
+            Self::ContractIdentity => "Contract identity",
+            Self::StateProof => "State proof",
+            Self::Finality => "Finality",
+            Self::Commitment => "Commitment",
+            Self::Seals => "Seals",
+            Self::WitnessMapping => "Witness mapping",
+            Self::History => "History",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Bitcoin commitment scheme used by a consignment
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitmentType {
+    /// Taproot output commitment
+    Tapret,
+
+    /// OP_RETURN output commitment
+    OpReturn,
+}
+
+/// Result of a single check
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationCheck {
+    pub kind: CheckKind,
+    pub status: CheckStatus,
+    /// Human-readable explanation
+    pub reason: String,
+}
+
+/// Detailed consignment validation report
+///
+/// # Example
+///
+/// ```rust,no_run
+/// # use f1r3fly_rgb::{F1r3flyConsignment, F1r3flyExecutor};
+/// # async fn example(consignment: F1r3flyConsignment, executor: &F1r3flyExecutor) -> Result<(), Box<dyn std::error::Error>> {
+/// let report = consignment.validate_report(executor).await;
+/// println!("{}", report);
+/// if !report.is_valid() {
+///     // Store the report next to the rejected consignment for support
+///     std::fs::write("report.json", serde_json::to_vec(&report)?)?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ValidationReport {
+    /// Contract ID of the validated consignment
+    pub contract_id: String,
+
+    /// Whether the consignment is a genesis consignment
+    pub is_genesis: bool,
+
+    /// Commitment scheme found in the anchor (None for genesis)
+    pub commitment_type: Option<CommitmentType>,
+
+    /// Checks in the order they were performed
+    pub checks: Vec<ValidationCheck>,
+
+    /// First failure, kept with its original error type for `into_result()`
+    #[serde(skip)]
+    first_error: Option<F1r3flyRgbError>,
+}
+
+impl ValidationReport {
+    /// Create an empty report
+    pub fn new(contract_id: impl ToString, is_genesis: bool) -> Self {
+        Self {
+            contract_id: contract_id.to_string(),
+            is_genesis,
+            commitment_type: None,
+            checks: Vec::new(),
+            first_error: None,
+        }
+    }
+
+    /// Record a passed check
+    pub fn pass(&mut self, kind: CheckKind, reason: impl Into<String>) {
+        self.push(kind, CheckStatus::Passed, reason.into());
+    }
+
+    /// Record a skipped check
+    pub fn skip(&mut self, kind: CheckKind, reason: impl Into<String>) {
+        self.push(kind, CheckStatus::Skipped, reason.into());
+    }
+
+    /// Record a check that passed with a caveat
+    pub fn warn(&mut self, kind: CheckKind, reason: impl Into<String>) {
+        self.push(kind, CheckStatus::Warning, reason.into());
+    }
+
+    /// Record a failed check
+    pub fn fail(&mut self, kind: CheckKind, error: F1r3flyRgbError) {
+        let reason = match &error {
+            F1r3flyRgbError::InvalidConsignment(msg) => msg.clone(),
+            other => other.to_string(),
+        };
+        self.push(kind, CheckStatus::Failed, reason);
+        self.first_error.get_or_insert(error);
+    }
+
+    /// Record the outcome of a fallible check
+    pub fn record(&mut self, kind: CheckKind, result: Result<String, F1r3flyRgbError>) -> bool {
+        match result {
+            Ok(reason) => {
+                self.pass(kind, reason);
+                true
+            }
+            Err(error) => {
+                self.fail(kind, error);
+                false
+            }
+        }
+    }
+
+    /// Whether no check failed
+    pub fn is_valid(&self) -> bool {
+        !self
+            .checks
+            .iter()
+            .any(|check| check.status == CheckStatus::Failed)
+    }
+
+    /// Get the result of a check by kind
+    pub fn check(&self, kind: CheckKind) -> Option<&ValidationCheck> {
+        self.checks.iter().find(|check| check.kind == kind)
+    }
+
+    /// Iterate over failed checks
+    pub fn failures(&self) -> impl Iterator<Item = &ValidationCheck> {
+        self.with_status(CheckStatus::Failed)
+    }
+
+    /// Iterate over checks passed with warnings
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationCheck> {
+        self.with_status(CheckStatus::Warning)
+    }
+
+    /// Convert into pass/fail result
+    ///
+    /// Returns the first failure with its original error type. Reports loaded
+    /// from JSON carry only reasons and fail with `InvalidConsignment`.
+    pub fn into_result(mut self) -> Result<(), F1r3flyRgbError> {
+        if let Some(error) = self.first_error.take() {
+            return Err(error);
+        }
+        match self.failures().next() {
+            Some(check) => Err(F1r3flyRgbError::InvalidConsignment(format!(
+                "{}: {}",
+                check.kind, check.reason
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    fn push(&mut self, kind: CheckKind, status: CheckStatus, reason: String) {
+        self.checks.push(ValidationCheck {
+            kind,
+            status,
+            reason,
+        });
+    }
+
+    fn with_status(&self, status: CheckStatus) -> impl Iterator<Item = &ValidationCheck> {
+        self.checks
+            .iter()
+            .filter(move |check| check.status == status)
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Consignment {} ({}): {}",
+            self.contract_id,
+            if self.is_genesis {
+                "genesis"
+            } else {
+                "transfer"
+            },
+            if self.is_valid() { "VALID" } else { "INVALID" }
+        )?;
+        for check in &self.checks {
+            let marker = match check.status {
+                CheckStatus::Passed => "✓",
+                CheckStatus::Failed => "✗",
+                CheckStatus::Skipped => "-",
+                CheckStatus::Warning => "!",
+            };
+            writeln!(f, "  {} {}: {}", marker, check.kind, check.reason)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_keeps_first_error() {
+        let mut report = ValidationReport::new("contract:test", false);
+        report.pass(CheckKind::FormatVersion, "Version 1.1");
+        report.skip(CheckKind::History, "No provenance history shipped");
+        assert!(report.is_valid());
+
+        report.fail(
+            CheckKind::Finality,
+            F1r3flyRgbError::ConnectionFailed("node offline".to_string()),
+        );
+        report.fail(
+            CheckKind::Commitment,
+            F1r3flyRgbError::InvalidConsignment("hash mismatch".to_string()),
+        );
+
+        assert!(!report.is_valid());
+        assert_eq!(report.failures().count(), 2);
+        assert_eq!(
+            report.check(CheckKind::Commitment).unwrap().reason,
+            "hash mismatch"
+        );
+        assert!(matches!(
+            report.clone().into_result(),
+            Err(F1r3flyRgbError::ConnectionFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_report_json_round_trip() {
+        let mut report = ValidationReport::new("contract:test", true);
+        report.skip(CheckKind::Commitment, "Genesis consignment");
+        report.fail(
+            CheckKind::Seals,
+            F1r3flyRgbError::InvalidConsignment("No seals in consignment".to_string()),
+        );
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.contains("\"status\":\"skipped\""));
+
+        let loaded: ValidationReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.checks, report.checks);
+        match loaded.into_result() {
+            Err(F1r3flyRgbError::InvalidConsignment(msg)) => {
+                assert_eq!(msg, "Seals: No seals in consignment")
+            }
+            other => panic!("Expected InvalidConsignment, got: {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,709 @@
+//! Witness transaction composer
+//!
+//! Builds the Bitcoin witness transaction of a transfer: spends the source
+//! seal outpoints (plus extra local UTXOs when needed), pays the invoice's
+//! `WitnessOut` address, returns change and places the Tapret or OP_RETURN
+//! commitment to the F1r3fly state hash.
+//!
+//! # Output Layout
+//!
+//! - **Tapret**: `[change (Tapret host), recipient]`
+//! - **OP_RETURN**: `[OP_RETURN, recipient, change]` (change dropped if dust)
+//!
+//! The recipient is always output 1, so `WitnessMapping::expected_vout` is 1.
+//! Change comes first for Tapret because the host must be the first taproot
+//! output, and the recipient's address may itself be taproot. The change
+//! output gets a witness mapping as well, for the sender's token change.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use f1r3fly_rgb::{get_recipient_address, CommitmentType, WitnessTxBuilder};
+//! # use f1r3fly_rgb::Utxo;
+//! # use f1r3fly_rgb::{RgbBeneficiary, Sats};
+//! # use bp::{InternalPk, Outpoint};
+//! # fn example(beneficiary: RgbBeneficiary, seal: Outpoint, utxos: Vec<Utxo>, change_key: InternalPk, state_hash: [u8; 32]) -> Result<(), Box<dyn std::error::Error>> {
+//! let address = get_recipient_address(&beneficiary, bitcoin::Network::Regtest)?;
+//!
+//! let witness = WitnessTxBuilder::new(&address, bitcoin::Network::Regtest, state_hash)?
+//!     .spend_seal(seal)
+//!     .utxos(utxos)
+//!     .fee_rate(5)
+//!     .change_key(change_key)
+//!     .commitment(CommitmentType::Tapret)
+//!     .build()?;
+//!
+//! // Sign witness.psbt, then ship witness.anchor and witness.witness_mapping
+//! // in the consignment
+//! # Ok(())
+//! # }
+//! ```
+
+use amplify::confinement::Confined;
+use amplify::ByteArray;
+use bp::seals::Anchor;
+use bp::{InternalPk, LockTime, Outpoint, Sats, SeqNo, SigScript, Tx, TxIn, TxOut, TxVer, Witness};
+use bpstd::psbt::Psbt;
+use bpstd::ScriptPubkey;
+use hypersonic::ContractId;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::multi_protocol::MultiCommitment;
+use crate::opreturn::{create_opreturn_anchor, embed_opret_commitment_psbt, OpReturnError};
+use crate::tapret::{create_anchor, create_mpc_anchor, embed_tapret_commitment, TapretError};
+use crate::validation::CommitmentType;
+use crate::{AnchorMethod, SealKey, WitnessMapping};
+
+pub type Result<T> = std::result::Result<T, WitnessTxError>;
+
+/// Minimum fee rate accepted by default relay policy (sat/vB)
+pub const MIN_FEE_RATE: u64 = 1;
+
+/// Fee rates above this are rejected as likely unit mistakes (sat/vB)
+pub const MAX_FEE_RATE: u64 = 1_000;
+
+/// Default value sent to the recipient's witness output
+pub const DEFAULT_RECIPIENT_AMOUNT: u64 = 1_000;
+
+/// Outputs below this value are not relayed
+pub const DUST_LIMIT: u64 = 546;
+
+/// Recipient output index in every witness transaction
+pub const RECIPIENT_VOUT: u32 = 1;
+
+// Conservative virtual size estimates (vbytes)
+const TX_OVERHEAD_VSIZE: u64 = 11;
+const INPUT_VSIZE: u64 = 68;
+const OUTPUT_BASE_VSIZE: u64 = 9;
+const P2TR_SCRIPT_LEN: u64 = 34;
+const OPRETURN_SCRIPT_LEN: u64 = 34;
+
+#[derive(Debug)]
+pub enum WitnessTxError {
+    /// Recipient address can't be parsed or is for another network
+    InvalidAddress(String),
+    /// Fee rate outside `MIN_FEE_RATE..=MAX_FEE_RATE`
+    InvalidFeeRate(u64),
+    /// No seal outpoint to spend
+    NoSeals,
+    /// Seal outpoint is not in the local UTXO set
+    MissingSealUtxo(String),
+    /// Tapret commitments need a taproot change key
+    MissingChangeKey,
+    /// Local UTXOs don't cover amount and fee
+    InsufficientFunds { needed: u64, available: u64 },
+    /// Tapret embedding failed
+    Tapret(TapretError),
+    /// Opret embedding failed
+    OpReturn(OpReturnError),
+    /// Transaction construction failed
+    Construction(String),
+}
+
+impl std::fmt::Display for WitnessTxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidAddress(msg) => write!(f, "Invalid recipient address: {}", msg),
+            Self::InvalidFeeRate(rate) => write!(
+                f,
+                "Fee rate {} sat/vB outside policy ({}..={})",
+                rate, MIN_FEE_RATE, MAX_FEE_RATE
+            ),
+            Self::NoSeals => write!(f, "No seal outpoints to spend"),
+            Self::MissingSealUtxo(outpoint) => {
+                write!(f, "Seal outpoint {} not in local UTXO set", outpoint)
+            }
+            Self::MissingChangeKey => write!(f, "Tapret commitment requires a change key"),
+            Self::InsufficientFunds { needed, available } => write!(
+                f,
+                "Insufficient funds: need {} sats, have {} sats",
+                needed, available
+            ),
+            Self::Tapret(e) => write!(f, "{}", e),
+            Self::OpReturn(e) => write!(f, "{}", e),
+            Self::Construction(msg) => {
+                write!(f, "Witness transaction construction failed: {}", msg)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WitnessTxError {}
+
+impl From<TapretError> for WitnessTxError {
+    fn from(e: TapretError) -> Self {
+        Self::Tapret(e)
+    }
+}
+
+impl From<OpReturnError> for WitnessTxError {
+    fn from(e: OpReturnError) -> Self {
+        Self::OpReturn(e)
+    }
+}
+
+/// Spendable UTXO owned by the wallet
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Utxo {
+    pub outpoint: Outpoint,
+    pub value: Sats,
+    pub script_pubkey: ScriptPubkey,
+}
+
+/// Composed witness transaction, ready for signing
+#[derive(Clone, Debug)]
+pub struct WitnessTx {
+    /// Unsigned PSBT with the commitment embedded
+    pub psbt: Psbt,
+
+    /// Anchor for the consignment / `BitcoinAnchorTracker`
+    pub anchor: Anchor,
+
+    /// Commitment method and output
+    pub anchor_method: AnchorMethod,
+
+    /// Mapping from the recipient's witness ID to its output
+    pub witness_mapping: WitnessMapping,
+
+    /// Mapping from the change output's witness ID to it (`None` without a
+    /// change output)
+    pub change_mapping: Option<WitnessMapping>,
+
+    /// Absolute fee paid
+    pub fee: Sats,
+
+    /// Estimated virtual size (vbytes) the fee was computed for
+    pub vsize: u64,
+}
+
+/// Derive the temporary witness ID of a recipient output
+///
+/// Format: "witness:{hash}:{vout}" where `hash` is the first 8 bytes of
+/// SHA-256 of the first spent seal and the address, hex-encoded (see
+/// `SealKey::witness`).
+pub fn witness_id(spent: Outpoint, address: &str, vout: u32) -> String {
+    SealKey::witness(spent, address, vout).to_string()
+}
+
+/// Builder for witness transactions
+///
+/// Serializable so the tracker can keep it for fee bumping (see
+/// `BitcoinAnchorTracker::bump_witness_fee`).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WitnessTxBuilder {
+    recipient_address: String,
+    recipient_script: ScriptPubkey,
+    recipient_amount: u64,
+    state_hash: [u8; 32],
+    seals: Vec<Outpoint>,
+    utxos: Vec<Utxo>,
+    fee_rate: u64,
+    change_key: Option<InternalPk>,
+    commitment: CommitmentType,
+    /// Contract committed through a single-contract MPC tree (raw bytes)
+    #[serde(default)]
+    contract_id: Option<[u8; 32]>,
+}
+
+impl WitnessTxBuilder {
+    /// Start a witness transaction paying `recipient_address`
+    ///
+    /// # Arguments
+    /// * `recipient_address` - Address from `get_recipient_address`
+    /// * `network` - Network the address must belong to
+    /// * `state_hash` - F1r3fly state hash (or MPC root) to commit
+    pub fn new(
+        recipient_address: &str,
+        network: bitcoin::Network,
+        state_hash: [u8; 32],
+    ) -> Result<Self> {
+        let address = bitcoin::Address::from_str(recipient_address)
+            .map_err(|e| WitnessTxError::InvalidAddress(e.to_string()))?
+            .require_network(network)
+            .map_err(|e| WitnessTxError::InvalidAddress(e.to_string()))?;
+        let recipient_script =
+            ScriptPubkey::from_checked(address.script_pubkey().as_bytes().to_vec());
+
+        Ok(Self {
+            recipient_address: recipient_address.to_string(),
+            recipient_script,
+            recipient_amount: DEFAULT_RECIPIENT_AMOUNT,
+            state_hash,
+            seals: Vec::new(),
+            utxos: Vec::new(),
+            fee_rate: MIN_FEE_RATE,
+            change_key: None,
+            commitment: CommitmentType::Tapret,
+            contract_id: None,
+        })
+    }
+
+    /// Spend a source seal outpoint (must be in the UTXO set)
+    pub fn spend_seal(mut self, outpoint: Outpoint) -> Self {
+        self.seals.push(outpoint);
+        self
+    }
+
+    /// Local UTXO set used for seals and coin selection
+    pub fn utxos(mut self, utxos: impl IntoIterator<Item = Utxo>) -> Self {
+        self.utxos.extend(utxos);
+        self
+    }
+
+    /// Fee rate in sat/vB
+    pub fn fee_rate(mut self, sat_per_vb: u64) -> Self {
+        self.fee_rate = sat_per_vb;
+        self
+    }
+
+    /// Configured fee rate in sat/vB
+    pub fn current_fee_rate(&self) -> u64 {
+        self.fee_rate
+    }
+
+    /// Value sent to the recipient output (defaults to `DEFAULT_RECIPIENT_AMOUNT`)
+    pub fn recipient_amount(mut self, amount: Sats) -> Self {
+        self.recipient_amount = amount.sats();
+        self
+    }
+
+    /// Internal key of the taproot change output
+    pub fn change_key(mut self, internal_key: InternalPk) -> Self {
+        self.change_key = Some(internal_key);
+        self
+    }
+
+    /// Commitment scheme (defaults to Tapret)
+    pub fn commitment(mut self, commitment: CommitmentType) -> Self {
+        self.commitment = commitment;
+        self
+    }
+
+    /// Contract the state hash belongs to
+    ///
+    /// When set, the state hash is committed through a single-contract MPC
+    /// tree and the anchor carries the contract's MPC path. Without it the
+    /// state hash (or an MPC root built by the caller) is committed as is.
+    pub fn contract_id(mut self, contract_id: ContractId) -> Self {
+        self.contract_id = Some(contract_id.to_byte_array());
+        self
+    }
+
+    /// Replace the state hash to commit
+    ///
+    /// Lets a builder be configured before the F1r3fly operation has run
+    /// (see `F1r3flyRgbContract::send`).
+    pub fn state_hash(mut self, state_hash: [u8; 32]) -> Self {
+        self.state_hash = state_hash;
+        self
+    }
+
+    /// Address the recipient output pays
+    pub fn recipient_address(&self) -> &str {
+        &self.recipient_address
+    }
+
+    /// Script of the recipient output
+    pub fn recipient_script(&self) -> &ScriptPubkey {
+        &self.recipient_script
+    }
+
+    /// Address paying `script` on the network of the recipient address
+    fn output_address(&self, script: &ScriptPubkey) -> Result<String> {
+        let recipient = bitcoin::Address::from_str(&self.recipient_address)
+            .map_err(|e| WitnessTxError::InvalidAddress(e.to_string()))?;
+        let network = [
+            bitcoin::Network::Bitcoin,
+            bitcoin::Network::Testnet,
+            bitcoin::Network::Signet,
+            bitcoin::Network::Regtest,
+        ]
+        .into_iter()
+        .find(|network| recipient.is_valid_for_network(*network))
+        .ok_or_else(|| WitnessTxError::InvalidAddress(self.recipient_address.clone()))?;
+        bitcoin::Address::from_script(bitcoin::Script::from_bytes(script.as_slice()), network)
+            .map(|address| address.to_string())
+            .map_err(|e| WitnessTxError::Construction(e.to_string()))
+    }
+
+    /// Select coins, compute fee and change, and embed the commitment
+    pub fn build(self) -> Result<WitnessTx> {
+        if !(MIN_FEE_RATE..=MAX_FEE_RATE).contains(&self.fee_rate) {
+            return Err(WitnessTxError::InvalidFeeRate(self.fee_rate));
+        }
+        if self.seals.is_empty() {
+            return Err(WitnessTxError::NoSeals);
+        }
+        if self.commitment == CommitmentType::Tapret && self.change_key.is_none() {
+            return Err(WitnessTxError::MissingChangeKey);
+        }
+
+        // Seals are always spent, first (witness IDs derive from input 0)
+        let mut selected = Vec::new();
+        for seal in &self.seals {
+            let utxo = self
+                .utxos
+                .iter()
+                .find(|utxo| utxo.outpoint == *seal)
+                .ok_or_else(|| WitnessTxError::MissingSealUtxo(seal.to_string()))?;
+            if !selected.contains(utxo) {
+                selected.push(utxo.clone());
+            }
+        }
+
+        // Largest-first for the rest
+        let mut candidates: Vec<&Utxo> = self
+            .utxos
+            .iter()
+            .filter(|utxo| !selected.contains(utxo))
+            .collect();
+        candidates.sort_by(|a, b| b.value.sats().cmp(&a.value.sats()));
+        let mut candidates = candidates.into_iter();
+
+        let recipient_vsize = OUTPUT_BASE_VSIZE + self.recipient_script.len() as u64;
+        let (fee, change, vsize) = loop {
+            let input_sum: u64 = selected.iter().map(|utxo| utxo.value.sats()).sum();
+            let vsize = TX_OVERHEAD_VSIZE
+                + INPUT_VSIZE * selected.len() as u64
+                + recipient_vsize
+                + OUTPUT_BASE_VSIZE
+                + match self.commitment {
+                    CommitmentType::Tapret => P2TR_SCRIPT_LEN,
+                    CommitmentType::OpReturn => {
+                        OPRETURN_SCRIPT_LEN + OUTPUT_BASE_VSIZE + P2TR_SCRIPT_LEN
+                    }
+                };
+            let fee = vsize * self.fee_rate;
+            let needed = self.recipient_amount + fee + DUST_LIMIT;
+
+            if input_sum >= needed {
+                break (fee, input_sum - self.recipient_amount - fee, vsize);
+            }
+            match candidates.next() {
+                Some(utxo) => selected.push(utxo.clone()),
+                None if self.commitment == CommitmentType::OpReturn
+                    && input_sum >= self.recipient_amount + fee =>
+                {
+                    // Dust change goes to fees
+                    break (input_sum - self.recipient_amount, 0, vsize);
+                }
+                None => {
+                    return Err(WitnessTxError::InsufficientFunds {
+                        needed,
+                        available: input_sum,
+                    })
+                }
+            }
+        };
+
+        let recipient = TxOut {
+            value: Sats::from(self.recipient_amount),
+            script_pubkey: self.recipient_script.clone(),
+        };
+        let change_out = self.change_key.map(|key| TxOut {
+            value: Sats::from(change),
+            script_pubkey: ScriptPubkey::p2tr_key_only(key),
+        });
+
+        let outputs = match self.commitment {
+            CommitmentType::Tapret => {
+                vec![
+                    change_out.clone().ok_or(WitnessTxError::MissingChangeKey)?,
+                    recipient,
+                ]
+            }
+            CommitmentType::OpReturn => {
+                let mut outputs = vec![
+                    TxOut {
+                        value: Sats::ZERO,
+                        // Placeholder, filled in by the Opret commitment below
+                        script_pubkey: ScriptPubkey::op_return(&[]),
+                    },
+                    recipient,
+                ];
+                if change >= DUST_LIMIT {
+                    let change_out = change_out.ok_or_else(|| {
+                        WitnessTxError::Construction(
+                            "change output requires a change key".to_string(),
+                        )
+                    })?;
+                    outputs.push(change_out);
+                }
+                outputs
+            }
+        };
+
+        let change_vout = match self.commitment {
+            CommitmentType::Tapret => Some(0u32),
+            CommitmentType::OpReturn => (outputs.len() > 2).then_some(2),
+        };
+        let change_mapping = match change_vout {
+            Some(vout) => {
+                let address = self.output_address(&outputs[vout as usize].script_pubkey)?;
+                Some(WitnessMapping {
+                    witness_id: SealKey::witness(self.seals[0], &address, vout),
+                    recipient_address: address,
+                    expected_vout: vout,
+                })
+            }
+            None => None,
+        };
+
+        let inputs = selected
+            .iter()
+            .map(|utxo| TxIn {
+                prev_output: utxo.outpoint,
+                sig_script: SigScript::empty(),
+                // Non-final sequence signals RBF for fee bumping
+                sequence: SeqNo::ZERO,
+                witness: Witness::default(),
+            })
+            .collect::<Vec<_>>();
+
+        let tx = Tx {
+            version: TxVer::V2,
+            inputs: Confined::try_from(inputs)
+                .map_err(|e| WitnessTxError::Construction(e.to_string()))?,
+            outputs: Confined::try_from(outputs)
+                .map_err(|e| WitnessTxError::Construction(e.to_string()))?,
+            lock_time: LockTime::ZERO,
+        };
+
+        let mut psbt = Psbt::from_tx(tx);
+        for (input, utxo) in psbt.inputs_mut().zip(&selected) {
+            input.witness_utxo = Some(TxOut {
+                value: utxo.value,
+                script_pubkey: utxo.script_pubkey.clone(),
+            });
+        }
+
+        let (commitment, mpc) = match self.contract_id.map(ContractId::from) {
+            Some(contract_id) => {
+                let tree = MultiCommitment::build(&[(contract_id, self.state_hash)])
+                    .map_err(|e| WitnessTxError::Construction(e.to_string()))?;
+                let mpc = tree
+                    .proof_for(contract_id)
+                    .map_err(|e| WitnessTxError::Construction(e.to_string()))?;
+                (tree.root_bytes(), Some(mpc))
+            }
+            None => (self.state_hash, None),
+        };
+
+        let (anchor, anchor_method) = match self.commitment {
+            CommitmentType::Tapret => {
+                if let Some(output) = psbt.outputs_mut().next() {
+                    output.tap_internal_key = self.change_key;
+                }
+                let proof = embed_tapret_commitment(&mut psbt, 0, commitment)?;
+                let anchor = match mpc {
+                    Some((protocol, merkle_proof)) => {
+                        create_mpc_anchor(&proof, protocol, merkle_proof)?
+                    }
+                    None => create_anchor(&proof)?,
+                };
+                (anchor, AnchorMethod::Tapret { output: 0 })
+            }
+            CommitmentType::OpReturn => {
+                if let (Some(output), Some(key)) = (psbt.outputs_mut().nth(2), self.change_key) {
+                    output.tap_internal_key = Some(key);
+                }
+                embed_opret_commitment_psbt(&mut psbt, 0, commitment)?;
+                let (mut anchor, method) = create_opreturn_anchor(commitment, 0);
+                if let Some((protocol, merkle_proof)) = mpc {
+                    anchor.mpc_protocol = protocol;
+                    anchor.mpc_proof = merkle_proof;
+                }
+                (anchor, method)
+            }
+        };
+
+        let witness_mapping = WitnessMapping {
+            witness_id: SealKey::witness(self.seals[0], &self.recipient_address, RECIPIENT_VOUT),
+            recipient_address: self.recipient_address,
+            expected_vout: RECIPIENT_VOUT,
+        };
+
+        log::debug!(
+            "Witness transaction composed: {} input(s), fee {} sats, {}",
+            selected.len(),
+            fee,
+            anchor_method
+        );
+
+        Ok(WitnessTx {
+            psbt,
+            anchor,
+            anchor_method,
+            witness_mapping,
+            change_mapping,
+            fee: Sats::from(fee),
+            vsize,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opreturn::{extract_opreturn_commitment, verify_opret_proof_in_tx};
+    use crate::tapret::{check_tapret_host, verify_tapret_proof_in_tx};
+    use bp::{secp256k1, Txid};
+
+    fn recipient() -> String {
+        bitcoin::Address::p2wsh(&bitcoin::ScriptBuf::new(), bitcoin::Network::Regtest).to_string()
+    }
+
+    fn change_key() -> InternalPk {
+        let secp = secp256k1::Secp256k1::new();
+        let secret_key = secp256k1::SecretKey::from_slice(&[3u8; 32]).unwrap();
+        let public_key = secp256k1::PublicKey::from_secret_key(&secp, &secret_key);
+        InternalPk::from(public_key.x_only_public_key().0)
+    }
+
+    fn utxo(seed: u8, value: u64) -> Utxo {
+        Utxo {
+            outpoint: Outpoint::new(Txid::from([seed; 32]), 0),
+            value: Sats::from(value),
+            script_pubkey: ScriptPubkey::p2tr_key_only(change_key()),
+        }
+    }
+
+    #[test]
+    fn test_tapret_witness_tx() {
+        let state_hash = [0x42u8; 32];
+        let seal = utxo(1, 600);
+        let witness = WitnessTxBuilder::new(&recipient(), bitcoin::Network::Regtest, state_hash)
+            .unwrap()
+            .spend_seal(seal.outpoint)
+            .utxos([seal.clone(), utxo(2, 2_000), utxo(3, 50_000)])
+            .fee_rate(2)
+            .change_key(change_key())
+            .build()
+            .expect("Witness transaction should build");
+
+        // Seal plus the largest extra UTXO
+        let tx: Tx = witness.psbt.to_unsigned_tx().into();
+        assert_eq!(tx.inputs.len(), 2);
+        assert_eq!(tx.inputs[0].prev_output, seal.outpoint);
+
+        let input_sum = 600 + 50_000;
+        let output_sum: u64 = tx.outputs.iter().map(|out| out.value.sats()).sum();
+        assert_eq!(input_sum - output_sum, witness.fee.sats());
+        assert_eq!(tx.outputs[1].value.sats(), DEFAULT_RECIPIENT_AMOUNT);
+
+        assert_eq!(witness.anchor_method, AnchorMethod::Tapret { output: 0 });
+        assert!(check_tapret_host(&tx, 0).is_ok());
+        let proof = witness.anchor.dbc_proof.as_ref().unwrap();
+        assert!(verify_tapret_proof_in_tx(&tx, state_hash, proof).is_ok());
+
+        assert_eq!(witness.witness_mapping.expected_vout, RECIPIENT_VOUT);
+        assert_eq!(
+            witness.witness_mapping.witness_id,
+            SealKey::witness(seal.outpoint, &recipient(), RECIPIENT_VOUT)
+        );
+        let change = witness.change_mapping.expect("Tapret always has change");
+        assert_eq!(change.expected_vout, 0);
+        let change_address = bitcoin::Address::from_str(&change.recipient_address)
+            .unwrap()
+            .require_network(bitcoin::Network::Regtest)
+            .unwrap();
+        assert_eq!(
+            change_address.script_pubkey().as_bytes(),
+            tx.outputs[0].script_pubkey.as_slice()
+        );
+        assert_eq!(
+            change.witness_id,
+            SealKey::witness(seal.outpoint, &change.recipient_address, 0)
+        );
+    }
+
+    #[test]
+    fn test_opreturn_witness_tx() {
+        let state_hash = [0x24u8; 32];
+        let seal = utxo(1, 10_000);
+        let witness = WitnessTxBuilder::new(&recipient(), bitcoin::Network::Regtest, state_hash)
+            .unwrap()
+            .spend_seal(seal.outpoint)
+            .utxos([seal])
+            .change_key(change_key())
+            .commitment(CommitmentType::OpReturn)
+            .build()
+            .expect("Witness transaction should build");
+
+        let tx: Tx = witness.psbt.to_unsigned_tx().into();
+        assert_eq!(tx.outputs.len(), 3);
+        assert_eq!(extract_opreturn_commitment(&tx, 0).unwrap(), state_hash);
+        assert!(verify_opret_proof_in_tx(&tx, 0, state_hash).is_ok());
+        assert_eq!(witness.anchor_method, AnchorMethod::OpReturn { output: 0 });
+        assert!(witness.anchor.dbc_proof.is_none());
+        assert_eq!(witness.change_mapping.unwrap().expected_vout, 2);
+    }
+
+    #[test]
+    fn test_contract_witness_tx_commits_mpc_root() {
+        use crate::multi_protocol::{anchor_commitment, is_legacy_anchor};
+
+        let (contract_id, state_hash) = (ContractId::from([0x17u8; 32]), [0x71u8; 32]);
+        let seal = utxo(1, 10_000);
+        let builder = WitnessTxBuilder::new(&recipient(), bitcoin::Network::Regtest, state_hash)
+            .unwrap()
+            .spend_seal(seal.outpoint)
+            .utxos([seal])
+            .change_key(change_key())
+            .contract_id(contract_id);
+
+        let tapret = builder.clone().build().unwrap();
+        let tx: Tx = tapret.psbt.to_unsigned_tx().into();
+        assert!(!is_legacy_anchor(&tapret.anchor));
+        let root = anchor_commitment(&tapret.anchor, contract_id, state_hash).unwrap();
+        assert_ne!(root, state_hash);
+        let proof = tapret.anchor.dbc_proof.as_ref().unwrap();
+        assert!(verify_tapret_proof_in_tx(&tx, root, proof).is_ok());
+
+        let opret = builder
+            .commitment(CommitmentType::OpReturn)
+            .build()
+            .unwrap();
+        let tx: Tx = opret.psbt.to_unsigned_tx().into();
+        let root = anchor_commitment(&opret.anchor, contract_id, state_hash).unwrap();
+        assert!(verify_opret_proof_in_tx(&tx, 0, root).is_ok());
+        assert!(opret.anchor.dbc_proof.is_none());
+    }
+
+    #[test]
+    fn test_witness_tx_errors() {
+        let builder = WitnessTxBuilder::new(&recipient(), bitcoin::Network::Regtest, [0u8; 32])
+            .unwrap()
+            .change_key(change_key());
+
+        assert!(matches!(
+            builder.clone().build(),
+            Err(WitnessTxError::NoSeals)
+        ));
+        assert!(matches!(
+            builder
+                .clone()
+                .spend_seal(utxo(9, 0).outpoint)
+                .utxos([utxo(1, 1_000)])
+                .build(),
+            Err(WitnessTxError::MissingSealUtxo(_))
+        ));
+        assert!(matches!(
+            builder
+                .clone()
+                .spend_seal(utxo(1, 0).outpoint)
+                .utxos([utxo(1, 1_000)])
+                .build(),
+            Err(WitnessTxError::InsufficientFunds { .. })
+        ));
+        assert!(matches!(
+            builder.clone().fee_rate(0).build(),
+            Err(WitnessTxError::InvalidFeeRate(0))
+        ));
+        assert!(matches!(
+            WitnessTxBuilder::new(&recipient(), bitcoin::Network::Bitcoin, [0u8; 32]),
+            Err(WitnessTxError::InvalidAddress(_))
+        ));
+    }
+}
@@ -16,9 +16,14 @@
 use amplify::confinement::SmallOrdMap;
 use amplify::ByteArray;
 use bp::seals::{Anchor, Noise, TxoSealExt, WOutpoint};
-use bp::{Outpoint, Vout};
+use bp::{secp256k1, InternalPk, Outpoint, Vout};
+use bpstd::ScriptPubkey;
 use f1r3fly_rgb::{
-    AnchorConfig, BitcoinAnchorTracker, Pile, Tx, Txid, TxoSeal, WTxoSeal, WitnessStatus,
+    check_file, rekey_file, AnchorConfig, AnchorMethod, AnchorStore, BitcoinAnchorError,
+    BitcoinAnchorTracker, ContractMetadata, EncryptionKey, ExecutorSnapshot, LogStore, MemoryStore,
+    MockChain, Pile, PrunePolicy, Sats, StatusKind, StorageBackend, SyncEvent, Tx, Txid, TxoSeal,
+    Utxo, WTxoSeal, WitnessFilter, WitnessStatus, WitnessTxBuilder, MIGRATIONS, MIN_FEE_RATE,
+    TRACKER_SCHEMA_VERSION,
 };
 use rgb::{CellAddr, Opid}; // Import from rgb-std (which re-exports from ultrasonic)
 use std::num::NonZero;
@@ -75,6 +80,7 @@ fn test_pile_lifecycle_with_persistence() {
     // Create with persistence config
     let conf = AnchorConfig {
         persistence_path: Some(db_path.clone()),
+        encryption: None,
     };
 
     let mut tracker = <BitcoinAnchorTracker<TxoSeal> as Pile>::new(conf.clone()).unwrap();
@@ -660,3 +666,1191 @@ fn test_large_scale_operations() {
         "TempDir should be cleaned up after drop"
     );
 }
+
+// ============================================================================
+// Test 11: Anchor Methods (Explicit and Legacy)
+// ============================================================================
+
+#[test]
+fn test_anchor_methods_persistence() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("anchor_methods.json");
+
+    let mut tracker = BitcoinAnchorTracker::<TxoSeal>::new();
+    let tapret_opid = test_opid(1);
+    let opreturn_opid = test_opid(2);
+    let legacy_opid = test_opid(3);
+
+    tracker.add_anchor_with_method(
+        tapret_opid,
+        test_anchor(),
+        AnchorMethod::Tapret { output: 0 },
+    );
+    tracker.add_anchor_with_method(
+        opreturn_opid,
+        test_anchor(),
+        AnchorMethod::OpReturn { output: 2 },
+    );
+    tracker.add_anchor(legacy_opid, test_anchor());
+
+    assert_eq!(
+        tracker.get_anchor_method(&opreturn_opid),
+        Some(AnchorMethod::OpReturn { output: 2 })
+    );
+    assert_eq!(tracker.get_anchor_method(&legacy_opid), None);
+
+    // Methods survive save/load
+    tracker.save(&db_path).unwrap();
+    let loaded = BitcoinAnchorTracker::<TxoSeal>::load_from_disk(&db_path).unwrap();
+    assert_eq!(
+        loaded.get_anchor_method(&tapret_opid),
+        Some(AnchorMethod::Tapret { output: 0 })
+    );
+    assert_eq!(
+        loaded.get_anchor_method(&opreturn_opid),
+        Some(AnchorMethod::OpReturn { output: 2 })
+    );
+
+    // Replacing or removing the anchor drops its method
+    let mut loaded = loaded;
+    loaded.add_anchor(tapret_opid, test_anchor());
+    assert_eq!(loaded.get_anchor_method(&tapret_opid), None);
+    loaded.remove_anchor(&opreturn_opid);
+    assert_eq!(loaded.get_anchor_method(&opreturn_opid), None);
+
+    // Files written before anchor methods existed still load
+    let mut json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&db_path).unwrap()).unwrap();
+    let mut bare = json["tracker"].take();
+    bare.as_object_mut().unwrap().remove("anchor_methods");
+    std::fs::write(&db_path, serde_json::to_string(&bare).unwrap()).unwrap();
+    let legacy = BitcoinAnchorTracker::<TxoSeal>::load_from_disk(&db_path).unwrap();
+    assert!(legacy.has_anchor(&tapret_opid));
+    assert_eq!(legacy.get_anchor_method(&tapret_opid), None);
+    assert_eq!(
+        AnchorMethod::infer_legacy(legacy.get_anchor(&tapret_opid).unwrap()),
+        AnchorMethod::OpReturn { output: 0 },
+        "Anchor without Tapret proof infers OP_RETURN"
+    );
+}
+
+// ============================================================================
+// Test 12: RBF Fee Bumping
+// ============================================================================
+
+/// Create a witness transaction builder spending a 20k sat seal UTXO
+fn test_witness_builder(seed: u8) -> (WitnessTxBuilder, Utxo) {
+    let secp = secp256k1::Secp256k1::new();
+    let secret_key = secp256k1::SecretKey::from_slice(&[5u8; 32]).unwrap();
+    let public_key = secp256k1::PublicKey::from_secret_key(&secp, &secret_key);
+    let change_key = InternalPk::from(public_key.x_only_public_key().0);
+
+    let seal = Utxo {
+        outpoint: Outpoint::new(test_txid(seed), Vout::from_u32(0)),
+        value: Sats::from(20_000u64),
+        script_pubkey: ScriptPubkey::p2tr_key_only(change_key),
+    };
+    let recipient =
+        bitcoin::Address::p2wsh(&bitcoin::ScriptBuf::new(), bitcoin::Network::Regtest).to_string();
+    let builder = WitnessTxBuilder::new(&recipient, bitcoin::Network::Regtest, [seed; 32])
+        .unwrap()
+        .spend_seal(seal.outpoint)
+        .utxos([seal.clone()])
+        .fee_rate(2)
+        .change_key(change_key);
+
+    (builder, seal)
+}
+
+#[test]
+fn test_bump_witness_fee() {
+    let (builder, seal) = test_witness_builder(1);
+
+    let mut tracker = BitcoinAnchorTracker::<TxoSeal>::new();
+    let opid = test_opid(1);
+
+    // No pending witness yet
+    assert!(tracker.bump_witness_fee(opid, 10).is_err());
+
+    let original = builder.clone().build().unwrap();
+    let original_txid = tracker.add_witness_tx(opid, builder, &original);
+    assert_eq!(
+        tracker.witness_status(original_txid),
+        WitnessStatus::Tentative
+    );
+
+    // Fee rate must increase
+    assert!(tracker.bump_witness_fee(opid, 2).is_err());
+
+    let replacement = tracker.bump_witness_fee(opid, 10).unwrap();
+    let replacement_tx: Tx = replacement.psbt.to_unsigned_tx().into();
+    let replacement_txid = replacement_tx.txid();
+    assert_ne!(replacement_txid, original_txid);
+    assert!(replacement.fee.sats() > original.fee.sats());
+    assert!(
+        replacement.fee.sats() >= original.fee.sats() + MIN_FEE_RATE * replacement.vsize,
+        "Absolute fee grows by at least the minimum relay fee"
+    );
+    assert_eq!(replacement_tx.inputs[0].prev_output, seal.outpoint);
+    assert_eq!(tracker.get_anchor(&opid), Some(&replacement.anchor));
+    assert_eq!(tracker.op_witness_ids(opid).len(), 2);
+
+    // Builder survives persistence, so bumping works after reload
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("rbf.json");
+    tracker.save(&db_path).unwrap();
+    let mut tracker = BitcoinAnchorTracker::<TxoSeal>::load_from_disk(&db_path).unwrap();
+
+    // Original gets mined instead of the replacement
+    tracker.confirm_witness(original_txid, NonZero::new(100).unwrap());
+    assert_eq!(
+        tracker.witness_status(replacement_txid),
+        WitnessStatus::Archived
+    );
+    assert_eq!(tracker.get_anchor(&opid), Some(&original.anchor));
+    assert_eq!(
+        tracker.get_anchor_method(&opid),
+        Some(AnchorMethod::Tapret { output: 0 })
+    );
+
+    // Nothing left to bump once mined
+    assert!(tracker.bump_witness_fee(opid, 20).is_err());
+}
+
+// ============================================================================
+// Test 13: Chain Sync (mining, reorgs, double-spends, replacements)
+// ============================================================================
+
+#[test]
+fn test_sync_witnesses_with_mock_chain() {
+    let mut chain = MockChain::new();
+    let mut tracker = BitcoinAnchorTracker::<TxoSeal>::new();
+
+    // Part A: Tentative → Mined → reorged out → Mined again
+    let (builder, _) = test_witness_builder(1);
+    let witness = builder.clone().build().unwrap();
+    let tx: Tx = witness.psbt.to_unsigned_tx().into();
+    let txid = tracker.add_witness_tx(test_opid(1), builder, &witness);
+
+    assert!(tracker.sync_witnesses(&chain).unwrap().is_empty());
+    chain.broadcast(tx.clone()).unwrap();
+    assert!(tracker.sync_witnesses(&chain).unwrap().is_empty());
+    assert_eq!(tracker.witness_status(txid), WitnessStatus::Tentative);
+
+    chain.mine_block();
+    assert_eq!(
+        tracker.sync_witnesses(&chain).unwrap(),
+        vec![SyncEvent::Mined { txid, height: 1 }]
+    );
+    assert_eq!(
+        tracker.witness_status(txid),
+        WitnessStatus::Mined(NonZero::new(1).unwrap())
+    );
+
+    chain.reorg(1).unwrap();
+    chain.evict(txid);
+    chain.mine_empty(2);
+    assert_eq!(
+        tracker.sync_witnesses(&chain).unwrap(),
+        vec![SyncEvent::Reorged { txid, height: 1 }]
+    );
+    assert_eq!(tracker.witness_status(txid), WitnessStatus::Tentative);
+    assert!(tracker.is_reorged(&test_opid(1)));
+    assert!(tracker.clear_reorged(&test_opid(1)));
+
+    chain.broadcast(tx).unwrap();
+    chain.mine_block();
+    assert_eq!(
+        tracker.sync_witnesses(&chain).unwrap(),
+        vec![SyncEvent::Mined { txid, height: 3 }]
+    );
+
+    // Part B: Inputs double-spent by an unrelated transaction
+    let (builder, _) = test_witness_builder(2);
+    let witness = builder.clone().build().unwrap();
+    let victim = tracker.add_witness_tx(test_opid(2), builder.clone(), &witness);
+
+    let thief = builder.fee_rate(50).build().unwrap();
+    let thief_tx: Tx = thief.psbt.to_unsigned_tx().into();
+    chain.mine_block_with(vec![thief_tx.clone()]);
+    assert_eq!(
+        tracker.sync_witnesses(&chain).unwrap(),
+        vec![SyncEvent::DoubleSpent {
+            txid: victim,
+            by: thief_tx.txid()
+        }]
+    );
+    assert_eq!(tracker.witness_status(victim), WitnessStatus::Archived);
+
+    // Part C: Fee-bumped replacement mined, original archived
+    let (builder, _) = test_witness_builder(3);
+    let witness = builder.clone().build().unwrap();
+    let original = tracker.add_witness_tx(test_opid(3), builder, &witness);
+    chain
+        .broadcast(witness.psbt.to_unsigned_tx().into())
+        .unwrap();
+
+    let replacement = tracker.bump_witness_fee(test_opid(3), 10).unwrap();
+    let replacement_txid = chain
+        .broadcast(replacement.psbt.to_unsigned_tx().into())
+        .unwrap();
+    let height = chain.mine_block();
+    assert_eq!(
+        tracker.sync_witnesses(&chain).unwrap(),
+        vec![
+            SyncEvent::Mined {
+                txid: replacement_txid,
+                height
+            },
+            SyncEvent::Replaced {
+                txid: original,
+                by: replacement_txid
+            },
+        ]
+    );
+    assert_eq!(tracker.witness_status(original), WitnessStatus::Archived);
+}
+
+// ============================================================================
+// Test 14: Reorg Detection
+// ============================================================================
+
+#[test]
+fn test_reorg_reincluding_witness_is_not_flagged() {
+    let mut chain = MockChain::new();
+    let mut tracker = BitcoinAnchorTracker::<TxoSeal>::new();
+
+    let (builder, _) = test_witness_builder(4);
+    let witness = builder.clone().build().unwrap();
+    let txid = tracker.add_witness_tx(test_opid(4), builder, &witness);
+    chain
+        .broadcast(witness.psbt.to_unsigned_tx().into())
+        .unwrap();
+    chain.mine_block();
+    tracker.sync_witnesses(&chain).unwrap();
+    let first_block = tracker.witness_block(txid).expect("Block hash recorded");
+
+    // Same height, another block: the witness stays mined and claims stay valid
+    chain.reorg(1).unwrap();
+    chain.mine_block();
+    assert!(tracker.sync_witnesses(&chain).unwrap().is_empty());
+    assert_eq!(
+        tracker.witness_status(txid),
+        WitnessStatus::Mined(NonZero::new(1).unwrap())
+    );
+    assert_ne!(tracker.witness_block(txid), Some(first_block));
+    assert_eq!(tracker.reorged_ops().count(), 0);
+
+    // Reorged out for good: operation flagged until the claim is handled
+    chain.reorg(1).unwrap();
+    chain.evict(txid);
+    chain.mine_empty(1);
+    assert_eq!(
+        tracker.sync_witnesses(&chain).unwrap(),
+        vec![SyncEvent::Reorged { txid, height: 1 }]
+    );
+    assert_eq!(
+        tracker.reorged_ops().collect::<Vec<_>>(),
+        vec![test_opid(4)]
+    );
+    assert_eq!(tracker.witness_block(txid), None);
+}
+
+// ============================================================================
+// Test 15: Fallible Access and Relation Repair
+// ============================================================================
+
+#[test]
+fn test_try_witness_access_and_idempotent_insert() {
+    let mut tracker = BitcoinAnchorTracker::<TxoSeal>::new();
+    let opid = test_opid(1);
+    let txid = test_txid(1);
+
+    assert!(tracker.try_pub_witness(txid).is_err());
+    assert!(tracker.try_cli_witness(txid).is_err());
+    assert!(tracker
+        .try_update_witness_status(txid, WitnessStatus::Archived)
+        .is_err());
+    // Pile variant no longer panics on unknown witnesses
+    tracker.update_witness_status(txid, WitnessStatus::Archived);
+
+    for _ in 0..3 {
+        tracker.add_witness(
+            opid,
+            txid,
+            &test_tx(),
+            &test_anchor(),
+            WitnessStatus::Tentative,
+        );
+    }
+    assert_eq!(tracker.op_witness_ids(opid).len(), 1);
+    assert_eq!(tracker.ops_by_witness_id(txid).len(), 1);
+    assert!(tracker.check_consistency().is_consistent());
+
+    assert!(tracker.try_pub_witness(txid).is_ok());
+    tracker
+        .try_update_witness_status(txid, WitnessStatus::Mined(NonZero::new(1).unwrap()))
+        .unwrap();
+    assert_eq!(
+        tracker.witness_status(txid),
+        WitnessStatus::Mined(NonZero::new(1).unwrap())
+    );
+}
+
+#[test]
+fn test_repair_persisted_duplicate_relations() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("tracker.json");
+
+    let mut tracker = BitcoinAnchorTracker::<TxoSeal>::new();
+    let opid = test_opid(1);
+    let txid = test_txid(1);
+    tracker.add_witness(
+        opid,
+        txid,
+        &test_tx(),
+        &test_anchor(),
+        WitnessStatus::Tentative,
+    );
+    tracker.save(&db_path).unwrap();
+
+    // Simulate a tracker written by an older version: duplicated relations,
+    // a one-sided relation and a relation to an unknown witness
+    let mut json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&db_path).unwrap()).unwrap();
+    let key = |opid: Opid| {
+        serde_json::to_value(opid)
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+    let wid = serde_json::to_value(txid).unwrap();
+    let unknown = serde_json::to_value(test_txid(9)).unwrap();
+    json["tracker"]["op_witnesses"][key(opid)] = serde_json::json!([wid, wid, unknown]);
+    json["tracker"]["op_witnesses"][key(test_opid(2))] = serde_json::json!([wid]);
+    std::fs::write(&db_path, json.to_string()).unwrap();
+
+    let mut loaded = BitcoinAnchorTracker::<TxoSeal>::load_from_disk(&db_path).unwrap();
+    let report = loaded.check_consistency();
+    assert_eq!(report.duplicate_relations, 1);
+    assert_eq!(report.one_sided_relations, 2);
+    assert_eq!(report.dangling_relations, 1);
+
+    assert_eq!(loaded.repair(), report);
+    assert!(loaded.check_consistency().is_consistent());
+    assert_eq!(loaded.op_witness_ids(opid).collect::<Vec<_>>(), vec![txid]);
+    assert_eq!(loaded.ops_by_witness_id(txid).len(), 2);
+    assert_eq!(loaded.op_witness_ids(test_opid(2)).len(), 1);
+}
+
+// ============================================================================
+// Test 16: Crash-Safe Persistence (atomic writes, backup, locking)
+// ============================================================================
+
+#[test]
+fn test_crash_safe_persistence() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("tracker.json");
+    let backup_path = temp_dir.path().join("tracker.json.bak");
+
+    let mut tracker = BitcoinAnchorTracker::<TxoSeal>::with_persistence(&db_path);
+    tracker.add_witness(
+        test_opid(1),
+        test_txid(1),
+        &test_tx(),
+        &test_anchor(),
+        WitnessStatus::Tentative,
+    );
+    tracker.try_commit_transaction().unwrap();
+    assert!(db_path.exists());
+    assert!(!temp_dir.path().join("tracker.json.tmp").exists());
+
+    // Second save rotates the previous file into the backup
+    tracker.add_witness(
+        test_opid(2),
+        test_txid(2),
+        &test_tx(),
+        &test_anchor(),
+        WitnessStatus::Tentative,
+    );
+    tracker.try_commit_transaction().unwrap();
+    assert!(backup_path.exists());
+
+    // Another tracker can read, but not write while the lock is held
+    let mut other = BitcoinAnchorTracker::<TxoSeal>::load_from_disk(&db_path).unwrap();
+    assert_eq!(other.witness_count(), 2);
+    assert!(matches!(
+        other.try_commit_transaction(),
+        Err(BitcoinAnchorError::Locked(_))
+    ));
+    other.commit_transaction();
+    assert!(matches!(
+        other.take_persist_error(),
+        Some(BitcoinAnchorError::Locked(_))
+    ));
+    assert!(other.take_persist_error().is_none());
+
+    // Lock released on drop
+    drop(tracker);
+    other.try_commit_transaction().unwrap();
+
+    // Corrupted main file: backup is loaded instead
+    drop(other);
+    std::fs::write(&db_path, "{ truncated").unwrap();
+    let recovered = BitcoinAnchorTracker::<TxoSeal>::load_from_disk(&db_path).unwrap();
+    assert_eq!(recovered.witness_count(), 2);
+}
+
+// ============================================================================
+// Test 17: Storage Backends (append-only log, in-memory)
+// ============================================================================
+
+#[test]
+fn test_log_store_appends_and_compacts() {
+    let temp_dir = TempDir::new().unwrap();
+    let log_path = temp_dir.path().join("tracker.log");
+    assert_eq!(StorageBackend::for_path(&log_path), StorageBackend::Log);
+
+    let conf = AnchorConfig {
+        persistence_path: Some(log_path.clone()),
+        encryption: None,
+    };
+    let mut tracker = <BitcoinAnchorTracker<TxoSeal> as Pile>::new(conf.clone()).unwrap();
+    for i in 1..=3 {
+        let mut seals = SmallOrdMap::new();
+        seals.insert(0u16, test_seal(test_txid(i), 0)).unwrap();
+        tracker.add_seals(test_opid(i), seals);
+        tracker.add_witness(
+            test_opid(i),
+            test_txid(i),
+            &test_tx(),
+            &test_anchor(),
+            WitnessStatus::Tentative,
+        );
+    }
+    tracker.add_anchor_with_method(
+        test_opid(1),
+        test_anchor(),
+        AnchorMethod::OpReturn { output: 1 },
+    );
+    tracker.try_commit_transaction().unwrap();
+    let full_len = std::fs::metadata(&log_path).unwrap().len();
+
+    // A status change appends only the changed witness record
+    tracker.update_witness_status(test_txid(2), WitnessStatus::Mined(NonZero::new(7).unwrap()));
+    tracker.try_commit_transaction().unwrap();
+    let appended = std::fs::metadata(&log_path).unwrap().len() - full_len;
+    assert!(appended > 0 && appended < full_len / 2);
+
+    // Nothing changed: nothing written
+    tracker.try_commit_transaction().unwrap();
+    assert_eq!(
+        std::fs::metadata(&log_path).unwrap().len(),
+        full_len + appended
+    );
+
+    // Uncommitted tail (crash mid-write) is ignored on load
+    drop(tracker);
+    let mut data = std::fs::read(&log_path).unwrap();
+    data.extend_from_slice(&[0x20, 0, 0, 0, 1, 2, 3]);
+    std::fs::write(&log_path, data).unwrap();
+
+    let mut loaded = BitcoinAnchorTracker::<TxoSeal>::load(conf).unwrap();
+    assert_eq!(loaded.operation_count(), 3);
+    assert_eq!(loaded.witness_count(), 3);
+    assert_eq!(
+        loaded.witness_status(test_txid(2)),
+        WitnessStatus::Mined(NonZero::new(7).unwrap())
+    );
+    assert_eq!(
+        loaded.get_anchor_method(&test_opid(1)),
+        Some(AnchorMethod::OpReturn { output: 1 })
+    );
+    let addr = CellAddr {
+        opid: test_opid(3),
+        pos: 0,
+    };
+    assert!(loaded.seal(addr).is_some());
+    assert_eq!(
+        loaded.ops_by_witness_id(test_txid(1)).collect::<Vec<_>>(),
+        vec![test_opid(1)]
+    );
+    assert!(loaded.check_consistency().is_consistent());
+    assert_eq!(
+        std::fs::metadata(&log_path).unwrap().len(),
+        full_len + appended
+    );
+
+    // The loaded tracker holds the log: a second one can't open it
+    assert!(matches!(
+        BitcoinAnchorTracker::<TxoSeal>::load_from_disk(&log_path),
+        Err(BitcoinAnchorError::Locked(_))
+    ));
+
+    // A failed write leaves a torn frame behind the open log; the next
+    // commit replaces it instead of appending after it
+    {
+        use std::io::Write;
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&log_path)
+            .unwrap();
+        file.write_all(&[0x40, 0, 0, 0, 9, 9, 9]).unwrap();
+    }
+    loaded.update_witness_status(test_txid(3), WitnessStatus::Mined(NonZero::new(9).unwrap()));
+    loaded.try_commit_transaction().unwrap();
+    drop(loaded);
+    let reloaded = BitcoinAnchorTracker::<TxoSeal>::load_from_disk(&log_path).unwrap();
+    assert_eq!(
+        reloaded.witness_status(test_txid(3)),
+        WitnessStatus::Mined(NonZero::new(9).unwrap())
+    );
+    assert_eq!(
+        reloaded.witness_status(test_txid(2)),
+        WitnessStatus::Mined(NonZero::new(7).unwrap())
+    );
+    drop(reloaded);
+
+    // Repeated updates past the threshold compact the log into a snapshot
+    let compact_path = temp_dir.path().join("compact.log");
+    let mut compacted = BitcoinAnchorTracker::<TxoSeal>::new()
+        .with_store(Box::new(LogStore::with_compaction(compact_path.clone(), 4)));
+    compacted.add_witness(
+        test_opid(1),
+        test_txid(1),
+        &test_tx(),
+        &test_anchor(),
+        WitnessStatus::Tentative,
+    );
+    compacted.try_commit_transaction().unwrap();
+    let snapshot_len = std::fs::metadata(&compact_path).unwrap().len();
+    for height in 1..=20 {
+        compacted.update_witness_status(
+            test_txid(1),
+            WitnessStatus::Mined(NonZero::new(height).unwrap()),
+        );
+        compacted.try_commit_transaction().unwrap();
+    }
+    assert!(std::fs::metadata(&compact_path).unwrap().len() < snapshot_len * 3);
+    drop(compacted);
+
+    let reloaded = BitcoinAnchorTracker::<TxoSeal>::load_from_disk(&compact_path).unwrap();
+    assert_eq!(
+        reloaded.witness_status(test_txid(1)),
+        WitnessStatus::Mined(NonZero::new(20).unwrap())
+    );
+    assert_eq!(reloaded.operation_count(), 1);
+}
+
+#[test]
+fn test_memory_store_round_trip() {
+    let conf = AnchorConfig {
+        persistence_path: Some(StorageBackend::MEMORY_PATH.into()),
+        encryption: None,
+    };
+    let mut tracker = <BitcoinAnchorTracker<TxoSeal> as Pile>::new(conf.clone()).unwrap();
+    assert_eq!(tracker.persistence_path(), None);
+
+    tracker.add_witness(
+        test_opid(1),
+        test_txid(1),
+        &test_tx(),
+        &test_anchor(),
+        WitnessStatus::Tentative,
+    );
+    tracker.add_anchor(test_opid(1), test_anchor());
+    tracker.try_commit_transaction().unwrap();
+    assert!(tracker.take_persist_error().is_none());
+
+    // Nothing reaches the disk; a fresh in-memory load starts empty
+    let fresh = BitcoinAnchorTracker::<TxoSeal>::load(conf).unwrap();
+    assert_eq!(fresh.witness_count(), 0);
+
+    // Committed records load back into an equal tracker
+    let mut store = MemoryStore::new();
+    assert!(AnchorStore::<TxoSeal>::load(&mut store).unwrap().is_none());
+    AnchorStore::<TxoSeal>::commit(&mut store, &tracker).unwrap();
+    assert_eq!(store.commits(), 1);
+    let restored = AnchorStore::<TxoSeal>::load(&mut store).unwrap().unwrap();
+    assert_eq!(restored.witness_count(), 1);
+    assert!(restored.has_anchor(&test_opid(1)));
+    assert_eq!(
+        restored.op_witness_ids(test_opid(1)).collect::<Vec<_>>(),
+        vec![test_txid(1)]
+    );
+}
+
+// ============================================================================
+// Test 18: Schema Versioning and Migrations
+// ============================================================================
+
+#[test]
+fn test_schema_versioning_and_migration() {
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("tracker.json");
+
+    let mut tracker = BitcoinAnchorTracker::<TxoSeal>::with_persistence(&db_path);
+    tracker.add_witness(
+        test_opid(1),
+        test_txid(1),
+        &test_tx(),
+        &test_anchor(),
+        WitnessStatus::Tentative,
+    );
+    tracker.try_commit_transaction().unwrap();
+    drop(tracker);
+
+    let report = check_file(&db_path).unwrap();
+    assert_eq!(report.version, TRACKER_SCHEMA_VERSION);
+    assert!(!report.needs_migration());
+
+    // Tracker written before versioning and the anchors map
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&db_path).unwrap()).unwrap();
+    let mut legacy = json["tracker"].clone();
+    for field in [
+        "anchors",
+        "anchor_methods",
+        "pending_witnesses",
+        "witness_blocks",
+        "reorged_ops",
+    ] {
+        legacy.as_object_mut().unwrap().remove(field);
+    }
+    let legacy = serde_json::to_string(&legacy).unwrap();
+    std::fs::write(&db_path, &legacy).unwrap();
+
+    // Dry run reports the pending migrations and leaves the file untouched
+    let report = check_file(&db_path).unwrap();
+    assert_eq!(report.version, 0);
+    assert_eq!(report.backend, StorageBackend::Json);
+    assert_eq!(report.migrations.len(), MIGRATIONS.len());
+    assert_eq!(std::fs::read_to_string(&db_path).unwrap(), legacy);
+
+    // Load migrates in memory; the next commit writes the current layout
+    let mut loaded = BitcoinAnchorTracker::<TxoSeal>::load_from_disk(&db_path).unwrap();
+    assert_eq!(loaded.witness_count(), 1);
+    assert!(!loaded.has_anchor(&test_opid(1)));
+    assert_eq!(std::fs::read_to_string(&db_path).unwrap(), legacy);
+    loaded.try_commit_transaction().unwrap();
+    assert!(!check_file(&db_path).unwrap().needs_migration());
+    drop(loaded);
+
+    // Files from a newer version are rejected instead of misread
+    let mut future: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&db_path).unwrap()).unwrap();
+    future["version"] = serde_json::json!(TRACKER_SCHEMA_VERSION + 1);
+    std::fs::write(&db_path, future.to_string()).unwrap();
+    assert!(matches!(
+        check_file(&db_path),
+        Err(BitcoinAnchorError::UnsupportedVersion(_))
+    ));
+    assert!(matches!(
+        BitcoinAnchorTracker::<TxoSeal>::load_from_disk(&db_path),
+        Err(BitcoinAnchorError::UnsupportedVersion(_))
+    ));
+
+    // Append-only logs report their header version
+    let log_path = temp_dir.path().join("tracker.log");
+    let mut logged = BitcoinAnchorTracker::<TxoSeal>::with_persistence(&log_path);
+    logged.try_commit_transaction().unwrap();
+    let report = check_file(&log_path).unwrap();
+    assert_eq!(report.backend, StorageBackend::Log);
+    assert_eq!(report.version, report.current);
+}
+
+// ============================================================================
+// Test 19: Outpoint Indexes and Wallet Queries
+// ============================================================================
+
+#[test]
+fn test_outpoint_indexes_and_queries() {
+    let (builder, utxo) = test_witness_builder(1);
+    let mut tracker = BitcoinAnchorTracker::<TxoSeal>::new();
+    let opid = test_opid(1);
+    let external = Outpoint::new(test_txid(7), Vout::from_u32(2));
+
+    // One seal on an output of the operation's own witness, one external
+    let mut seals = SmallOrdMap::new();
+    seals
+        .insert(
+            0u16,
+            WTxoSeal {
+                primary: WOutpoint::Wout(Vout::from_u32(1)),
+                secondary: TxoSealExt::Noise(Noise::strict_dumb()),
+            },
+        )
+        .unwrap();
+    seals.insert(1u16, test_seal(test_txid(7), 2)).unwrap();
+    tracker.add_seals(opid, seals);
+    assert_eq!(tracker.ops_by_outpoint(external), vec![opid]);
+    assert_eq!(tracker.unanchored_ops(), vec![opid]);
+
+    let original = builder.clone().build().unwrap();
+    let original_txid = tracker.add_witness_tx(opid, builder, &original);
+    assert!(tracker.unanchored_ops().is_empty());
+    assert_eq!(
+        tracker.ops_by_outpoint(Outpoint::new(original_txid, Vout::from_u32(1))),
+        vec![opid]
+    );
+    let on_witness = tracker.seals_by_txid(original_txid);
+    assert_eq!(on_witness.len(), 1);
+    assert_eq!(on_witness[0].0, CellAddr { opid, pos: 0 });
+    assert_eq!(
+        tracker.witnesses_spending(utxo.outpoint),
+        vec![original_txid]
+    );
+
+    // A fee bump spends the same UTXO and resolves the seal on its own output
+    let replacement = tracker.bump_witness_fee(opid, 10).unwrap();
+    let replacement_txid = Tx::from(replacement.psbt.to_unsigned_tx()).txid();
+    let mut spenders = tracker.witnesses_spending(utxo.outpoint);
+    spenders.sort();
+    let mut expected = vec![original_txid, replacement_txid];
+    expected.sort();
+    assert_eq!(spenders, expected);
+    assert_eq!(tracker.seals_by_txid(replacement_txid).len(), 1);
+    assert_eq!(tracker.pending_witnesses().len(), 2);
+
+    // Status and depth filters
+    tracker.confirm_witness(replacement_txid, NonZero::new(100).unwrap());
+    assert!(tracker.pending_witnesses().is_empty());
+    let mined = WitnessFilter::new().status(StatusKind::Mined);
+    assert_eq!(
+        tracker.filter_witnesses(&mined.clone().confirmations(1..=6, 105)),
+        vec![replacement_txid]
+    );
+    assert!(tracker
+        .filter_witnesses(&mined.confirmations(1..=6, 110))
+        .is_empty());
+    assert_eq!(
+        tracker.filter_witnesses(&WitnessFilter::new().status(StatusKind::Archived)),
+        vec![original_txid]
+    );
+
+    // Indexes persist, and are rebuilt for files stored without them
+    let temp_dir = TempDir::new().unwrap();
+    let db_path = temp_dir.path().join("indexed.json");
+    tracker.save(&db_path).unwrap();
+    let loaded = BitcoinAnchorTracker::<TxoSeal>::load_from_disk(&db_path).unwrap();
+    assert_eq!(loaded.ops_by_outpoint(external), vec![opid]);
+    assert_eq!(loaded.witnesses_spending(utxo.outpoint).len(), 2);
+    assert!(loaded.check_consistency().is_consistent());
+    drop(loaded);
+
+    let mut json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&db_path).unwrap()).unwrap();
+    json["tracker"]["outpoint_seals"] = serde_json::json!([]);
+    json["tracker"]
+        .as_object_mut()
+        .unwrap()
+        .remove("outpoint_spenders");
+    std::fs::write(&db_path, json.to_string()).unwrap();
+    let loaded = BitcoinAnchorTracker::<TxoSeal>::load_from_disk(&db_path).unwrap();
+    assert_eq!(loaded.ops_by_outpoint(external), vec![opid]);
+    assert_eq!(loaded.witnesses_spending(utxo.outpoint).len(), 2);
+
+    // Record-based stores keep them too
+    let mut store = MemoryStore::new();
+    AnchorStore::<TxoSeal>::commit(&mut store, &tracker).unwrap();
+    let restored = AnchorStore::<TxoSeal>::load(&mut store).unwrap().unwrap();
+    assert_eq!(restored.seals_by_txid(original_txid).len(), 1);
+    assert_eq!(restored.ops_by_outpoint(external), vec![opid]);
+    assert!(restored.check_consistency().is_consistent());
+}
+
+// ============================================================================
+// Test 20: Pruning and Cold Storage
+// ============================================================================
+
+#[test]
+fn test_prune_and_restore() {
+    let temp_dir = TempDir::new().unwrap();
+    let archive_path = temp_dir.path().join("archive.log");
+    let mut tracker = BitcoinAnchorTracker::<TxoSeal>::new();
+    let seal_on = |seed: u8| {
+        let mut seals = SmallOrdMap::new();
+        seals.insert(0u16, test_seal(test_txid(seed), 0)).unwrap();
+        seals
+    };
+
+    // Op 1: RBF-replaced witness, archived once the replacement is mined
+    let (builder, _) = test_witness_builder(1);
+    let op1 = test_opid(1);
+    tracker.add_seals(op1, seal_on(8));
+    let original = builder.clone().build().unwrap();
+    let original_txid = tracker.add_witness_tx(op1, builder, &original);
+    let replacement = tracker.bump_witness_fee(op1, 10).unwrap();
+    let replacement_txid = Tx::from(replacement.psbt.to_unsigned_tx()).txid();
+    tracker.confirm_witness(replacement_txid, NonZero::new(100).unwrap());
+
+    // Op 2: mined, its seal later spent by op 3's mined witness
+    let op2 = test_opid(2);
+    tracker.add_seals(op2, seal_on(5));
+    tracker.add_witness(
+        op2,
+        test_txid(2),
+        &test_tx(),
+        &test_anchor(),
+        WitnessStatus::Mined(NonZero::new(50).unwrap()),
+    );
+    tracker.add_anchor(op2, test_anchor());
+    let (builder, _) = test_witness_builder(5);
+    let op3 = test_opid(3);
+    let spend = builder.clone().build().unwrap();
+    let spend_txid = tracker.add_witness_tx(op3, builder, &spend);
+    tracker.confirm_witness(spend_txid, NonZero::new(120).unwrap());
+
+    // Op 4: its only witness was double-spent
+    let op4 = test_opid(4);
+    tracker.add_witness(
+        op4,
+        test_txid(4),
+        &test_tx(),
+        &test_anchor(),
+        WitnessStatus::Archived,
+    );
+    tracker.add_anchor(op4, test_anchor());
+
+    // Nothing is old enough yet
+    let month = std::time::Duration::from_secs(30 * 86_400);
+    let policy = PrunePolicy::archived_for(month).archive_to(&archive_path);
+    assert!(tracker.prune(&policy).unwrap().is_empty());
+    assert!(!archive_path.exists());
+
+    let later = std::time::SystemTime::now() + month * 2;
+    let report = tracker.prune(&policy.superseded_ops().at(later)).unwrap();
+    assert_eq!(report.witnesses, 3);
+    assert_eq!(report.operations, 1);
+    assert_eq!(report.anchors, 2);
+    assert_eq!(report.archive.as_deref(), Some(archive_path.as_path()));
+
+    assert!(!tracker.has_witness(original_txid));
+    assert!(tracker.has_witness(replacement_txid));
+    assert!(!tracker.has_witness(test_txid(2)));
+    assert!(!tracker.has_witness(test_txid(4)));
+    assert!(tracker.seals(op2, 0).is_empty());
+    assert!(!tracker.has_anchor(&op2));
+    assert!(!tracker.has_anchor(&op4));
+    assert!(tracker.has_anchor(&op1));
+    assert!(tracker.has_anchor(&op3));
+    assert_eq!(
+        tracker.op_witness_ids(op1).collect::<Vec<_>>(),
+        vec![replacement_txid]
+    );
+    assert!(tracker.check_consistency().is_consistent());
+
+    // Pruned records come back from cold storage for disputes
+    let restored = tracker.restore_pruned(&archive_path).unwrap();
+    assert_eq!(restored.witnesses, 3);
+    assert_eq!(restored.operations, 1);
+    assert_eq!(restored.anchors, 2);
+    assert!(tracker.has_witness(original_txid));
+    assert_eq!(tracker.op_witness_ids(op1).len(), 2);
+    assert!(tracker.has_anchor(&op2));
+    assert_eq!(
+        tracker.ops_by_outpoint(Outpoint::new(test_txid(5), Vout::from_u32(0))),
+        vec![op2]
+    );
+    assert!(tracker.check_consistency().is_consistent());
+}
+
+// ============================================================================
+// Test 21: Export, Import and Merge
+// ============================================================================
+
+#[test]
+fn test_export_and_merge() {
+    let temp_dir = TempDir::new().unwrap();
+    let bundle_path = temp_dir.path().join("bundle.json");
+    let seal_on = |seed: u8| {
+        let mut seals = SmallOrdMap::new();
+        seals.insert(0u16, test_seal(test_txid(seed), 0)).unwrap();
+        seals
+    };
+
+    // Device A knows ops 1 and 2, both tentative
+    let mut device_a = BitcoinAnchorTracker::<TxoSeal>::new();
+    for n in 1..=2 {
+        device_a.add_seals(test_opid(n), seal_on(n));
+        device_a.add_witness(
+            test_opid(n),
+            test_txid(n),
+            &test_tx(),
+            &test_anchor(),
+            WitnessStatus::Tentative,
+        );
+        device_a.add_anchor(test_opid(n), test_anchor());
+    }
+
+    // Bundle holds op 1 only; unknown ops are skipped
+    let bundle = device_a.export_ops([test_opid(1), test_opid(9)]);
+    assert_eq!(bundle.operation_count(), 1);
+    assert_eq!(bundle.witness_count(), 1);
+    assert!(bundle.has_anchor(&test_opid(1)));
+    assert!(!bundle.has_anchor(&test_opid(2)));
+    assert!(bundle.check_consistency().is_consistent());
+    bundle.save(&bundle_path).unwrap();
+
+    // Device B saw op 1's witness mined and has a different seal for op 2
+    let mut device_b = BitcoinAnchorTracker::<TxoSeal>::new();
+    let report = device_b.import_bundle(&bundle_path).unwrap();
+    assert_eq!(report.operations_added, 1);
+    assert_eq!(report.witnesses_added, 1);
+    assert_eq!(report.anchors_added, 1);
+    assert!(report.is_clean());
+    device_b.confirm_witness(test_txid(1), NonZero::new(100).unwrap());
+    device_b.add_seals(test_opid(2), seal_on(7));
+
+    // Status advances, the seal conflict is reported and ours kept
+    let report = device_a.merge(device_b.export_ops([test_opid(1), test_opid(2)]));
+    assert_eq!(report.witnesses_advanced, 1);
+    assert_eq!(report.operations_added, 0);
+    assert_eq!(report.seal_conflicts, vec![test_opid(2)]);
+    assert!(!report.is_clean());
+    assert_eq!(
+        device_a.witness_status(test_txid(1)),
+        WitnessStatus::Mined(NonZero::new(100).unwrap())
+    );
+    assert_eq!(
+        device_a.seals(test_opid(2), 0).get(&0),
+        Some(&test_seal(test_txid(2), 0))
+    );
+
+    // A less advanced status never downgrades
+    let mut stale = device_a.export_ops([test_opid(1)]);
+    stale.update_witness_status(test_txid(1), WitnessStatus::Tentative);
+    let report = device_a.merge(stale);
+    assert_eq!(report.witnesses_advanced, 0);
+    assert!(report.is_clean());
+    assert_eq!(
+        device_a.witness_status(test_txid(1)),
+        WitnessStatus::Mined(NonZero::new(100).unwrap())
+    );
+
+    // Merging is idempotent
+    let before = device_a.operation_count();
+    let report = device_a.merge(device_a.export_ops([test_opid(1), test_opid(2)]));
+    assert_eq!(report, Default::default());
+    assert_eq!(device_a.operation_count(), before);
+    assert!(device_a.check_consistency().is_consistent());
+}
+
+// ============================================================================
+// Test 22: Encryption at Rest
+// ============================================================================
+
+#[test]
+fn test_encrypted_persistence_and_rekey() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("tracker.json");
+    let key = EncryptionKey::from_bytes([7u8; 32]);
+    let opid = test_opid(1);
+    let txid = test_txid(1);
+
+    let mut tracker =
+        BitcoinAnchorTracker::<TxoSeal>::with_encrypted_persistence(&path, key.clone()).unwrap();
+    let mut seals = SmallOrdMap::new();
+    seals.insert(0u16, test_seal(txid, 0)).unwrap();
+    tracker.add_seals(opid, seals);
+    tracker.add_witness(
+        opid,
+        txid,
+        &test_tx(),
+        &test_anchor(),
+        WitnessStatus::Tentative,
+    );
+    tracker.try_commit_transaction().unwrap();
+    tracker.add_anchor(opid, test_anchor());
+    tracker.try_commit_transaction().unwrap();
+
+    // Neither the file nor its backup leak tracker data
+    for file in [path.clone(), temp_dir.path().join("tracker.json.bak")] {
+        let data = std::fs::read(&file).unwrap();
+        assert!(data.starts_with(b"F1RGBENC"));
+        assert!(!String::from_utf8_lossy(&data).contains("witnesses"));
+    }
+    assert!(matches!(
+        check_file(&path),
+        Err(BitcoinAnchorError::Encryption(_))
+    ));
+
+    // Loading needs the right key, and never falls back to the backup
+    drop(tracker);
+    assert!(matches!(
+        BitcoinAnchorTracker::<TxoSeal>::load_from_disk(&path),
+        Err(BitcoinAnchorError::Encryption(_))
+    ));
+    assert!(matches!(
+        BitcoinAnchorTracker::<TxoSeal>::load_encrypted(
+            &path,
+            EncryptionKey::from_bytes([8u8; 32])
+        ),
+        Err(BitcoinAnchorError::Encryption(_))
+    ));
+    let mut tracker = BitcoinAnchorTracker::<TxoSeal>::load_encrypted(&path, key.clone()).unwrap();
+    assert!(tracker.has_anchor(&opid));
+
+    // Switch to a passphrase; the old key no longer opens file or backup
+    let passphrase = EncryptionKey::passphrase("correct horse battery staple");
+    tracker.rekey(Some(passphrase.clone())).unwrap();
+    tracker.try_commit_transaction().unwrap();
+    drop(tracker);
+    assert!(BitcoinAnchorTracker::<TxoSeal>::load_encrypted(&path, key).is_err());
+    std::fs::remove_file(&path).unwrap();
+    let from_backup =
+        BitcoinAnchorTracker::<TxoSeal>::load_encrypted(&path, passphrase.clone()).unwrap();
+    assert!(from_backup.has_anchor(&opid));
+    from_backup.save_encrypted(&path, &passphrase).unwrap();
+    drop(from_backup);
+
+    // Decrypting offline leaves a plain JSON tracker
+    rekey_file(&path, Some(&passphrase), None).unwrap();
+    assert!(check_file(&path).is_ok());
+    let plain = BitcoinAnchorTracker::<TxoSeal>::load_from_disk(&path).unwrap();
+    assert!(plain.has_witness(txid));
+    drop(plain);
+
+    // Plaintext files are rejected with a key until encrypted explicitly
+    let new_key = EncryptionKey::from_bytes([9u8; 32]);
+    assert!(matches!(
+        BitcoinAnchorTracker::<TxoSeal>::load_encrypted(&path, new_key.clone()),
+        Err(BitcoinAnchorError::Encryption(_))
+    ));
+    rekey_file(&path, None, Some(&new_key)).unwrap();
+    assert!(std::fs::read(&path).unwrap().starts_with(b"F1RGBENC"));
+    let tracker = BitcoinAnchorTracker::<TxoSeal>::load_encrypted(&path, new_key).unwrap();
+    assert!(tracker.has_witness(txid));
+    drop(tracker);
+
+    // Logs can't be encrypted
+    assert!(matches!(
+        BitcoinAnchorTracker::<TxoSeal>::with_encrypted_persistence(
+            temp_dir.path().join("tracker.log"),
+            EncryptionKey::from_bytes([7u8; 32]),
+        ),
+        Err(BitcoinAnchorError::InvalidConfiguration(_))
+    ));
+
+    // Executor snapshots use the same format
+    let snapshot_path = temp_dir.path().join("executor.json");
+    let snapshot = ExecutorSnapshot {
+        derivation_index: 3,
+        auto_derive: true,
+        contracts: vec![(
+            hex::encode([1u8; 32]),
+            ContractMetadata {
+                registry_uri: "rho:id:example".to_string(),
+                methods: vec!["transfer".to_string()],
+                rholang_source: "new x in { Nil }".to_string(),
+            },
+        )],
+    };
+    snapshot.save(&snapshot_path, Some(&passphrase)).unwrap();
+    assert!(ExecutorSnapshot::load(&snapshot_path, None).is_err());
+    let loaded = ExecutorSnapshot::load(&snapshot_path, Some(&passphrase)).unwrap();
+    assert_eq!(loaded.derivation_index, 3);
+    assert_eq!(loaded.contracts[0].1.registry_uri, "rho:id:example");
+}
+
+#[test]
+fn test_pile_encrypted_round_trip() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("tracker.json");
+    let key = EncryptionKey::from_bytes([5u8; 32]);
+    let conf = AnchorConfig {
+        persistence_path: Some(path.clone()),
+        encryption: Some(key.clone()),
+    };
+    let opid = test_opid(1);
+    let txid = test_txid(1);
+
+    let mut tracker = <BitcoinAnchorTracker<TxoSeal> as Pile>::new(conf.clone()).unwrap();
+    tracker.add_witness(
+        opid,
+        txid,
+        &test_tx(),
+        &test_anchor(),
+        WitnessStatus::Tentative,
+    );
+    tracker.add_anchor(opid, test_anchor());
+    tracker.try_commit_transaction().unwrap();
+    drop(tracker);
+    assert!(std::fs::read(&path).unwrap().starts_with(b"F1RGBENC"));
+
+    // Pile::load opens it with the configured key only
+    let loaded = <BitcoinAnchorTracker<TxoSeal> as Pile>::load(conf.clone()).unwrap();
+    assert!(loaded.has_witness(txid));
+    assert!(loaded.has_anchor(&opid));
+    drop(loaded);
+    let plain = AnchorConfig {
+        persistence_path: Some(path.clone()),
+        encryption: None,
+    };
+    assert!(matches!(
+        <BitcoinAnchorTracker<TxoSeal> as Pile>::load(plain),
+        Err(BitcoinAnchorError::Encryption(_))
+    ));
+    let wrong = AnchorConfig {
+        persistence_path: Some(path),
+        encryption: Some(EncryptionKey::from_bytes([6u8; 32])),
+    };
+    assert!(matches!(
+        <BitcoinAnchorTracker<TxoSeal> as Pile>::load(wrong),
+        Err(BitcoinAnchorError::Encryption(_))
+    ));
+}
+
+// ============================================================================
+// Test 23: Discarding an Operation
+// ============================================================================
+
+#[test]
+fn test_discard_op() {
+    let mut tracker = BitcoinAnchorTracker::<TxoSeal>::new();
+
+    // Ops 1 and 2 share a witness
+    for n in 1..=2 {
+        let mut seals = SmallOrdMap::new();
+        seals.insert(0u16, test_seal(test_txid(n), 0)).unwrap();
+        tracker.add_seals(test_opid(n), seals);
+        tracker.add_witness(
+            test_opid(n),
+            test_txid(1),
+            &test_tx(),
+            &test_anchor(),
+            WitnessStatus::Tentative,
+        );
+        tracker.add_anchor(test_opid(n), test_anchor());
+    }
+
+    // Op 3 has its own witness transaction and fee-bump builder
+    let (builder, _) = test_witness_builder(3);
+    let witness = builder.clone().build().unwrap();
+    let txid = tracker.add_witness_tx(test_opid(3), builder, &witness);
+    assert_eq!(tracker.pending_witnesses().len(), 1);
+
+    assert!(tracker.discard_op(test_opid(3)));
+    assert!(!tracker.has_witness(txid));
+    assert!(!tracker.has_anchor(&test_opid(3)));
+    assert!(tracker.pending_witnesses().is_empty());
+    assert!(tracker.bump_witness_fee(test_opid(3), 10).is_err());
+
+    // A shared witness stays with the remaining operation
+    assert!(tracker.discard_op(test_opid(1)));
+    assert!(tracker.seals(test_opid(1), 0).is_empty());
+    assert!(tracker.has_witness(test_txid(1)));
+    assert_eq!(
+        tracker.ops_by_witness_id(test_txid(1)).collect::<Vec<_>>(),
+        vec![test_opid(2)]
+    );
+    assert!(tracker
+        .ops_by_outpoint(Outpoint::new(test_txid(1), Vout::from_u32(0)))
+        .is_empty());
+    assert!(tracker.check_consistency().is_consistent());
+
+    // Unknown operations are left alone
+    assert!(!tracker.discard_op(test_opid(9)));
+    assert_eq!(tracker.operation_count(), 1);
+}
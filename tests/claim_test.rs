@@ -0,0 +1,407 @@
+//! ClaimManager Tests
+//!
+//! Recording and persistence of pending claims run offline. The claim flow
+//! (send, confirm the witness, accept, claim, freeze and revert) needs a live
+//! F1r3node instance.
+//!
+//! Requirements (live tests):
+//! - Running f1r3node instance
+//! - FIREFLY_* environment variables set
+//!
+//! Run with: cargo test --test claim_test -- --nocapture
+
+use amplify::ByteArray;
+use bp::{Outpoint, Txid};
+use f1r3fly_rgb::{
+    AcceptedConsignment, ClaimManager, ClaimStatus, EncryptionKey, F1r3flyRgbError, Opid, SealKey,
+    WitnessMapping,
+};
+use hypersonic::ContractId;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use tempfile::TempDir;
+
+/// Load environment variables from .env file
+fn load_env() {
+    use std::path::PathBuf;
+
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push(".env");
+
+    dotenv::from_path(&path).ok();
+}
+
+/// Generate a unique derivation index offset from test name
+fn test_derivation_offset(test_name: &str) -> u32 {
+    let mut hasher = DefaultHasher::new();
+    test_name.hash(&mut hasher);
+    (hasher.finish() % (u32::MAX as u64)) as u32
+}
+
+/// Taproot address paying `key` as output key, with `key` negated if needed
+/// so it signs for the invoice's recipient key (see `get_recipient_pubkey`)
+fn taproot_recipient(key: secp256k1::SecretKey) -> (secp256k1::SecretKey, bitcoin::Address) {
+    let secp = secp256k1::Secp256k1::new();
+    let (x_only, parity) = secp256k1::PublicKey::from_secret_key(&secp, &key).x_only_public_key();
+    let key = match parity {
+        secp256k1::Parity::Even => key,
+        secp256k1::Parity::Odd => key.negate(),
+    };
+    let mut script = vec![0x51, 0x20];
+    script.extend_from_slice(&x_only.serialize());
+    let address = bitcoin::Address::from_script(
+        bitcoin::Script::from_bytes(&script),
+        bitcoin::Network::Regtest,
+    )
+    .expect("Valid taproot script");
+    (key, address)
+}
+
+/// Accepted transfer whose balance still sits at its witness identifier
+fn accepted_transfer(seed: u8) -> AcceptedConsignment {
+    let txid = Txid::from_byte_array([seed; 32]);
+    let address = format!("bcrt1qexample{}", seed);
+    let spent = Outpoint::new(Txid::from_byte_array([seed | 0x80; 32]), 0u32);
+    let witness_id = SealKey::witness(spent, &address, 1);
+
+    AcceptedConsignment {
+        contract_id: ContractId::from([1u8; 32]),
+        opid: Opid::from([seed; 32]),
+        new_contract: false,
+        is_genesis: false,
+        witness_id: Some(txid),
+        seals: vec![Outpoint::new(txid, 1u32)],
+        seal_id: Some(witness_id),
+        witness_mapping: Some(WitnessMapping {
+            witness_id,
+            recipient_address: address,
+            expected_vout: 1,
+        }),
+        amount: 500,
+    }
+}
+
+// ============================================================================
+// Test 1: Recording and Persisting Claims
+// ============================================================================
+
+#[test]
+fn test_record_and_persist_claims() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("claims.json");
+
+    let mut claims = ClaimManager::with_persistence(&path, None).unwrap();
+    assert!(claims.record(&accepted_transfer(2)).unwrap());
+    assert!(path.exists());
+
+    // Recorded once
+    assert!(!claims.record(&accepted_transfer(2)).unwrap());
+
+    // Genesis and already claimed transfers have nothing to claim
+    let mut genesis = accepted_transfer(3);
+    genesis.is_genesis = true;
+    genesis.witness_id = None;
+    genesis.witness_mapping = None;
+    assert!(!claims.record(&genesis).unwrap());
+
+    let mut claimed = accepted_transfer(4);
+    claimed.seal_id = Some(SealKey::from(Outpoint::new(
+        Txid::from_byte_array([4u8; 32]),
+        1u32,
+    )));
+    assert!(!claims.record(&claimed).unwrap());
+
+    let claim = &claims.claims()[0];
+    assert_eq!(claim.status, ClaimStatus::Pending);
+    assert_eq!(claim.vout, 1);
+    assert_eq!(claim.amount, 500);
+    assert_eq!(
+        claim.real_utxo(),
+        SealKey::from(Outpoint::new(Txid::from_byte_array([2u8; 32]), 1u32))
+    );
+    assert_eq!(Some(claim.witness_id), accepted_transfer(2).seal_id);
+
+    // Reloaded from disk
+    let reloaded = ClaimManager::with_persistence(&path, None).unwrap();
+    assert_eq!(reloaded.claims(), claims.claims());
+    assert_eq!(reloaded.pending().count(), 1);
+
+    // Encrypted claim files need their key
+    let key = EncryptionKey::from_bytes([9u8; 32]);
+    let encrypted_path = temp_dir.path().join("claims.enc");
+    let mut encrypted = ClaimManager::with_persistence(&encrypted_path, Some(key.clone())).unwrap();
+    encrypted.record(&accepted_transfer(5)).unwrap();
+    assert!(matches!(
+        ClaimManager::with_persistence(&encrypted_path, None),
+        Err(F1r3flyRgbError::PersistenceFailed(_))
+    ));
+    let reloaded = ClaimManager::with_persistence(&encrypted_path, Some(key.clone())).unwrap();
+    assert_eq!(reloaded.claims().len(), 1);
+
+    // A plaintext file isn't accepted in place of an encrypted one
+    assert!(matches!(
+        ClaimManager::with_persistence(&path, Some(key)),
+        Err(F1r3flyRgbError::PersistenceFailed(_))
+    ));
+}
+
+// ============================================================================
+// Test 2: Claim After Witness Confirmation
+// ============================================================================
+
+#[tokio::test]
+async fn test_claim_after_confirmation() {
+    use bp::{InternalPk, Sats};
+    use bpstd::ScriptPubkey;
+    use f1r3fly_rgb::{
+        generate_freeze_claim_signature, generate_invoice, generate_issue_signature,
+        generate_nonce, parse_invoice, ClaimEvent, ClaimRecord, F1r3flyExecutor,
+        F1r3flyRgbContract, F1r3flyRgbContracts, MockChain, StrictVal, Tx, Utxo, WitnessTxBuilder,
+        DEFAULT_CLAIM_CONFIRMATIONS,
+    };
+
+    load_env();
+
+    let temp_dir = TempDir::new().unwrap();
+    let claims_path = temp_dir.path().join("claims.json");
+
+    // Alice issues 5000 to her seal
+    let mut executor = F1r3flyExecutor::new().expect("Failed to create executor");
+    executor.set_derivation_index(test_derivation_offset("test_claim_after_confirmation"));
+    executor.set_auto_derive(false);
+    let mut alice = F1r3flyRgbContract::issue(executor, "CLAIM", "Claim Test Token", 10_000, 0)
+        .await
+        .expect("Failed to issue asset");
+    let contract_id = alice.contract_id();
+
+    let alice_key = alice.executor().get_child_key().unwrap();
+    let secp = secp256k1::Secp256k1::new();
+    let alice_public_key = secp256k1::PublicKey::from_secret_key(&secp, &alice_key);
+    let alice_seal = bp::seals::TxoSeal {
+        primary: Outpoint::new(Txid::from_byte_array([0x21u8; 32]), 0u32),
+        secondary: bp::seals::TxoSealExt::Noise(strict_types::StrictDumb::strict_dumb()),
+    };
+    let alice_seal_str = F1r3flyRgbContract::serialize_seal(&alice_seal);
+
+    let nonce = generate_nonce();
+    let signature = generate_issue_signature(&alice_seal_str, 5000, nonce, &alice_key).unwrap();
+    let alice_pubkey_hex = hex::encode(alice_public_key.serialize_uncompressed());
+    alice
+        .executor_mut()
+        .call_method(
+            contract_id,
+            "issue",
+            &[
+                ("recipient", StrictVal::from(alice_seal_str.as_str())),
+                ("amount", StrictVal::from(5000u64)),
+                (
+                    "recipientPubKey",
+                    StrictVal::from(alice_pubkey_hex.as_str()),
+                ),
+                ("nonce", StrictVal::from(nonce)),
+                ("signatureHex", StrictVal::from(signature.as_str())),
+            ],
+        )
+        .await
+        .expect("Issue should succeed");
+
+    // Alice sends 700 to Bob's invoice
+    let (bob_key, bob_address) =
+        taproot_recipient(secp256k1::SecretKey::from_slice(&[0x31u8; 32]).unwrap());
+    let generated = generate_invoice(
+        contract_id,
+        700,
+        bob_address.clone(),
+        0,
+        rgb::Consensus::Bitcoin,
+        true,
+    )
+    .unwrap();
+    let invoice = parse_invoice(&generated.invoice.to_string()).unwrap();
+
+    let change_key = InternalPk::from(alice_public_key.x_only_public_key().0);
+    let builder = WitnessTxBuilder::new(
+        &bob_address.to_string(),
+        bitcoin::Network::Regtest,
+        [0u8; 32],
+    )
+    .unwrap()
+    .utxos([Utxo {
+        outpoint: alice_seal.primary,
+        value: Sats::from(20_000u64),
+        script_pubkey: ScriptPubkey::p2tr_key_only(change_key),
+    }])
+    .change_key(change_key);
+    let sent = alice
+        .send(&invoice, &alice_seal, &alice_key, builder)
+        .await
+        .expect("Send should succeed");
+
+    // Bob accepts, checks the invoice and records the claim
+    let mut bob = F1r3flyRgbContracts::new(F1r3flyExecutor::new().unwrap());
+    let accepted = bob
+        .accept_consignment(&sent.consignment.to_bytes().unwrap())
+        .await
+        .expect("Accept failed");
+    assert_eq!(accepted.amount, 700);
+    sent.consignment
+        .validate_against_invoice(&generated, bob.executor())
+        .await
+        .expect("Unclaimed transfer should pay the invoice");
+    let mut claims = ClaimManager::with_persistence(&claims_path, None).unwrap();
+    assert!(claims.record(&accepted).unwrap());
+
+    // Nothing happens while the witness is unconfirmed
+    let mut chain = MockChain::new();
+    let witness_tx: Tx = sent.psbt.to_unsigned_tx().into();
+    chain.broadcast(witness_tx).unwrap();
+    let events = claims.process(&chain, &mut bob, &bob_key).await.unwrap();
+    assert!(events.is_empty());
+    assert_eq!(claims.pending().count(), 1);
+
+    // Snapshot before claiming, to replay the claim later
+    let snapshot_path = temp_dir.path().join("claims-before.json");
+    std::fs::copy(&claims_path, &snapshot_path).unwrap();
+
+    // One confirmation is not enough by default
+    chain.mine_block();
+    let events = claims.process(&chain, &mut bob, &bob_key).await.unwrap();
+    assert!(events.is_empty());
+
+    // Witness reorged out and mined again: not claimed while marked reorged
+    let tracker = bob.get_mut(&contract_id).unwrap().tracker_mut();
+    tracker.sync_witnesses(&chain).unwrap();
+    chain.reorg(1).unwrap();
+    tracker.sync_witnesses(&chain).unwrap();
+    assert!(tracker.is_reorged(&accepted.opid));
+    chain.mine_block();
+    chain.mine_empty(DEFAULT_CLAIM_CONFIRMATIONS as usize - 1);
+    let events = claims.process(&chain, &mut bob, &bob_key).await.unwrap();
+    assert!(events.is_empty());
+    assert_eq!(claims.pending().count(), 1);
+
+    let tracker = bob.get_mut(&contract_id).unwrap().tracker_mut();
+    assert!(tracker.clear_reorged(&accepted.opid));
+    let events = claims.process(&chain, &mut bob, &bob_key).await.unwrap();
+    let real_utxo = SealKey::from(Outpoint::new(sent.txid, 1u32));
+    assert_eq!(
+        events,
+        vec![ClaimEvent::Claimed {
+            witness_id: accepted.seal_id.unwrap(),
+            real_utxo,
+            amount: 700,
+        }]
+    );
+    assert_eq!(claims.pending().count(), 0);
+
+    // Claimed state was persisted
+    let reloaded = ClaimManager::with_persistence(&claims_path, None).unwrap();
+    assert!(matches!(
+        reloaded.claims()[0].status,
+        ClaimStatus::Claimed { amount: 700, .. }
+    ));
+
+    // Accepting after the claim reads the balance from the real UTXO
+    let reaccepted = bob
+        .accept_consignment(&sent.consignment.to_bytes().unwrap())
+        .await
+        .expect("Accept after claim failed");
+    assert_eq!(reaccepted.seal_id, Some(real_utxo));
+    assert_eq!(reaccepted.amount, 700);
+
+    // The real UTXO receives more from elsewhere: the transferred amount is
+    // still the claimed one
+    let top_up_nonce = generate_nonce();
+    let top_up_signature =
+        generate_issue_signature(&real_utxo.to_string(), 300, top_up_nonce, &alice_key).unwrap();
+    alice
+        .executor_mut()
+        .call_method(
+            contract_id,
+            "issue",
+            &[
+                ("recipient", StrictVal::from(real_utxo.to_string().as_str())),
+                ("amount", StrictVal::from(300u64)),
+                (
+                    "recipientPubKey",
+                    StrictVal::from(alice_pubkey_hex.as_str()),
+                ),
+                ("nonce", StrictVal::from(top_up_nonce)),
+                ("signatureHex", StrictVal::from(top_up_signature.as_str())),
+            ],
+        )
+        .await
+        .expect("Top-up issue should succeed");
+    sent.consignment
+        .validate_against_invoice(&generated, bob.executor())
+        .await
+        .expect("Claimed transfer should pay the invoice despite the top-up");
+
+    // Replaying a claim that already went through doesn't submit it again
+    let mut replay = ClaimManager::with_persistence(&snapshot_path, None).unwrap();
+    let events = replay.process(&chain, &mut bob, &bob_key).await.unwrap();
+    assert_eq!(
+        events,
+        vec![ClaimEvent::AlreadyClaimed {
+            witness_id: accepted.seal_id.unwrap(),
+            real_utxo,
+            amount: 700,
+        }]
+    );
+
+    // Witness reorged out: a freeze of an unclaimed UTXO is rejected without
+    // consuming its nonce, so the same nonce still freezes the real claim
+    let unclaimed = SealKey::from(Outpoint::new(Txid::from_byte_array([0x5Eu8; 32]), 0u32));
+    let freeze_nonce = generate_nonce();
+    for utxo in [unclaimed, real_utxo] {
+        let signature =
+            generate_freeze_claim_signature(&utxo, true, freeze_nonce, &alice_key).unwrap();
+        let utxo_id = utxo.to_string();
+        alice
+            .executor_mut()
+            .call_method(
+                contract_id,
+                "freezeClaim",
+                &[
+                    ("real_utxo", StrictVal::from(utxo_id.as_str())),
+                    ("action", StrictVal::from("freeze")),
+                    ("nonce", StrictVal::from(freeze_nonce)),
+                    ("signatureHex", StrictVal::from(signature.as_str())),
+                ],
+            )
+            .await
+            .expect("Freeze call should execute");
+    }
+    assert!(alice.claim_of(&real_utxo).await.unwrap().unwrap().frozen);
+    assert!(matches!(
+        alice.freeze_claim(&unclaimed, true, &alice_key).await,
+        Err(F1r3flyRgbError::ClaimFailed(_))
+    ));
+
+    // Witness mined again
+    alice
+        .freeze_claim(&real_utxo, false, &alice_key)
+        .await
+        .expect("Unfreeze should succeed");
+    assert_eq!(
+        alice.claim_of(&real_utxo).await.unwrap(),
+        Some(ClaimRecord {
+            witness_id: accepted.seal_id.unwrap(),
+            amount: 700,
+            frozen: false,
+        })
+    );
+
+    // Witness double-spent: the claimed amount goes back to the witness key,
+    // the top-up stays
+    let restored = alice
+        .revert_claim(&real_utxo, &alice_key)
+        .await
+        .expect("Revert should succeed");
+    assert_eq!(restored, 700);
+    assert_eq!(alice.claim_of(&real_utxo).await.unwrap(), None);
+    assert!(matches!(
+        alice.revert_claim(&real_utxo, &alice_key).await,
+        Err(F1r3flyRgbError::ClaimFailed(_))
+    ));
+}
@@ -17,10 +17,14 @@
 
 use amplify::confinement::SmallOrdMap;
 use bp::seals::{Anchor, WTxoSeal};
-use bp::{Tx, Vout};
+use bp::{secp256k1, InternalPk, Outpoint, Tx, Vout};
+use bpstd::ScriptPubkey;
 use commit_verify::{Digest, DigestExt, Sha256};
 use f1r3fly_rgb::{
-    create_tapret_anchor, F1r3flyConsignment, F1r3flyExecutor, F1r3flyRgbContract, StrictVal,
+    create_tapret_anchor, AnchorMethod, CheckKind, CheckStatus, CommitmentType, ConsignmentFormat,
+    ConsignmentVersion, ContractId, ContractMetadata, F1r3flyConsignment, F1r3flyExecutor,
+    F1r3flyRgbContract, F1r3flyStateProof, HistoryEntry, HistoryOperation, Sats, SealKey,
+    StrictVal, Utxo, WitnessMapping, WitnessTx, WitnessTxBuilder,
 };
 use rgb::Pile;
 use std::collections::hash_map::DefaultHasher;
@@ -73,6 +77,42 @@ fn create_dummy_anchor() -> Anchor {
     Anchor::strict_dumb()
 }
 
+/// Seal the offline consignment's witness mapping is derived from
+fn offline_spent_seal() -> Outpoint {
+    Outpoint::new(f1r3fly_rgb::Txid::from([0x0fu8; 32]), 0u32)
+}
+
+/// Build a consignment without touching F1r3node (serialization tests only)
+fn create_offline_consignment() -> F1r3flyConsignment {
+    F1r3flyConsignment {
+        version: ConsignmentVersion::CURRENT.major,
+        minor_version: ConsignmentVersion::CURRENT.minor,
+        contract_id: hypersonic::ContractId::from([7u8; 32]),
+        contract_metadata: ContractMetadata {
+            registry_uri: "rho:id:offline".to_string(),
+            methods: vec!["issue".to_string(), "transfer".to_string()],
+            rholang_source: "new stdout(`rho:io:stdout`) in { stdout!(\"ok\") }".to_string(),
+        },
+        f1r3fly_proof: F1r3flyStateProof {
+            block_hash: "ab".repeat(32),
+            state_hash: [9u8; 32],
+            deploy_id: "cd".repeat(32),
+        },
+        bitcoin_anchor: create_dummy_anchor(),
+        anchor_method: Some(AnchorMethod::OpReturn { output: 1 }),
+        opid: Some(f1r3fly_rgb::Opid::from([5u8; 32])),
+        seals: create_test_seals(2, 9000),
+        witness_txs: vec![Tx::strict_dumb()],
+        is_genesis: false,
+        witness_mapping: Some(WitnessMapping {
+            witness_id: SealKey::witness(offline_spent_seal(), "bcrt1qoffline", 0),
+            recipient_address: "bcrt1qoffline".to_string(),
+            expected_vout: 0,
+        }),
+        history: Vec::new(),
+    }
+}
+
 #[tokio::test]
 async fn test_consignment_happy_path_with_validation() {
     load_env();
@@ -106,8 +146,9 @@ async fn test_consignment_happy_path_with_validation() {
     let opid = issue_result.opid;
 
     // Step 4: Create Tapret anchor with cryptographic proof
-    let (anchor, witness_tx) = create_tapret_anchor(issue_result.state_hash)
-        .expect("Tapret anchor creation should succeed");
+    let (anchor, witness_tx, anchor_method) =
+        create_tapret_anchor(contract.contract_id(), issue_result.state_hash)
+            .expect("Tapret anchor creation should succeed");
     let txid = witness_tx.txid();
     let block_height = std::num::NonZeroU64::new(1).unwrap();
 
@@ -120,7 +161,9 @@ async fn test_consignment_happy_path_with_validation() {
         rgb::WitnessStatus::Mined(block_height),
     );
 
-    contract.tracker_mut().add_anchor(opid, anchor);
+    contract
+        .tracker_mut()
+        .add_anchor_with_method(opid, anchor, anchor_method);
 
     // Step 6: Create consignment with real Bitcoin TX
     let witness_txs = vec![witness_tx.clone()];
@@ -190,6 +233,100 @@ async fn test_consignment_happy_path_with_validation() {
         .expect("Validation should succeed with real Tapret proof and finalized block");
 }
 
+#[tokio::test]
+async fn test_consignment_validate_report() {
+    load_env();
+
+    // Step 1: Deploy contract and anchor an operation
+    let mut executor = F1r3flyExecutor::new().expect("Failed to create F1r3flyExecutor");
+    executor.set_derivation_index(test_derivation_offset("test_consignment_validate_report"));
+
+    let mut contract =
+        F1r3flyRgbContract::issue(executor, "CONS6", "Consignment Test Token 6", 1_000_000, 8)
+            .await
+            .expect("Failed to deploy contract");
+
+    let seals = create_test_seals(1, 6000);
+    let issue_params = &[
+        ("recipient", StrictVal::from("test_recipient_6")),
+        ("amount", StrictVal::from(250_000u64)),
+    ];
+    let issue_result = contract
+        .call_method("issue", issue_params, seals.clone())
+        .await
+        .expect("Issue failed");
+    let opid = issue_result.opid;
+
+    let (anchor, witness_tx, anchor_method) =
+        create_tapret_anchor(contract.contract_id(), issue_result.state_hash)
+            .expect("Tapret anchor creation should succeed");
+    contract.tracker_mut().add_witness(
+        opid,
+        witness_tx.txid(),
+        &witness_tx,
+        &anchor,
+        rgb::WitnessStatus::Mined(std::num::NonZeroU64::new(1).unwrap()),
+    );
+    contract
+        .tracker_mut()
+        .add_anchor_with_method(opid, anchor, anchor_method);
+
+    let consignment =
+        F1r3flyConsignment::new(&contract, issue_result, seals, vec![witness_tx], false)
+            .expect("Failed to create consignment");
+
+    // Step 2: Every check of a genuine consignment passes or is skipped
+    let report = consignment.validate_report(contract.executor()).await;
+    assert!(report.is_valid(), "Report should be valid: {}", report);
+    for kind in [CheckKind::ContractIdentity, CheckKind::StateProof] {
+        assert_eq!(
+            report.check(kind).map(|check| check.status),
+            Some(CheckStatus::Passed),
+            "{} should pass",
+            kind
+        );
+    }
+    assert_eq!(report.commitment_type, Some(CommitmentType::Tapret));
+
+    // Step 3: Contract ID not derived from the registry URI fails identity only
+    let mut renamed = consignment.clone();
+    renamed.contract_metadata.registry_uri = "rho:id:someoneelse".to_string();
+    let report = renamed.validate_report(contract.executor()).await;
+    assert!(!report.is_valid());
+    assert_eq!(
+        report.check(CheckKind::ContractIdentity).unwrap().status,
+        CheckStatus::Failed
+    );
+    assert_eq!(
+        report.check(CheckKind::StateProof).unwrap().status,
+        CheckStatus::Passed
+    );
+
+    // Step 4: State hash not bound to block and deploy fails the state proof,
+    // and the remaining checks still run
+    let mut tampered = consignment.clone();
+    tampered.f1r3fly_proof.state_hash[0] ^= 0xFF;
+    let report = tampered.validate_report(contract.executor()).await;
+    assert!(!report.is_valid());
+    assert_eq!(
+        report.check(CheckKind::StateProof).unwrap().status,
+        CheckStatus::Failed
+    );
+    assert_eq!(
+        report.check(CheckKind::ContractIdentity).unwrap().status,
+        CheckStatus::Passed
+    );
+    assert_eq!(
+        report.check(CheckKind::Commitment).unwrap().status,
+        CheckStatus::Failed,
+        "Witness commits to the original state hash"
+    );
+    assert_eq!(
+        report.check(CheckKind::Finality).unwrap().status,
+        CheckStatus::Passed
+    );
+}
+
 #[tokio::test]
 async fn test_consignment_fails_without_witness_transaction() {
     load_env();
@@ -302,3 +439,462 @@ async fn test_consignment_fails_without_anchor() {
         Err(other) => panic!("Expected InvalidConsignment error, got: {:?}", other),
     }
 }
+
+#[tokio::test]
+async fn test_consignment_history_must_start_with_issue() {
+    load_env();
+
+    // Step 1: Deploy contract
+    let mut executor = F1r3flyExecutor::new().expect("Failed to create F1r3flyExecutor");
+    executor.set_derivation_index(test_derivation_offset(
+        "test_consignment_history_must_start_with_issue",
+    ));
+
+    let mut contract =
+        F1r3flyRgbContract::issue(executor, "CONS4", "Consignment Test Token 4", 1_000_000, 8)
+            .await
+            .expect("Failed to deploy contract");
+
+    // Step 2: Execute an operation and anchor it
+    let seals = create_test_seals(1, 8000);
+
+    let issue_params = &[
+        ("recipient", StrictVal::from("test_recipient_4")),
+        ("amount", StrictVal::from(100_000u64)),
+    ];
+
+    let issue_result = contract
+        .call_method("issue", issue_params, seals.clone())
+        .await
+        .expect("Issue failed");
+    let opid = issue_result.opid;
+
+    let (anchor, witness_tx, anchor_method) =
+        create_tapret_anchor(contract.contract_id(), issue_result.state_hash)
+            .expect("Tapret anchor creation should succeed");
+    contract.tracker_mut().add_witness(
+        opid,
+        witness_tx.txid(),
+        &witness_tx,
+        &anchor,
+        rgb::WitnessStatus::Mined(std::num::NonZeroU64::new(1).unwrap()),
+    );
+    contract.tracker_mut().add_anchor(opid, anchor.clone());
+
+    // Step 3: History whose first entry is a transfer (no issuance root)
+    let history = vec![HistoryEntry::new(
+        HistoryOperation::Transfer,
+        &issue_result,
+        seals.clone(),
+        Some(anchor),
+        Some(witness_tx.clone()),
+    )
+    .expect("History entry creation should succeed")
+    .with_anchor_method(anchor_method)];
+
+    let consignment =
+        F1r3flyConsignment::new(&contract, issue_result, seals, vec![witness_tx], false)
+            .expect("Failed to create consignment")
+            .with_history(history);
+
+    // Step 4: History survives serialization
+    let bytes = consignment.to_bytes().expect("Failed to serialize");
+    let received = F1r3flyConsignment::from_bytes(&bytes).expect("Failed to deserialize");
+    assert_eq!(received.history().len(), 1, "History should round-trip");
+    assert_eq!(received.history()[0].anchor_method, Some(anchor_method));
+
+    // Step 5: Validation must reject the chain
+    match received.validate(contract.executor()).await {
+        Ok(_) => panic!("Validation should fail for history without issuance"),
+        Err(f1r3fly_rgb::F1r3flyRgbError::InvalidConsignment(msg)) => {
+            assert!(msg.contains("issue"), "Unexpected error: {}", msg);
+        }
+        Err(other) => panic!("Expected InvalidConsignment error, got: {:?}", other),
+    }
+}
+
+/// Build a Tapret witness transaction spending `seal` and committing to `state_hash`
+fn build_chain_witness(contract_id: ContractId, seal: Outpoint, state_hash: [u8; 32]) -> WitnessTx {
+    let secp = secp256k1::Secp256k1::new();
+    let secret_key = secp256k1::SecretKey::from_slice(&[7u8; 32]).unwrap();
+    let public_key = secp256k1::PublicKey::from_secret_key(&secp, &secret_key);
+    let change_key = InternalPk::from(public_key.x_only_public_key().0);
+
+    let utxo = Utxo {
+        outpoint: seal,
+        value: Sats::from(50_000u64),
+        script_pubkey: ScriptPubkey::p2tr_key_only(change_key),
+    };
+    let recipient =
+        bitcoin::Address::p2wsh(&bitcoin::ScriptBuf::new(), bitcoin::Network::Regtest).to_string();
+    WitnessTxBuilder::new(&recipient, bitcoin::Network::Regtest, state_hash)
+        .expect("Valid recipient address")
+        .spend_seal(seal)
+        .utxos([utxo])
+        .change_key(change_key)
+        .contract_id(contract_id)
+        .build()
+        .expect("Witness transaction should build")
+}
+
+#[tokio::test]
+async fn test_consignment_valid_history_chain() {
+    load_env();
+
+    // Step 1: Deploy contract
+    let mut executor = F1r3flyExecutor::new().expect("Failed to create F1r3flyExecutor");
+    executor.set_derivation_index(test_derivation_offset(
+        "test_consignment_valid_history_chain",
+    ));
+
+    let mut contract =
+        F1r3flyRgbContract::issue(executor, "CONS5", "Consignment Test Token 5", 1_000_000, 8)
+            .await
+            .expect("Failed to deploy contract");
+    let contract_id = contract.contract_id();
+
+    // Step 2: Issuance, with its seal on output 0 of the issue transaction
+    let issue_seals = create_test_seals(1, 0);
+    let issue_result = contract
+        .call_method(
+            "issue",
+            &[
+                ("recipient", StrictVal::from("test_recipient_5")),
+                ("amount", StrictVal::from(100_000u64)),
+            ],
+            issue_seals.clone(),
+        )
+        .await
+        .expect("Issue failed");
+    let (issue_anchor, issue_tx, _) =
+        create_tapret_anchor(contract.contract_id(), issue_result.state_hash)
+            .expect("Tapret anchor creation should succeed");
+
+    // Step 3: Transfer spending the issued seal, defining a seal on its recipient output
+    let transfer_seals = create_test_seals(1, 1);
+    let transfer_result = contract
+        .call_method(
+            "issue",
+            &[
+                ("recipient", StrictVal::from("test_recipient_5_transfer")),
+                ("amount", StrictVal::from(1_000u64)),
+            ],
+            transfer_seals.clone(),
+        )
+        .await
+        .expect("Transfer operation failed");
+    let transfer_witness = build_chain_witness(
+        contract_id,
+        Outpoint::new(issue_tx.txid(), Vout::from_u32(0)),
+        transfer_result.state_hash,
+    );
+    let transfer_tx: Tx = transfer_witness.psbt.to_unsigned_tx().into();
+
+    // Step 4: Consigned operation spending the transferred seal
+    let seals = create_test_seals(1, 2);
+    let result = contract
+        .call_method(
+            "issue",
+            &[
+                ("recipient", StrictVal::from("test_recipient_5_final")),
+                ("amount", StrictVal::from(500u64)),
+            ],
+            seals.clone(),
+        )
+        .await
+        .expect("Consigned operation failed");
+    let witness = build_chain_witness(
+        contract_id,
+        Outpoint::new(transfer_tx.txid(), Vout::from_u32(1)),
+        result.state_hash,
+    );
+    let witness_tx: Tx = witness.psbt.to_unsigned_tx().into();
+    contract.tracker_mut().add_witness(
+        result.opid,
+        witness_tx.txid(),
+        &witness_tx,
+        &witness.anchor,
+        rgb::WitnessStatus::Mined(std::num::NonZeroU64::new(3).unwrap()),
+    );
+    contract.tracker_mut().add_anchor_with_method(
+        result.opid,
+        witness.anchor,
+        witness.anchor_method,
+    );
+
+    // Step 5: Consignment carrying the chain from issuance
+    let history = vec![
+        HistoryEntry::new(
+            HistoryOperation::Issue,
+            &issue_result,
+            issue_seals,
+            Some(issue_anchor),
+            Some(issue_tx),
+        )
+        .expect("History entry creation should succeed"),
+        HistoryEntry::new(
+            HistoryOperation::Transfer,
+            &transfer_result,
+            transfer_seals,
+            Some(transfer_witness.anchor),
+            Some(transfer_tx),
+        )
+        .expect("History entry creation should succeed")
+        .with_anchor_method(transfer_witness.anchor_method),
+    ];
+    let consignment = F1r3flyConsignment::new(&contract, result, seals, vec![witness_tx], false)
+        .expect("Failed to create consignment")
+        .with_history(history);
+
+    // Step 6: Chain survives serialization and validates end to end
+    let bytes = consignment.to_bytes().expect("Failed to serialize");
+    let received = F1r3flyConsignment::from_bytes(&bytes).expect("Failed to deserialize");
+    assert_eq!(received.history().len(), 2, "History should round-trip");
+    received
+        .validate(contract.executor())
+        .await
+        .expect("Valid history chain should validate");
+
+    // Step 7: A forged intermediate state breaks the chain
+    let mut forged = received.clone();
+    forged.history[1].f1r3fly_proof.state_hash = [0xAB; 32];
+    match forged.validate(contract.executor()).await {
+        Ok(_) => panic!("Validation should fail for a forged intermediate state"),
+        Err(f1r3fly_rgb::F1r3flyRgbError::InvalidConsignment(msg)) => {
+            assert!(
+                msg.contains("History entry 1") && msg.contains("state hash"),
+                "Unexpected error: {}",
+                msg
+            );
+        }
+        Err(other) => panic!("Expected InvalidConsignment error, got: {:?}", other),
+    }
+
+    // Step 8: A claim onto an output the chain never defined is rejected
+    let mut claimed = received.clone();
+    let mut claim = claimed.history[1].clone();
+    claim.operation = HistoryOperation::Claim;
+    claim.bitcoin_anchor = None;
+    claim.anchor_method = None;
+    claim.witness_tx = None;
+    claim.seals = create_test_seals(1, 9);
+    claimed.history.push(claim);
+    match claimed.validate(contract.executor()).await {
+        Ok(_) => panic!("Validation should fail for a claim onto an unknown seal"),
+        Err(f1r3fly_rgb::F1r3flyRgbError::InvalidConsignment(msg)) => {
+            assert!(
+                msg.contains("History entry 2") && msg.contains("claim"),
+                "Unexpected error: {}",
+                msg
+            );
+        }
+        Err(other) => panic!("Expected InvalidConsignment error, got: {:?}", other),
+    }
+}
+
+// ============================================================================
+// Serialization Formats (no F1r3node required)
+// ============================================================================
+
+#[test]
+fn test_consignment_binary_and_armored_round_trip() {
+    let consignment = create_offline_consignment();
+
+    // Binary is the default and carries the magic number
+    let binary = consignment.to_bytes().expect("Binary serialization failed");
+    assert_eq!(&binary[..4], &f1r3fly_rgb::CONSIGNMENT_MAGIC);
+    assert_eq!(
+        ConsignmentFormat::detect(&binary),
+        Some(ConsignmentFormat::Binary)
+    );
+
+    // Encoding is deterministic
+    assert_eq!(binary, consignment.to_bytes().unwrap());
+
+    // Armored and JSON forms are detected and decoded by from_bytes
+    let armored = consignment.to_armored().expect("Armoring failed");
+    assert!(armored.starts_with("-----BEGIN F1R3FLY CONSIGNMENT-----"));
+    let json = consignment.to_json().expect("JSON serialization failed");
+
+    for (format, bytes) in [
+        (ConsignmentFormat::Binary, binary.clone()),
+        (ConsignmentFormat::Armored, armored.clone().into_bytes()),
+        (ConsignmentFormat::Json, json),
+    ] {
+        assert_eq!(ConsignmentFormat::detect(&bytes), Some(format));
+        let decoded = F1r3flyConsignment::from_bytes(&bytes).expect("Decoding failed");
+        assert_eq!(decoded.contract_id, consignment.contract_id);
+        assert_eq!(
+            decoded.contract_metadata.methods,
+            consignment.contract_metadata.methods
+        );
+        assert_eq!(
+            decoded.f1r3fly_proof.state_hash,
+            consignment.f1r3fly_proof.state_hash
+        );
+        assert_eq!(decoded.seals, consignment.seals);
+        assert_eq!(decoded.anchor_method, consignment.anchor_method);
+        assert_eq!(decoded.witness_txs.len(), 1);
+        assert_eq!(
+            decoded.witness_mapping.as_ref().map(|m| m.witness_id),
+            Some(SealKey::witness(offline_spent_seal(), "bcrt1qoffline", 0))
+        );
+        // Canonical bytes are identical regardless of source format
+        assert_eq!(decoded.to_bytes().unwrap(), binary);
+    }
+
+    // Corrupted armor is rejected
+    let tampered = armored.replacen("Checksum-SHA256: ", "Checksum-SHA256: 00", 1);
+    assert!(F1r3flyConsignment::from_armored(&tampered).is_err());
+
+    // Unknown data is rejected
+    assert!(F1r3flyConsignment::from_bytes(b"not a consignment").is_err());
+}
+
+#[test]
+fn test_consignment_version_negotiation() {
+    let consignment = create_offline_consignment();
+    assert_eq!(consignment.format_version(), ConsignmentVersion::CURRENT);
+    assert!(ConsignmentVersion::supported().contains(&ConsignmentVersion::CURRENT));
+    assert_eq!(
+        "1.1".parse::<ConsignmentVersion>().unwrap(),
+        ConsignmentVersion::V1_1
+    );
+
+    // Unsupported major version is rejected (binary)
+    let mut binary = consignment.to_bytes().unwrap();
+    binary[4..6].copy_from_slice(&2u16.to_le_bytes());
+    assert!(matches!(
+        F1r3flyConsignment::from_bytes(&binary),
+        Err(f1r3fly_rgb::F1r3flyRgbError::UnsupportedVersion { .. })
+    ));
+
+    // Newer minor version with appended fields is tolerated (binary)
+    let mut binary = consignment.to_bytes().unwrap();
+    binary[6..8].copy_from_slice(&(ConsignmentVersion::CURRENT.minor + 1).to_le_bytes());
+    binary.extend_from_slice(b"future field");
+    let decoded = F1r3flyConsignment::from_bytes(&binary).expect("Newer minor should decode");
+    assert_eq!(decoded.format_version(), ConsignmentVersion::CURRENT);
+    assert_eq!(decoded.seals, consignment.seals);
+
+    let json: serde_json::Value = serde_json::from_slice(&consignment.to_json().unwrap()).unwrap();
+
+    // Unsupported major version is rejected (JSON)
+    let mut future = json.clone();
+    future["version"] = 2.into();
+    assert!(matches!(
+        F1r3flyConsignment::from_bytes(&serde_json::to_vec(&future).unwrap()),
+        Err(f1r3fly_rgb::F1r3flyRgbError::UnsupportedVersion { .. })
+    ));
+
+    // History entries carry their anchor method
+    let method = AnchorMethod::OpReturn { output: 1 };
+    let with_history = consignment.clone().with_history(vec![HistoryEntry {
+        operation: HistoryOperation::Issue,
+        opid: consignment.operation_id(),
+        f1r3fly_proof: consignment.f1r3fly_proof.clone(),
+        bitcoin_anchor: Some(create_dummy_anchor()),
+        anchor_method: Some(method),
+        witness_tx: None,
+        seals: create_test_seals(1, 0),
+    }]);
+    let binary = with_history.to_bytes().unwrap();
+    let decoded = F1r3flyConsignment::from_bytes(&binary).expect("History should decode");
+    assert_eq!(decoded.history()[0].anchor_method, Some(method));
+
+    // Unknown fields from a newer minor version are tolerated (JSON)
+    let mut newer = json.clone();
+    newer["minor_version"] = 99.into();
+    newer["future_field"] = "ignored".into();
+    let decoded = F1r3flyConsignment::from_bytes(&serde_json::to_vec(&newer).unwrap())
+        .expect("Newer minor should decode");
+    assert_eq!(decoded.contract_id, consignment.contract_id);
+
+    // 1.0 consignments (no minor version, history, anchor method or opid) are
+    // migrated, keeping their version and inferring the anchor method
+    let mut legacy = json;
+    let object = legacy.as_object_mut().unwrap();
+    object.remove("minor_version");
+    object.remove("history");
+    object.remove("anchor_method");
+    object.remove("opid");
+    let mut migrated = legacy.clone();
+    assert_eq!(
+        F1r3flyConsignment::migrate_json(&mut migrated).unwrap(),
+        ConsignmentVersion::V1_0
+    );
+    assert!(migrated["history"].as_array().unwrap().is_empty());
+    let decoded = F1r3flyConsignment::from_bytes(&serde_json::to_vec(&legacy).unwrap())
+        .expect("Legacy consignment should migrate");
+    assert_eq!(decoded.format_version(), ConsignmentVersion::V1_0);
+    assert_eq!(
+        decoded.anchor_method,
+        Some(AnchorMethod::infer_legacy(&consignment.bitcoin_anchor))
+    );
+    assert_eq!(decoded.opid, None);
+    assert_ne!(decoded.operation_id(), consignment.operation_id());
+
+    // A migrated consignment round-trips through binary with its version
+    let redecoded = F1r3flyConsignment::from_bytes(&decoded.to_bytes().unwrap())
+        .expect("Migrated consignment should re-encode");
+    assert_eq!(redecoded.format_version(), ConsignmentVersion::V1_0);
+    assert_eq!(redecoded.anchor_method, decoded.anchor_method);
+    assert_eq!(redecoded.operation_id(), decoded.operation_id());
+}
+
+// ============================================================================
+// Invoice Matching
+// ============================================================================
+
+#[tokio::test]
+async fn test_consignment_invoice_mismatch() {
+    use f1r3fly_rgb::{generate_invoice, F1r3flyRgbError, InvoiceMismatch};
+
+    load_env();
+    let executor = F1r3flyExecutor::new().expect("Failed to create F1r3flyExecutor");
+
+    let consignment = create_offline_consignment();
+    let address = bitcoin::Address::p2wsh(
+        &bitcoin::ScriptBuf::new_op_return(&[1, 2, 3, 4]),
+        bitcoin::Network::Regtest,
+    );
+
+    // Invoice for another contract (rejected before any F1r3node query)
+    let other_contract = hypersonic::ContractId::from([8u8; 32]);
+    let invoice = generate_invoice(
+        other_contract,
+        100,
+        address.clone(),
+        0,
+        rgb::Consensus::Bitcoin,
+        true,
+    )
+    .expect("Invoice generation failed");
+    match consignment
+        .validate_against_invoice(&invoice, &executor)
+        .await
+    {
+        Err(F1r3flyRgbError::InvoiceMismatch(InvoiceMismatch::ContractId { .. })) => {}
+        other => panic!("Expected contract ID mismatch, got: {:?}", other),
+    }
+
+    // Invoice for this contract, but the witness transaction doesn't pay its address
+    let invoice = generate_invoice(
+        consignment.contract_id,
+        100,
+        address,
+        0,
+        rgb::Consensus::Bitcoin,
+        true,
+    )
+    .expect("Invoice generation failed");
+    match consignment
+        .validate_against_invoice(&invoice, &executor)
+        .await
+    {
+        Err(F1r3flyRgbError::InvoiceMismatch(
+            InvoiceMismatch::MissingOutput { .. } | InvoiceMismatch::Beneficiary { .. },
+        )) => {}
+        other => panic!("Expected beneficiary mismatch, got: {:?}", other),
+    }
+}
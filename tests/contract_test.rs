@@ -114,6 +114,25 @@ fn create_query_seal() -> TxoSeal {
     }
 }
 
+/// Taproot address paying `key` as output key, with `key` negated if needed
+/// so it signs for the invoice's recipient key (see `get_recipient_pubkey`)
+fn taproot_recipient(key: secp256k1::SecretKey) -> (secp256k1::SecretKey, bitcoin::Address) {
+    let secp = secp256k1::Secp256k1::new();
+    let (x_only, parity) = secp256k1::PublicKey::from_secret_key(&secp, &key).x_only_public_key();
+    let key = match parity {
+        secp256k1::Parity::Even => key,
+        secp256k1::Parity::Odd => key.negate(),
+    };
+    let mut script = vec![0x51, 0x20];
+    script.extend_from_slice(&x_only.serialize());
+    let address = bitcoin::Address::from_script(
+        bitcoin::Script::from_bytes(&script),
+        bitcoin::Network::Regtest,
+    )
+    .expect("Valid taproot script");
+    (key, address)
+}
+
 /// Create a matching pair of WTxoSeal and TxoSeal for testing
 ///
 /// Returns (witnessed_seal, query_seal) with the same underlying outpoint
@@ -166,16 +185,20 @@ async fn test_contract_lifecycle_with_seal_tracking() {
 
     // Verify metadata
     let methods = &contract.metadata().methods;
-    assert_eq!(methods.len(), 6, "Contract should have exactly 6 methods");
+    assert_eq!(methods.len(), 10, "Contract should have exactly 10 methods");
 
     // Verify exact method set (order-independent)
     let mut expected_methods = vec![
         "getMetadata".to_string(),
         "issue".to_string(),
         "transfer".to_string(),
+        "transferWithChange".to_string(),
         "balanceOf".to_string(),
         "claim".to_string(),
         "ownerOf".to_string(),
+        "freezeClaim".to_string(),
+        "revertClaim".to_string(),
+        "claimOf".to_string(),
     ];
     let mut actual_methods = methods.clone();
     expected_methods.sort();
@@ -1209,6 +1232,150 @@ async fn test_transfer_rejects_unauthorized_sender() {
     );
 }
 
+/// Test: transferWithChange can't name another holder's UTXO as change
+///
+/// Verifies:
+/// - A change UTXO owned by someone else is rejected, and no balance or
+///   ownership moves
+/// - A fresh change UTXO is accepted and registered to the sender
+#[tokio::test]
+async fn test_transfer_with_change_rejects_foreign_change() {
+    load_env();
+
+    let test_name = "transfer_with_change_rejects_foreign_change";
+    let offset = test_derivation_offset(test_name);
+
+    // Step 1: Alice deploys contract and issues to herself
+    let mut executor = F1r3flyExecutor::new().expect("Failed to create executor");
+    executor.set_derivation_index(offset);
+    executor.set_auto_derive(false);
+
+    let mut contract = F1r3flyRgbContract::issue(executor, "CHNG", "Change Owner Test", 10_000, 0)
+        .await
+        .expect("Failed to issue asset");
+
+    let contract_id = contract.contract_id();
+
+    let alice_key = contract
+        .executor()
+        .get_child_key()
+        .expect("Failed to get Alice's key");
+    let secp = secp256k1::Secp256k1::new();
+    let alice_public_key = secp256k1::PublicKey::from_secret_key(&secp, &alice_key);
+    let alice_pubkey_hex = hex::encode(alice_public_key.serialize_uncompressed());
+
+    let victim_key = secp256k1::SecretKey::from_slice(&[0x77u8; 32]).unwrap();
+    let victim_pubkey_hex = hex::encode(
+        secp256k1::PublicKey::from_secret_key(&secp, &victim_key).serialize_uncompressed(),
+    );
+
+    let alice_seal = create_query_seal();
+    let alice_seal_str = F1r3flyRgbContract::serialize_seal(&alice_seal);
+    let (_, victim_seal) = create_matching_seal_pair(7000, 1);
+    let victim_seal_str = F1r3flyRgbContract::serialize_seal(&victim_seal);
+    let (_, bob_seal) = create_matching_seal_pair(7001, 2);
+    let bob_seal_str = F1r3flyRgbContract::serialize_seal(&bob_seal);
+    let (_, change_seal) = create_matching_seal_pair(7002, 3);
+    let change_seal_str = F1r3flyRgbContract::serialize_seal(&change_seal);
+
+    // Step 2: Issue to Alice and to the victim
+    for (seal_str, amount, pubkey_hex) in [
+        (&alice_seal_str, 5000u64, &alice_pubkey_hex),
+        (&victim_seal_str, 2000u64, &victim_pubkey_hex),
+    ] {
+        let nonce = generate_nonce();
+        let signature = generate_issue_signature(seal_str, amount, nonce, &alice_key)
+            .expect("Failed to generate issue signature");
+        contract
+            .executor_mut()
+            .call_method(
+                contract_id,
+                "issue",
+                &[
+                    ("recipient", StrictVal::from(seal_str.as_str())),
+                    ("amount", StrictVal::from(amount)),
+                    ("recipientPubKey", StrictVal::from(pubkey_hex.as_str())),
+                    ("nonce", StrictVal::from(nonce)),
+                    ("signatureHex", StrictVal::from(signature.as_str())),
+                ],
+            )
+            .await
+            .expect("Issue should succeed");
+    }
+
+    // Step 3: Alice sends 1000 to Bob, naming the victim's seal as change
+    let sign = |change: &str| {
+        let nonce = generate_nonce();
+        let signature = f1r3fly_rgb::generate_transfer_with_change_signature(
+            &alice_seal_str,
+            &bob_seal_str,
+            1000,
+            change,
+            nonce,
+            &alice_key,
+        )
+        .expect("Failed to generate signature");
+        (nonce, signature)
+    };
+    let (nonce, signature) = sign(&victim_seal_str);
+    contract
+        .executor_mut()
+        .call_method(
+            contract_id,
+            "transferWithChange",
+            &[
+                ("from", StrictVal::from(alice_seal_str.as_str())),
+                ("to", StrictVal::from(bob_seal_str.as_str())),
+                ("amount", StrictVal::from(1000u64)),
+                ("toPubKey", StrictVal::from(alice_pubkey_hex.as_str())),
+                ("change", StrictVal::from(victim_seal_str.as_str())),
+                ("nonce", StrictVal::from(nonce)),
+                ("fromSignatureHex", StrictVal::from(signature.as_str())),
+            ],
+        )
+        .await
+        .expect("Deploy should succeed (but business logic should reject)");
+
+    // Step 4: Nothing moved, the victim keeps balance and ownership
+    assert_eq!(contract.balance(&alice_seal).await.unwrap(), 5000);
+    assert_eq!(contract.balance(&bob_seal).await.unwrap(), 0);
+    assert_eq!(contract.balance(&victim_seal).await.unwrap(), 2000);
+    assert_eq!(
+        contract.owner_of(&victim_seal).await.unwrap(),
+        Some(victim_pubkey_hex.clone()),
+        "Victim should still own their UTXO"
+    );
+
+    // Step 5: A fresh change seal is accepted and owned by Alice
+    let (nonce, signature) = sign(&change_seal_str);
+    contract
+        .executor_mut()
+        .call_method(
+            contract_id,
+            "transferWithChange",
+            &[
+                ("from", StrictVal::from(alice_seal_str.as_str())),
+                ("to", StrictVal::from(bob_seal_str.as_str())),
+                ("amount", StrictVal::from(1000u64)),
+                ("toPubKey", StrictVal::from(alice_pubkey_hex.as_str())),
+                ("change", StrictVal::from(change_seal_str.as_str())),
+                ("nonce", StrictVal::from(nonce)),
+                ("fromSignatureHex", StrictVal::from(signature.as_str())),
+            ],
+        )
+        .await
+        .expect("Transfer with change should succeed");
+
+    assert_eq!(contract.balance(&alice_seal).await.unwrap(), 0);
+    assert_eq!(contract.balance(&bob_seal).await.unwrap(), 1000);
+    assert_eq!(contract.balance(&change_seal).await.unwrap(), 4000);
+    assert_eq!(contract.balance(&victim_seal).await.unwrap(), 2000);
+    assert_eq!(
+        contract.owner_of(&change_seal).await.unwrap(),
+        Some(alice_pubkey_hex)
+    );
+}
+
 #[tokio::test]
 async fn test_transfer_rejects_reused_nonce() {
     load_env();
@@ -2161,3 +2328,190 @@ async fn test_owner_of_query() {
         "Second UTXO should have no owner (never received tokens)"
     );
 }
+
+// ============================================================================
+// Test: High-Level Send
+// ============================================================================
+
+/// Test: send() runs the full transfer flow and returns a deliverable consignment
+///
+/// Verifies:
+/// - Invoices the seal can't pay are rejected before anything executes
+/// - Balance moves from the source seal to the recipient's witness identifier
+/// - Witness, anchor and fee-bump builder are recorded in the tracker
+/// - The consignment matches the invoice and its unsigned PSBT
+#[tokio::test]
+async fn test_send_produces_consignment() {
+    use bp::{InternalPk, Sats};
+    use bpstd::ScriptPubkey;
+    use f1r3fly_rgb::{generate_invoice, parse_invoice, F1r3flyRgbError, Utxo, WitnessTxBuilder};
+
+    load_env();
+
+    let test_name = "send_produces_consignment";
+    let offset = test_derivation_offset(test_name);
+
+    // Step 1: Deploy contract and issue to Alice
+    let mut executor = F1r3flyExecutor::new().expect("Failed to create executor");
+    executor.set_derivation_index(offset);
+    executor.set_auto_derive(false);
+
+    let mut contract = F1r3flyRgbContract::issue(executor, "SEND", "Send Test Token", 10_000, 0)
+        .await
+        .expect("Failed to issue asset");
+    let contract_id = contract.contract_id();
+
+    let alice_key = contract
+        .executor()
+        .get_child_key()
+        .expect("Failed to get Alice's key");
+    let secp = secp256k1::Secp256k1::new();
+    let alice_public_key = secp256k1::PublicKey::from_secret_key(&secp, &alice_key);
+    let alice_pubkey_hex = hex::encode(alice_public_key.serialize_uncompressed());
+
+    let alice_seal = create_query_seal();
+    let alice_seal_str = F1r3flyRgbContract::serialize_seal(&alice_seal);
+
+    let issue_nonce = generate_nonce();
+    let issue_signature = generate_issue_signature(&alice_seal_str, 5000, issue_nonce, &alice_key)
+        .expect("Failed to generate issue signature");
+    contract
+        .executor_mut()
+        .call_method(
+            contract_id,
+            "issue",
+            &[
+                ("recipient", StrictVal::from(alice_seal_str.as_str())),
+                ("amount", StrictVal::from(5000u64)),
+                (
+                    "recipientPubKey",
+                    StrictVal::from(alice_pubkey_hex.as_str()),
+                ),
+                ("nonce", StrictVal::from(issue_nonce)),
+                ("signatureHex", StrictVal::from(issue_signature.as_str())),
+            ],
+        )
+        .await
+        .expect("Issue should succeed");
+
+    // Step 2: Bob's invoice and Alice's witness transaction builder
+    let (_bob_key, bob_address) =
+        taproot_recipient(secp256k1::SecretKey::from_slice(&[7u8; 32]).unwrap());
+
+    let change_key = InternalPk::from(alice_public_key.x_only_public_key().0);
+    let builder = WitnessTxBuilder::new(
+        &bob_address.to_string(),
+        bitcoin::Network::Regtest,
+        [0u8; 32],
+    )
+    .unwrap()
+    .utxos([Utxo {
+        outpoint: alice_seal.primary,
+        value: Sats::from(20_000u64),
+        script_pubkey: ScriptPubkey::p2tr_key_only(change_key),
+    }])
+    .fee_rate(2)
+    .change_key(change_key);
+
+    // Step 3: Invoice above the seal's balance is rejected up front
+    let too_much = generate_invoice(
+        contract_id,
+        9000,
+        bob_address.clone(),
+        0,
+        rgb::Consensus::Bitcoin,
+        true,
+    )
+    .expect("Invoice generation failed");
+    let too_much = parse_invoice(&too_much.invoice.to_string()).expect("Invoice parsing failed");
+    match contract
+        .send(&too_much, &alice_seal, &alice_key, builder.clone())
+        .await
+    {
+        Err(F1r3flyRgbError::SendFailed(_)) => {}
+        other => panic!(
+            "Expected SendFailed, got: {:?}",
+            other.map(|sent| sent.opid)
+        ),
+    }
+    assert_eq!(contract.tracker().operation_count(), 0);
+    assert_eq!(contract.balance(&alice_seal).await.unwrap(), 5000);
+
+    // Step 4: Send 1200 to Bob
+    let generated = generate_invoice(
+        contract_id,
+        1200,
+        bob_address.clone(),
+        0,
+        rgb::Consensus::Bitcoin,
+        true,
+    )
+    .expect("Invoice generation failed");
+    let invoice = parse_invoice(&generated.invoice.to_string()).expect("Invoice parsing failed");
+
+    let sent = contract
+        .send(&invoice, &alice_seal, &alice_key, builder)
+        .await
+        .expect("Send should succeed");
+
+    // The spent seal is emptied; the remainder waits on the change output
+    assert_eq!(contract.balance(&alice_seal).await.unwrap(), 0);
+    let change = sent
+        .change_mapping
+        .as_ref()
+        .expect("Remainder needs change");
+    assert_eq!(change.expected_vout, 0);
+    let change_balance = contract
+        .executor()
+        .query_state(
+            contract_id,
+            "balanceOf",
+            &[("address", StrictVal::from(change.witness_id.as_str()))],
+        )
+        .await
+        .expect("Failed to query change balance");
+    assert_eq!(
+        change_balance
+            .as_u64()
+            .or_else(|| change_balance.as_i64().map(|i| i as u64)),
+        Some(3800)
+    );
+
+    // Step 5: Tracker records the tentative witness with its builder
+    let tracker = contract.tracker();
+    assert_eq!(
+        tracker.witness_status(sent.txid),
+        rgb::WitnessStatus::Tentative
+    );
+    assert!(tracker.has_anchor(&sent.opid));
+    assert_eq!(
+        tracker.pending_witnesses(),
+        vec![(sent.txid, vec![sent.opid])]
+    );
+
+    // Step 6: Consignment spends the seal and pays the invoice
+    let consignment = &sent.consignment;
+    assert_eq!(consignment.operation_id(), sent.opid);
+    assert!(!consignment.is_genesis);
+    assert_eq!(consignment.witness_txs.len(), 1);
+    assert_eq!(consignment.witness_txs[0].txid(), sent.txid);
+    assert_eq!(
+        consignment.witness_txs[0].inputs[0].prev_output,
+        alice_seal.primary
+    );
+    assert_eq!(
+        consignment.seal_outpoints(),
+        vec![
+            bp::Outpoint::new(sent.txid, 1u32),
+            bp::Outpoint::new(sent.txid, 0u32)
+        ]
+    );
+
+    let psbt_tx: bp::Tx = sent.psbt.to_unsigned_tx().into();
+    assert_eq!(psbt_tx.txid(), sent.txid);
+
+    consignment
+        .validate_against_invoice(&generated, contract.executor())
+        .await
+        .expect("Consignment should match the invoice");
+}
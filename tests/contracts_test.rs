@@ -422,3 +422,117 @@ async fn test_contracts_concurrent_operations_state_isolation() {
         "Contract 3 should still exist"
     );
 }
+
+// ============================================================================
+// Test 6: Accept Consignment Into Recipient Collection
+// ============================================================================
+
+#[tokio::test]
+async fn test_contracts_accept_consignment() {
+    use amplify::confinement::SmallOrdMap;
+    use amplify::ByteArray;
+    use bp::seals::{Noise, TxoSealExt, WOutpoint, WTxoSeal};
+    use bp::{Outpoint, Txid};
+    use f1r3fly_rgb::{create_tapret_anchor, F1r3flyConsignment, SealKey, StrictVal};
+    use strict_types::StrictDumb;
+
+    load_env();
+
+    // Sender issues and anchors an operation
+    let mut executor = F1r3flyExecutor::new().expect("Failed to create executor");
+    executor.set_derivation_index(test_derivation_offset("test_contracts_accept_consignment"));
+    let mut sender = F1r3flyRgbContract::issue(executor, "ACPT", "Accept Test", 1_000_000, 8)
+        .await
+        .expect("Failed to issue");
+
+    // Seal on an existing UTXO, which the issued amount is assigned to
+    let seal_outpoint = Outpoint::new(Txid::from_byte_array([0xAC; 32]), 0u32);
+    let seal_key = SealKey::from(seal_outpoint);
+    let mut seals = SmallOrdMap::new();
+    seals
+        .insert(
+            0u16,
+            WTxoSeal {
+                primary: WOutpoint::Extern(seal_outpoint),
+                secondary: TxoSealExt::Noise(Noise::strict_dumb()),
+            },
+        )
+        .unwrap();
+    let result = sender
+        .call_method(
+            "issue",
+            &[
+                ("recipient", StrictVal::from(seal_key.to_string().as_str())),
+                ("amount", StrictVal::from(1_000u64)),
+            ],
+            seals.clone(),
+        )
+        .await
+        .expect("Issue failed");
+    let opid = result.opid;
+    let (anchor, witness_tx, method) =
+        create_tapret_anchor(sender.contract_id(), result.state_hash)
+            .expect("Tapret anchor creation failed");
+    let txid = witness_tx.txid();
+    sender.tracker_mut().add_witness(
+        opid,
+        txid,
+        &witness_tx,
+        &anchor,
+        rgb::WitnessStatus::Mined(std::num::NonZeroU64::new(1).unwrap()),
+    );
+    sender
+        .tracker_mut()
+        .add_anchor_with_method(opid, anchor, method);
+    let bytes = F1r3flyConsignment::new(&sender, result, seals, vec![witness_tx], false)
+        .expect("Failed to create consignment")
+        .to_bytes()
+        .unwrap();
+
+    // Recipient accepts it without knowing the contract
+    let executor = F1r3flyExecutor::new().expect("Failed to create executor");
+    let mut recipient = F1r3flyRgbContracts::new(executor);
+    let accepted = recipient
+        .accept_consignment(&bytes)
+        .await
+        .expect("Accept failed");
+
+    assert_eq!(accepted.contract_id, sender.contract_id());
+    assert_eq!(accepted.opid, opid);
+    assert!(accepted.new_contract);
+    assert_eq!(accepted.witness_id, Some(txid));
+    assert_eq!(accepted.seals, vec![seal_outpoint]);
+    // No witness mapping: no recipient seal to read a balance from, even
+    // though the declared seal holds one
+    assert_eq!(accepted.seal_id, None);
+    assert_eq!(accepted.amount, 0);
+    assert!(recipient.contains(&accepted.contract_id));
+    assert!(recipient
+        .executor()
+        .get_contract_metadata(accepted.contract_id)
+        .is_some());
+
+    let tracker = recipient.get(&accepted.contract_id).unwrap().tracker();
+    assert_eq!(tracker.seals(opid, 0).len(), 1);
+    assert!(tracker.has_witness(txid));
+    assert_eq!(tracker.witness_status(txid), rgb::WitnessStatus::Tentative);
+    assert!(tracker.has_anchor(&opid));
+    assert_eq!(tracker.get_anchor_method(&opid), Some(method));
+
+    // Accepting again changes nothing
+    let again = recipient
+        .accept_consignment(&bytes)
+        .await
+        .expect("Second accept failed");
+    assert!(!again.new_contract);
+    assert_eq!(again.amount, 0);
+    assert_eq!(recipient.count(), 1);
+    assert_eq!(
+        recipient
+            .get(&accepted.contract_id)
+            .unwrap()
+            .tracker()
+            .operation_count(),
+        1
+    );
+}
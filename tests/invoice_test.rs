@@ -11,7 +11,8 @@ use amplify::ByteArray;
 use bitcoin::{Address, Network, PublicKey};
 use commit_verify::{Digest, Sha256};
 use f1r3fly_rgb::{
-    extract_seal, generate_invoice, get_recipient_address, parse_invoice, RgbBeneficiary,
+    extract_seal, generate_invoice, get_recipient_address, get_recipient_pubkey, parse_invoice,
+    RgbBeneficiary,
 };
 use hypersonic::ContractId;
 use rgb::{AuthToken, Consensus};
@@ -335,3 +336,38 @@ fn test_invoice_error_cases() {
         assert!(result.is_err(), "Malformed invoice should return error");
     }
 }
+
+/// Test 6: Recipient public key is the output key of a taproot invoice address
+#[test]
+fn test_get_recipient_pubkey_extraction() {
+    let contract_id = test_contract_id("test_contract_pubkey");
+    let invoice_for = |address: Address| {
+        let generated = generate_invoice(contract_id, 1000, address, 0, Consensus::Bitcoin, true)
+            .expect("Invoice generation should succeed");
+        parse_invoice(&generated.invoice.to_string()).expect("Invoice parsing should succeed")
+    };
+
+    // P2TR: even-Y output key
+    let address = test_address_p2tr();
+    let pubkey = get_recipient_pubkey(&invoice_for(address.clone()).beneficiary)
+        .expect("P2TR invoice should carry the recipient key");
+    let (x_only, parity) = pubkey.x_only_public_key();
+    assert_eq!(
+        &x_only.serialize()[..],
+        &address.script_pubkey().as_bytes()[2..],
+        "Public key should be the taproot output key"
+    );
+    assert_eq!(parity, secp256k1::Parity::Even);
+
+    // Other address types don't carry a key
+    for address in [test_address_p2wpkh(), test_address_p2wsh()] {
+        assert!(
+            get_recipient_pubkey(&invoice_for(address).beneficiary).is_err(),
+            "Non-taproot invoice should return error"
+        );
+    }
+
+    // AuthToken not supported
+    let token_beneficiary = RgbBeneficiary::Token(AuthToken::strict_dumb());
+    assert!(get_recipient_pubkey(&token_beneficiary).is_err());
+}